@@ -53,6 +53,42 @@ async fn main() -> Result<()> {
         assert_eq!(file_config.max_capacity, config.max_capacity)
     }
 
+    if command_line_args.max_registers_per_owner.is_some() {
+        assert_eq!(
+            command_line_args.max_registers_per_owner,
+            config.max_registers_per_owner
+        )
+    } else {
+        assert_eq!(
+            file_config.max_registers_per_owner,
+            config.max_registers_per_owner
+        )
+    }
+
+    if command_line_args.max_register_ops_per_owner.is_some() {
+        assert_eq!(
+            command_line_args.max_register_ops_per_owner,
+            config.max_register_ops_per_owner
+        )
+    } else {
+        assert_eq!(
+            file_config.max_register_ops_per_owner,
+            config.max_register_ops_per_owner
+        )
+    }
+
+    if command_line_args.max_register_bytes_per_owner.is_some() {
+        assert_eq!(
+            command_line_args.max_register_bytes_per_owner,
+            config.max_register_bytes_per_owner
+        )
+    } else {
+        assert_eq!(
+            file_config.max_register_bytes_per_owner,
+            config.max_register_bytes_per_owner
+        )
+    }
+
     if command_line_args.root_dir.is_some() {
         assert_eq!(command_line_args.root_dir, config.root_dir)
     } else {
@@ -7,25 +7,57 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use super::{
-    data::{encrypt_blob, to_chunk, Blob, Spot},
-    Client,
+    data::{encrypt_blob, pack, to_chunk, Blob, FileEncryptor, Spot},
+    upload_journal::UploadJournal,
+    Client, TransferProgress,
 };
 use crate::{
-    client::{client_api::data::DataMapLevel, utils::encryption, Error, Result},
-    messaging::data::{DataCmd, DataQuery, QueryResponse},
-    types::{BytesAddress, Chunk, ChunkAddress, Encryption},
+    client::{
+        client_api::data::DataMapLevel,
+        connections::NUM_OF_ELDERS_SUBSET_FOR_QUERIES,
+        utils::{keypair_encryption, retry},
+        Error, Result,
+    },
+    messaging::data::{DataCmd, DataQuery, QueryResponse, StoreReceipt},
+    types::{BytesAddress, Chunk, ChunkAddress, DataAddress, Encryption, Token},
     url::Scope,
 };
 
 use bincode::deserialize;
 use bytes::Bytes;
-use futures::future::join_all;
+use futures::{
+    future,
+    stream::{self, BoxStream, FuturesUnordered},
+    Stream, StreamExt, TryStreamExt,
+};
 use itertools::Itertools;
+use rayon::prelude::*;
 use self_encryption::{self, ChunkInfo, DataMap, EncryptedChunk};
-use tokio::task;
+use std::{collections::BTreeSet, path::Path, sync::Arc, time::Duration};
+use tempfile::NamedTempFile;
+use tokio::{
+    fs::File,
+    io::{AsyncRead, AsyncWrite, AsyncWriteExt},
+    task,
+};
 use tracing::trace;
 use xor_name::XorName;
 
+/// Number of chunks encrypted and held in memory at a time when uploading from a file.
+const UPLOAD_BATCH_SIZE: usize = 8;
+
+/// Maximum number of chunk addresses sent in a single query for their existence.
+const EXISTENCE_QUERY_BATCH_SIZE: usize = 256;
+
+/// Maximum number of sections queried at a time for the cost of storing data there.
+const STORE_COST_QUERY_CONCURRENCY: usize = 8;
+
+/// Number of chunks recorded as stored in an upload journal between writes of the journal to disk.
+const JOURNAL_FLUSH_INTERVAL: usize = 32;
+
+/// Interval before the first retry of a failed chunk read, which then grows exponentially.
+const CHUNK_RETRY_INTERVAL: Duration = Duration::from_secs(1);
+
 struct HeadChunk {
     chunk: Chunk,
     address: BytesAddress,
@@ -45,7 +77,12 @@ impl Client {
         {
             self.read_all(data_map).await
         } else {
-            self.get_bytes(chunk, address.scope())
+            let bytes = self.get_bytes(chunk, address.scope())?;
+            self.report_progress(TransferProgress::BytesDecrypted {
+                decrypted: bytes.len(),
+                total: bytes.len(),
+            });
+            Ok(bytes)
         }
     }
 
@@ -83,11 +120,84 @@ impl Client {
         self.seek(data_map, position, length).await
     }
 
+    /// Reads the bytes at `address` from the network and writes them to `writer`.
+    ///
+    /// Chunks are fetched in order, a bounded number at a time, and are decrypted and written
+    /// out as they arrive, so the whole content is never held in memory.
+    #[instrument(skip(self, writer), level = "debug")]
+    pub async fn download_to(
+        &self,
+        address: BytesAddress,
+        mut writer: impl AsyncWrite + Unpin,
+    ) -> Result<()> {
+        let mut stream = self.download_stream(address).await?;
+        while let Some(bytes) = stream.next().await {
+            writer.write_all(&bytes?).await?;
+        }
+        writer.flush().await?;
+        Ok(())
+    }
+
+    /// Reads the bytes at `address` from the network as a stream of consecutive pieces of the content.
+    ///
+    /// Each item is the decrypted contents of one chunk. Chunks are fetched in order,
+    /// with at most a bounded number of them being fetched ahead of the one yielded.
+    #[instrument(skip(self), level = "debug")]
+    pub async fn download_stream(
+        &self,
+        address: BytesAddress,
+    ) -> Result<BoxStream<'static, Result<Bytes>>> {
+        let chunk = self.get_chunk(address.name()).await?;
+        if let Ok(data_map) = self
+            .unpack_head_chunk(HeadChunk {
+                chunk: chunk.clone(),
+                address,
+            })
+            .await
+        {
+            Ok(self.stream_all(data_map).boxed())
+        } else {
+            let bytes = self.get_bytes(chunk, address.scope())?;
+            Ok(stream::once(future::ready(Ok(bytes))).boxed())
+        }
+    }
+
+    /// Gets a chunk from the client's chunk cache if enabled and holding the chunk,
+    /// or else from the network.
+    ///
+    /// A chunk received from the network is verified to match the requested name. If it does not,
+    /// the chunk is requested once more from other Elders, if there are enough of them,
+    /// before giving up with [`Error::ChunkIntegrity`].
     #[instrument(skip(self), level = "trace")]
     pub(crate) async fn get_chunk(&self, name: &XorName) -> Result<Chunk> {
-        let res = self
-            .send_query(DataQuery::GetChunk(ChunkAddress(*name)))
-            .await?;
+        let cache = if let Some(cache) = &self.chunk_cache {
+            match cache.get(name) {
+                Ok(Some(chunk)) => return Ok(chunk),
+                Ok(None) => Some(cache),
+                Err(error) => {
+                    warn!("Failed to read chunk {} from the cache: {:?}", name, error);
+                    Some(cache)
+                }
+            }
+        } else {
+            None
+        };
+
+        let query = DataQuery::GetChunk(ChunkAddress(*name));
+        // the session only accepts a chunk whose content hashes to the name it was queried by
+        let res = match self.send_query(query.clone()).await {
+            Err(error @ Error::ChunkIntegrity { .. }) => {
+                warn!("{}, asking other Elders", error);
+                match self
+                    .send_query_skipping_elders(query, NUM_OF_ELDERS_SUBSET_FOR_QUERIES)
+                    .await
+                {
+                    Err(Error::InsufficientElderConnections(..)) => return Err(error),
+                    res => res?,
+                }
+            }
+            res => res?,
+        };
 
         let operation_id = res.operation_id;
         let chunk: Chunk = match res.response {
@@ -97,9 +207,164 @@ impl Client {
             _ => return Err(Error::ReceivedUnexpectedEvent),
         }?;
 
+        if let Some(cache) = cache {
+            if let Err(error) = cache.insert(&chunk) {
+                warn!("Failed to add chunk {} to the cache: {:?}", name, error);
+            }
+        }
+
         Ok(chunk)
     }
 
+    /// Returns a client which, when uploading, leaves out the chunks [`Client::existing_chunks`]
+    /// reports as already stored on the network.
+    ///
+    /// The answer to an existence query comes from a single Elder and is not signed by its section,
+    /// so a faulty Elder can have chunks left out which are not actually stored.
+    /// Without deduplication, which is the default, every chunk is sent.
+    pub fn with_upload_deduplication(&self) -> Self {
+        let mut client = self.clone();
+        client.deduplicate_uploads = true;
+        client
+    }
+
+    /// Returns which of the chunks at the given addresses are already stored on the network.
+    ///
+    /// The addresses are sent to the Elders of the sections the chunks belong to, which ask
+    /// their Adults whether they hold them, so no chunk contents are transferred.
+    #[instrument(skip_all, level = "debug")]
+    pub async fn existing_chunks(
+        &self,
+        addresses: Vec<ChunkAddress>,
+    ) -> Result<BTreeSet<ChunkAddress>> {
+        let batches = addresses
+            .into_iter()
+            .into_group_map_by(|address| self.session.section_prefix(address.name()))
+            .into_values()
+            .flat_map(|addresses| {
+                addresses
+                    .chunks(EXISTENCE_QUERY_BATCH_SIZE)
+                    .map(<[ChunkAddress]>::to_vec)
+                    .collect_vec()
+            });
+
+        let queries = batches.map(|addresses| async move {
+            let res = self
+                .send_query(DataQuery::ChunkExists(addresses.clone()))
+                .await?;
+            let operation_id = res.operation_id;
+            let existing = match res.response {
+                QueryResponse::ChunkExists((result, _)) => {
+                    result.map_err(|err| Error::from((err, operation_id)))
+                }
+                _ => return Err(Error::ReceivedUnexpectedEvent),
+            }?;
+
+            // only the chunks we asked about are taken into account
+            Ok(addresses
+                .into_iter()
+                .filter(|address| existing.contains(address))
+                .collect_vec())
+        });
+
+        let existing = future::try_join_all(queries).await?;
+        Ok(existing.into_iter().flatten().collect())
+    }
+
+    /// Returns what storing the bytes as public data would cost, see
+    /// [`Client::store_cost_for_scoped`].
+    #[instrument(skip_all, level = "debug")]
+    pub async fn store_cost_for(&self, bytes: Bytes) -> Result<Token> {
+        self.store_cost_for_scoped(bytes, Scope::Public).await
+    }
+
+    /// Returns what storing the bytes with the given scope would cost, the sum of the prices
+    /// quoted by the sections the chunks [`Client::chunk_bytes`] produces out of them would be
+    /// stored at.
+    ///
+    /// Prices follow the [`store_cost`] curve, rising as the sections fill up, so quotes are
+    /// only indicative of the cost at the time of the query. As they are proportional to the bytes
+    /// stored, each section is asked a single quote for all the chunks it would hold.
+    ///
+    /// [`store_cost`]: crate::messaging::data::store_cost
+    #[instrument(skip_all, level = "debug")]
+    pub async fn store_cost_for_scoped(&self, bytes: Bytes, scope: Scope) -> Result<Token> {
+        let sections = self
+            .chunk_sizes(bytes, scope)
+            .await?
+            .into_iter()
+            .into_group_map_by(|(name, _)| self.session.section_prefix(name))
+            .into_values();
+
+        stream::iter(sections)
+            .map(|chunks| async move {
+                let bytes = chunks.iter().map(|(_, size)| *size as u64).sum();
+                // the quote is asked of the section the first of its chunks is stored at
+                let name = chunks[0].0;
+                let address = match scope {
+                    Scope::Public => BytesAddress::Public(name),
+                    Scope::Private => BytesAddress::Private(name),
+                };
+                let query = DataQuery::GetStoreCost {
+                    bytes,
+                    address: DataAddress::Bytes(address),
+                };
+                let res = self.send_query(query).await?;
+                let operation_id = res.operation_id;
+                match res.response {
+                    QueryResponse::GetStoreCost((result, _)) => {
+                        result.map_err(|err| Error::from((err, operation_id)))
+                    }
+                    _ => Err(Error::ReceivedUnexpectedEvent),
+                }
+            })
+            .buffer_unordered(STORE_COST_QUERY_CONCURRENCY)
+            .try_fold(Token::zero(), |total, quote| async move {
+                total
+                    .checked_add(quote)
+                    .ok_or_else(|| Error::from(crate::types::Error::ExcessiveValue))
+            })
+            .await
+    }
+
+    // Names and sizes of the chunks the bytes are stored as, the same as those of
+    // `Client::chunk_bytes`. The chunks are encrypted one at a time to learn them,
+    // so that they are never all held in memory.
+    async fn chunk_sizes(&self, bytes: Bytes, scope: Scope) -> Result<Vec<(XorName, usize)>> {
+        if self_encryption::MIN_ENCRYPTABLE_BYTES > bytes.len() {
+            let (_, chunk) = self.package_spot(Spot::new(bytes)?, scope)?;
+            return Ok(vec![(*chunk.name(), chunk.payload_size())]);
+        }
+
+        let (infos, sizes): (Vec<_>, Vec<_>) = task::spawn_blocking(move || {
+            let encryptor = FileEncryptor::from_bytes(bytes)?;
+            (0..encryptor.num_chunks())
+                .map(|index| {
+                    let (info, chunk) = encryptor.encrypt_chunk(index)?;
+                    Ok((info, chunk.payload_size()))
+                })
+                .collect::<Result<Vec<_>>>()
+        })
+        .await
+        .map_err(|err| Error::Generic(err.to_string()))??
+        .into_iter()
+        .unzip();
+
+        let names = infos.iter().map(|info| info.dst_hash).collect_vec();
+        let encryption = keypair_encryption(scope, &self.keypair);
+        let (_, data_map_chunks) = pack(DataMap::new(infos), vec![], encryption.as_ref())?;
+
+        Ok(names
+            .into_iter()
+            .zip(sizes)
+            .chain(
+                data_map_chunks
+                    .iter()
+                    .map(|chunk| (*chunk.name(), chunk.payload_size())),
+            )
+            .collect())
+    }
+
     /// Tries to chunk the bytes, returning an address and chunks, without storing anything to network.
     #[instrument(skip_all, level = "trace")]
     pub fn chunk_bytes(&self, bytes: Bytes, scope: Scope) -> Result<(BytesAddress, Vec<Chunk>)> {
@@ -116,7 +381,7 @@ impl Client {
     /// Does not store anything to the network.
     #[instrument(skip(self, blob), level = "trace")]
     fn encrypt_blob(&self, blob: Blob, scope: Scope) -> Result<(BytesAddress, Vec<Chunk>)> {
-        let owner = encryption(scope, self.public_key());
+        let owner = keypair_encryption(scope, &self.keypair);
         encrypt_blob(blob.bytes(), owner.as_ref())
     }
 
@@ -124,7 +389,7 @@ impl Client {
     /// The chunk content will be in plain text if it has public scope, or encrypted if it is instead private.
     /// Does not store anything to the network.
     fn package_spot(&self, spot: Spot, scope: Scope) -> Result<(BytesAddress, Chunk)> {
-        let encryption = encryption(scope, self.public_key());
+        let encryption = keypair_encryption(scope, &self.keypair);
         let chunk = to_chunk(spot.bytes(), encryption.as_ref())?;
         if chunk.value().len() >= self_encryption::MIN_ENCRYPTABLE_BYTES {
             return Err(Error::Generic("You might need to pad the `Spot` contents and then store it as a `Blob`, as the encryption has made it slightly too big".to_string()));
@@ -140,6 +405,10 @@ impl Client {
 
     /// Directly writes [`Bytes`] to the network in the
     /// form of immutable chunks, without any batching.
+    ///
+    /// Chunks which fail to be stored do not fail the upload right away: the others are still sent,
+    /// and [`Error::UploadIncomplete`] is then returned. Uploading the same content again resumes
+    /// the upload, only sending the chunks which were not yet stored.
     #[instrument(skip(self, bytes), level = "debug")]
     pub async fn upload(&self, bytes: Bytes, scope: Scope) -> Result<BytesAddress> {
         if let Ok(blob) = Blob::new(bytes.clone()) {
@@ -150,24 +419,203 @@ impl Client {
         }
     }
 
+    /// Writes [`Bytes`] to the network in the form of immutable chunks as [`Client::upload`]
+    /// does, returning along with the address the receipts signed by the sections holding
+    /// the chunks, as proof that each of them was stored.
+    ///
+    /// Each chunk is sent to all Elders of its section, and only counts as stored once its
+    /// receipt is received, that is once the Adults holding it have all confirmed storing it.
+    /// Every chunk is sent, including those already stored on the network,
+    /// and the upload is not journaled: a failed upload is to be started over.
+    #[instrument(skip(self, bytes), level = "debug")]
+    pub async fn upload_with_receipts(
+        &self,
+        bytes: Bytes,
+        scope: Scope,
+    ) -> Result<(BytesAddress, Vec<StoreReceipt>)> {
+        let (address, chunks) = self.chunk_bytes(bytes, scope)?;
+
+        let total = chunks.len();
+        let mut tasks: FuturesUnordered<_> = chunks
+            .into_iter()
+            .map(|chunk| {
+                let writer = self.clone();
+                task::spawn(async move {
+                    writer
+                        .send_cmd_with_receipt(DataCmd::StoreChunk(chunk))
+                        .await
+                })
+            })
+            .collect();
+
+        let mut receipts = Vec::with_capacity(total);
+        while let Some(result) = tasks.next().await {
+            let receipt = result.map_err(|err| Error::Generic(err.to_string()))??;
+            receipts.push(receipt);
+            self.report_progress(TransferProgress::ChunksStored {
+                stored: receipts.len(),
+                total,
+            });
+        }
+
+        Ok((address, receipts))
+    }
+
+    /// Resumes an upload to `address` which previously failed, only sending the chunks of
+    /// `bytes` which were not yet stored, as uploading the same content again does.
+    ///
+    /// The upload journal does not keep the chunks, so the content uploaded is needed again.
+    /// Fails if `bytes` are not the content at `address`, or if there is no failed upload
+    /// to `address` to resume, e.g. because it was already completed.
+    #[instrument(skip(self, bytes), level = "debug")]
+    pub async fn resume_upload(&self, address: BytesAddress, bytes: Bytes) -> Result<BytesAddress> {
+        let (head_address, all_chunks) = self.chunk_bytes(bytes, address.scope())?;
+        if head_address != address {
+            return Err(Error::Generic(format!(
+                "The content to resume the upload with is not the one at {:?}",
+                address
+            )));
+        }
+
+        let journal = UploadJournal::open(&self.root_dir, address)?.ok_or_else(|| {
+            Error::Generic(format!("No incomplete upload found for {:?}", address))
+        })?;
+
+        self.upload_journaled(journal, all_chunks).await
+    }
+
+    /// Writes the contents of the file at `path` to the network in the form of immutable chunks.
+    ///
+    /// The file is self-encrypted and stored a few chunks at a time, so that memory use stays
+    /// bounded regardless of the file size. Returns the same address as [`Client::upload`] would
+    /// for the same contents, and is likewise journaled so that uploading the same file again
+    /// resumes a failed upload.
+    ///
+    /// The chunks are encrypted twice: once up front to learn their names, so that the upload can
+    /// be journaled before anything is sent, and once more as they are being stored.
+    #[instrument(skip(self), level = "debug")]
+    pub async fn upload_from_path(&self, path: &Path, scope: Scope) -> Result<BytesAddress> {
+        let file_size = tokio::fs::metadata(path).await?.len() as usize;
+        if self_encryption::MIN_ENCRYPTABLE_BYTES > file_size {
+            let spot = Spot::new(Bytes::from(tokio::fs::read(path).await?))?;
+            return self.upload_spot(spot, scope).await;
+        }
+
+        let path = path.to_path_buf();
+        let (encryptor, data_map) = task::spawn_blocking(move || {
+            let encryptor = FileEncryptor::new(&path)?;
+            let data_map = encryptor.data_map()?;
+            Ok::<_, Error>((encryptor, data_map))
+        })
+        .await
+        .map_err(|err| Error::Generic(err.to_string()))??;
+        let encryptor = Arc::new(encryptor);
+
+        let infos = data_map.infos();
+        let encryption = keypair_encryption(scope, &self.keypair);
+        let (head_address, data_map_chunks) = pack(data_map, vec![], encryption.as_ref())?;
+        let total = infos.len() + data_map_chunks.len();
+        let journal = UploadJournal::open_or_create(
+            &self.root_dir,
+            head_address,
+            infos
+                .iter()
+                .map(|info| info.dst_hash)
+                .chain(data_map_chunks.iter().map(|chunk| *chunk.name())),
+        )?;
+
+        let pending = journal.pending_names()?;
+        let mut stored = total - pending.len();
+        if stored > 0 {
+            self.report_progress(TransferProgress::ChunksStored { stored, total });
+        }
+
+        let pending_indices = infos
+            .iter()
+            .filter(|info| pending.contains(&info.dst_hash))
+            .map(|info| info.index)
+            .collect_vec();
+        for batch in pending_indices.chunks(UPLOAD_BATCH_SIZE) {
+            let encryptor = encryptor.clone();
+            let batch = batch.to_vec();
+            let chunks = task::spawn_blocking(move || encryptor.encrypt_chunks(&batch))
+                .await
+                .map_err(|err| Error::Generic(err.to_string()))??;
+            trace!(
+                "Storing {} chunks of file ({} of {} stored)",
+                chunks.len(),
+                stored,
+                total
+            );
+            self.store_journaled(&journal, chunks, &mut stored, total)
+                .await?;
+        }
+
+        let data_map_chunks = data_map_chunks
+            .into_iter()
+            .filter(|chunk| pending.contains(chunk.name()))
+            .collect_vec();
+        self.store_journaled(&journal, data_map_chunks, &mut stored, total)
+            .await?;
+
+        complete_upload(journal)
+    }
+
+    /// Writes everything read from `reader` to the network in the form of immutable chunks.
+    ///
+    /// The contents are first spooled to a temporary file, which is then uploaded
+    /// as with [`Client::upload_from_path`].
+    #[instrument(skip_all, level = "debug")]
+    pub async fn upload_from_reader(
+        &self,
+        mut reader: impl AsyncRead + Unpin,
+        scope: Scope,
+    ) -> Result<BytesAddress> {
+        let temp_file = NamedTempFile::new()?;
+        let mut file = File::from_std(temp_file.reopen()?);
+        let _ = tokio::io::copy(&mut reader, &mut file).await?;
+        file.flush().await?;
+        drop(file);
+
+        self.upload_from_path(temp_file.path(), scope).await
+    }
+
     /// Directly writes a [`Blob`] to the network in the
     /// form of immutable self encrypted chunks, without any batching.
+    /// Progress is kept in an upload journal, so that a failed upload can be resumed.
     #[instrument(skip_all, level = "trace")]
     async fn upload_blob(&self, blob: Blob, scope: Scope) -> Result<BytesAddress> {
         let (head_address, all_chunks) = self.encrypt_blob(blob, scope)?;
+        let journal = UploadJournal::open_or_create(
+            &self.root_dir,
+            head_address,
+            all_chunks.iter().map(|chunk| *chunk.name()),
+        )?;
 
-        let tasks = all_chunks.into_iter().map(|chunk| {
-            let writer = self.clone();
-            task::spawn(async move { writer.send_cmd(DataCmd::StoreChunk(chunk)).await })
-        });
+        self.upload_journaled(journal, all_chunks).await
+    }
 
-        let _ = join_all(tasks)
-            .await
+    // Stores the chunks of an upload which its journal has as pending, completing the upload
+    // once they are all stored.
+    async fn upload_journaled(
+        &self,
+        journal: UploadJournal,
+        all_chunks: Vec<Chunk>,
+    ) -> Result<BytesAddress> {
+        let total = all_chunks.len();
+        let pending = journal.pending_names()?;
+        let chunks = all_chunks
             .into_iter()
-            .flatten() // swallows errors
+            .filter(|chunk| pending.contains(chunk.name()))
             .collect_vec();
+        let mut stored = total - chunks.len();
+        if stored > 0 {
+            self.report_progress(TransferProgress::ChunksStored { stored, total });
+        }
 
-        Ok(head_address)
+        self.store_journaled(&journal, chunks, &mut stored, total)
+            .await?;
+        complete_upload(journal)
     }
 
     /// Directly writes a [`Spot`] to the network in the
@@ -176,6 +624,10 @@ impl Client {
     async fn upload_spot(&self, spot: Spot, scope: Scope) -> Result<BytesAddress> {
         let (address, chunk) = self.package_spot(spot, scope)?;
         self.send_cmd(DataCmd::StoreChunk(chunk)).await?;
+        self.report_progress(TransferProgress::ChunksStored {
+            stored: 1,
+            total: 1,
+        });
         Ok(address)
     }
 
@@ -183,11 +635,178 @@ impl Client {
     // ---------- Private helpers -----------------
     // --------------------------------------------
 
+    // Stores the chunks concurrently, recording each successful store in the journal.
+    // Chunks which fail to be stored are left pending in the journal.
+    // `stored` is the count of chunks of the upload stored so far, out of `total`.
+    async fn store_journaled(
+        &self,
+        journal: &UploadJournal,
+        chunks: Vec<Chunk>,
+        stored: &mut usize,
+        total: usize,
+    ) -> Result<()> {
+        let count = chunks.len();
+        let chunks = self.skip_existing_chunks(journal, chunks).await?;
+        if count > chunks.len() {
+            *stored += count - chunks.len();
+            self.report_progress(TransferProgress::ChunksStored {
+                stored: *stored,
+                total,
+            });
+        }
+        let mut tasks: FuturesUnordered<_> = chunks
+            .into_iter()
+            .map(|chunk| {
+                let writer = self.clone();
+                task::spawn(async move {
+                    let name = *chunk.name();
+                    writer
+                        .send_cmd(DataCmd::StoreChunk(chunk))
+                        .await
+                        .map(|_| name)
+                })
+            })
+            .collect();
+
+        let mut unflushed = 0;
+        while let Some(result) = tasks.next().await {
+            match result {
+                Ok(Ok(name)) => {
+                    journal.mark_stored(&name)?;
+                    unflushed += 1;
+                    if unflushed == JOURNAL_FLUSH_INTERVAL {
+                        journal.flush()?;
+                        unflushed = 0;
+                    }
+                    *stored += 1;
+                    self.report_progress(TransferProgress::ChunksStored {
+                        stored: *stored,
+                        total,
+                    });
+                }
+                Ok(Err(error)) => warn!("Storing chunk resulted in error: {:?}", error),
+                Err(error) => warn!("Storing chunk task failed: {:?}", error),
+            }
+        }
+
+        journal.flush()
+    }
+
+    // Leaves out the chunks which are already stored on the network, recording them as stored in the journal,
+    // if upload deduplication is enabled.
+    // Not being able to find out which chunks exist is not an error, all chunks are then sent.
+    async fn skip_existing_chunks(
+        &self,
+        journal: &UploadJournal,
+        chunks: Vec<Chunk>,
+    ) -> Result<Vec<Chunk>> {
+        if !self.deduplicate_uploads {
+            return Ok(chunks);
+        }
+
+        let addresses = chunks.iter().map(|chunk| *chunk.address()).collect();
+        let existing = match self.existing_chunks(addresses).await {
+            Ok(existing) => existing,
+            Err(error) => {
+                warn!(
+                    "Could not find out which chunks are already stored: {:?}",
+                    error
+                );
+                return Ok(chunks);
+            }
+        };
+
+        let (existing, missing): (Vec<_>, Vec<_>) = chunks
+            .into_iter()
+            .partition(|chunk| existing.contains(chunk.address()));
+
+        if !existing.is_empty() {
+            let mut bytes = 0;
+            for chunk in &existing {
+                journal.mark_stored(chunk.name())?;
+                bytes += chunk.value().len();
+            }
+            debug!(
+                "{} chunks ({} bytes) are already stored, not sending them again",
+                existing.len(),
+                bytes
+            );
+            self.report_progress(TransferProgress::ChunksDeduplicated {
+                chunks: existing.len(),
+                bytes,
+            });
+        }
+
+        Ok(missing)
+    }
+
     // Gets and decrypts chunks from the network using nothing else but the data map, then returns the raw data.
     async fn read_all(&self, data_map: DataMap) -> Result<Bytes> {
         let encrypted_chunks = Self::try_get_chunks(self.clone(), data_map.infos()).await?;
-        self_encryption::decrypt_full_set(&data_map, &encrypted_chunks)
-            .map_err(Error::SelfEncryption)
+        let bytes = decrypt_verified(&data_map, encrypted_chunks)?;
+        self.report_progress(TransferProgress::BytesDecrypted {
+            decrypted: bytes.len(),
+            total: data_map.file_size(),
+        });
+        Ok(bytes)
+    }
+
+    // Gets a chunk from the network, retrying with exponential backoff
+    // for as long as the query timeout allows.
+    async fn get_chunk_with_retry(&self, name: &XorName) -> Result<Chunk> {
+        retry(
+            || async {
+                self.get_chunk(name).await.map_err(|error| {
+                    debug!("Reading chunk {} failed, will retry: {:?}", name, error);
+                    backoff::Error::Transient(error)
+                })
+            },
+            CHUNK_RETRY_INTERVAL,
+            self.query_timeout,
+        )
+        .await
+    }
+
+    // Gets the chunks from the network in order, decrypting and yielding them one at a time.
+    fn stream_all(&self, data_map: DataMap) -> impl Stream<Item = Result<Bytes>> {
+        let reader = self.clone();
+        let concurrency = self.chunk_fetch_concurrency;
+        let infos = data_map.infos();
+        let total_chunks = infos.len();
+        let total_bytes = data_map.file_size();
+        let data_map = Arc::new(data_map);
+        let progress = self.clone();
+        let mut fetched = 0;
+        let mut decrypted = 0;
+
+        stream::iter(infos)
+            .map(move |info| {
+                let reader = reader.clone();
+                let data_map = data_map.clone();
+                async move {
+                    let chunk = reader.get_chunk_with_retry(&info.dst_hash).await?;
+                    let encrypted_chunk = EncryptedChunk {
+                        index: info.index,
+                        content: chunk.value().clone(),
+                    };
+                    decrypt_chunk(&data_map, &info, encrypted_chunk)
+                }
+            })
+            .buffered(concurrency)
+            .inspect(move |result| {
+                if let Ok(bytes) = result {
+                    fetched += 1;
+                    decrypted += bytes.len();
+                    progress.report_progress(TransferProgress::ChunksFetched {
+                        fetched,
+                        total: total_chunks,
+                    });
+                    progress.report_progress(TransferProgress::BytesDecrypted {
+                        decrypted,
+                        total: total_bytes,
+                    });
+                }
+            })
     }
 
     // Gets a subset of chunks from the network, decrypts and
@@ -207,42 +826,62 @@ impl Client {
         )
         .await?;
 
-        self_encryption::decrypt_range(&data_map, &encrypted_chunks, info.relative_pos, len)
-            .map_err(Error::SelfEncryption)
+        let decrypted = decrypt_verified(&data_map, encrypted_chunks)?;
+        let end = info.relative_pos + len;
+        if end > decrypted.len() {
+            return Err(Error::SelfEncryption(
+                self_encryption::Error::TooFewBytesDecrypted(decrypted.len(), end),
+            ));
+        }
+        let bytes = decrypted.slice(info.relative_pos..end);
+        self.report_progress(TransferProgress::BytesDecrypted {
+            decrypted: bytes.len(),
+            total: len,
+        });
+        Ok(bytes)
     }
 
+    // Gets the chunks from the network, with at most `chunk_fetch_concurrency` of them
+    // being fetched at a time. The chunks are returned in the same order as the keys.
     #[instrument(skip_all, level = "trace")]
     async fn try_get_chunks(reader: Client, keys: Vec<ChunkInfo>) -> Result<Vec<EncryptedChunk>> {
         let expected_count = keys.len();
-
-        let tasks = keys.into_iter().map(|key| {
-            let reader = reader.clone();
-            task::spawn(async move {
-                match reader.get_chunk(&key.dst_hash).await {
-                    Ok(chunk) => Some(EncryptedChunk {
-                        index: key.index,
-                        content: chunk.value().clone(),
-                    }),
-                    Err(e) => {
-                        warn!(
-                            "Reading chunk {} from network, resulted in error {}.",
-                            &key.dst_hash, e
-                        );
-                        None
+        let concurrency = reader.chunk_fetch_concurrency;
+
+        let mut tasks = stream::iter(keys)
+            .map(|key| {
+                let reader = reader.clone();
+                task::spawn(async move {
+                    match reader.get_chunk_with_retry(&key.dst_hash).await {
+                        Ok(chunk) => Some(EncryptedChunk {
+                            index: key.index,
+                            content: chunk.value().clone(),
+                        }),
+                        Err(e) => {
+                            warn!(
+                                "Reading chunk {} from network, resulted in error {}.",
+                                &key.dst_hash, e
+                            );
+                            None
+                        }
                     }
-                }
+                })
             })
-        });
+            .buffered(concurrency);
 
         // This swallowing of errors
         // is basically a compaction into a single
         // error saying "didn't get all chunks".
-        let encrypted_chunks = join_all(tasks)
-            .await
-            .into_iter()
-            .flatten()
-            .flatten()
-            .collect_vec();
+        let mut encrypted_chunks = vec![];
+        while let Some(result) = tasks.next().await {
+            if let Ok(Some(chunk)) = result {
+                encrypted_chunks.push(chunk);
+                reader.report_progress(TransferProgress::ChunksFetched {
+                    fetched: encrypted_chunks.len(),
+                    total: expected_count,
+                });
+            }
+        }
 
         if expected_count > encrypted_chunks.len() {
             Err(Error::NotEnoughChunks(
@@ -282,28 +921,95 @@ impl Client {
         if matches!(scope, Scope::Public) {
             Ok(chunk.value().clone())
         } else {
-            let owner = encryption(scope, self.public_key())
+            let owner = keypair_encryption(scope, &self.keypair)
                 .ok_or_else(|| Error::Generic("Could not get an encryption object.".to_string()))?;
             Ok(owner.decrypt(chunk.value().clone())?)
         }
     }
 }
 
+// Removes the journal of an upload if all of its chunks were stored,
+// or else fails with the number of chunks still to be stored.
+fn complete_upload(journal: UploadJournal) -> Result<BytesAddress> {
+    let address = journal.address();
+    let pending = journal.pending_count();
+    if pending > 0 {
+        return Err(Error::UploadIncomplete(address, pending));
+    }
+
+    journal.remove()?;
+    Ok(address)
+}
+
+// Decrypts the chunks, verifying each of them against its `ChunkInfo` in the data map,
+// and returns their contents concatenated in order.
+fn decrypt_verified(
+    data_map: &DataMap,
+    mut encrypted_chunks: Vec<EncryptedChunk>,
+) -> Result<Bytes> {
+    let infos = data_map.infos();
+    encrypted_chunks.sort_by_key(|chunk| chunk.index);
+
+    let decrypted = encrypted_chunks
+        .into_par_iter()
+        .map(|chunk| {
+            let info = infos
+                .get(chunk.index)
+                .filter(|info| info.index == chunk.index)
+                .ok_or_else(|| {
+                    Error::Generic(format!("Chunk {} is not in the data map", chunk.index))
+                })?;
+            decrypt_chunk(data_map, info, chunk)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Bytes::from(decrypted.concat()))
+}
+
+// Decrypts a single chunk, verifying that both its encrypted and decrypted contents
+// hash to the names recorded for it in the data map.
+fn decrypt_chunk(data_map: &DataMap, info: &ChunkInfo, chunk: EncryptedChunk) -> Result<Bytes> {
+    let actual = XorName::from_content(&chunk.content);
+    if actual != info.dst_hash {
+        return Err(Error::ChunkIntegrity {
+            expected: info.dst_hash,
+            actual,
+        });
+    }
+
+    let bytes = self_encryption::decrypt_range(data_map, &[chunk], 0, info.src_size)
+        .map_err(Error::SelfEncryption)?;
+
+    let actual = XorName::from_content(&bytes);
+    if actual != info.src_hash {
+        return Err(Error::ChunkIntegrity {
+            expected: info.src_hash,
+            actual,
+        });
+    }
+
+    Ok(bytes)
+}
+
 #[cfg(test)]
 mod tests {
-    use super::Spot;
+    use super::{decrypt_verified, Spot, TransferProgress, UploadJournal};
 
     use crate::client::{
         client_api::blob_apis::Blob,
         utils::test_utils::{create_test_client, init_test_logger},
+        Error,
     };
+    use crate::messaging::data::{store_cost, ReceiptAddress, StorageLevel};
     use crate::routing::log_markers::LogMarker;
-    use crate::types::{utils::random_bytes, BytesAddress, Keypair};
+    use crate::types::{utils::random_bytes, BytesAddress, Keypair, Token};
     use crate::url::Scope;
     use bytes::Bytes;
     use eyre::Result;
     use futures::future::join_all;
     use rand::rngs::OsRng;
+    use std::{collections::BTreeSet, io::Write};
+    use tempfile::NamedTempFile;
     use tokio::time::Instant;
     use tracing::Instrument;
 
@@ -317,14 +1023,14 @@ mod tests {
         let blob = random_bytes(MIN_BLOB_SIZE);
 
         use crate::client::client_api::data::encrypt_blob;
-        use crate::client::utils::encryption;
-        let owner = encryption(Scope::Private, keypair.public_key());
+        use crate::client::utils::keypair_encryption;
+        let owner = keypair_encryption(Scope::Private, &keypair);
         let (first_address, mut first_chunks) = encrypt_blob(blob.clone(), owner.as_ref())?;
 
         first_chunks.sort();
 
         for _ in 0..100 {
-            let owner = encryption(Scope::Private, keypair.public_key());
+            let owner = keypair_encryption(Scope::Private, &keypair);
             let (head_address, mut all_chunks) = encrypt_blob(blob.clone(), owner.as_ref())?;
             assert_eq!(first_address, head_address);
             all_chunks.sort();
@@ -334,6 +1040,55 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn private_data_map_is_unreadable_without_the_key() -> Result<()> {
+        init_test_logger();
+        let keypair = Keypair::new_ed25519(&mut OsRng);
+        let other_keypair = Keypair::new_ed25519(&mut OsRng);
+        let blob = random_bytes(MIN_BLOB_SIZE);
+
+        use crate::client::client_api::data::encrypt_blob;
+        use crate::client::utils::keypair_encryption;
+        use crate::types::Encryption;
+        let owner = keypair_encryption(Scope::Private, &keypair);
+        let (head_address, all_chunks) = encrypt_blob(blob, owner.as_ref())?;
+        let head_chunk = all_chunks
+            .into_iter()
+            .find(|chunk| chunk.name() == head_address.name())
+            .ok_or_else(|| eyre::eyre!("No data map chunk"))?;
+
+        let other = keypair_encryption(Scope::Private, &other_keypair)
+            .ok_or_else(|| eyre::eyre!("No encryption for private scope"))?;
+        assert!(other.decrypt(head_chunk.value().clone()).is_err());
+
+        let owner = owner.ok_or_else(|| eyre::eyre!("No encryption for private scope"))?;
+        assert!(owner.decrypt(head_chunk.value().clone()).is_ok());
+
+        Ok(())
+    }
+
+    #[test]
+    fn decryption_verifies_chunks() -> Result<()> {
+        init_test_logger();
+        let bytes = random_bytes(3 * MIN_BLOB_SIZE);
+        let (data_map, encrypted_chunks) = self_encryption::encrypt(bytes.clone())?;
+
+        let decrypted = decrypt_verified(&data_map, encrypted_chunks.clone())?;
+        assert_eq!(decrypted, bytes);
+
+        let mut tampered_chunks = encrypted_chunks;
+        let mut content = tampered_chunks[1].content.to_vec();
+        content[0] ^= 1;
+        tampered_chunks[1].content = Bytes::from(content);
+
+        assert!(matches!(
+            decrypt_verified(&data_map, tampered_chunks),
+            Err(Error::ChunkIntegrity { .. })
+        ));
+
+        Ok(())
+    }
+
     // Test storing and reading min size blob.
     #[tokio::test(flavor = "multi_thread")]
     async fn store_and_read_3kb() -> Result<()> {
@@ -512,6 +1267,260 @@ mod tests {
         store_and_read_blob(10 * 1024 * 1024, Scope::Private).await
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn store_and_read_from_path() -> Result<()> {
+        init_test_logger();
+        let _outer_span = tracing::info_span!("store_and_read_from_path").entered();
+
+        let client = create_test_client().await?;
+
+        for (size, scope) in [
+            (MIN_BLOB_SIZE / 3, Scope::Public),
+            (5 * 1024 * 1024, Scope::Public),
+            (5 * 1024 * 1024, Scope::Private),
+        ] {
+            let bytes = random_bytes(size);
+            let mut file = NamedTempFile::new()?;
+            file.write_all(&bytes)?;
+            file.flush()?;
+
+            let address = client.upload_from_path(file.path(), scope).await?;
+
+            // the same contents uploaded from memory end up at the same address
+            let (expected_address, _) = client.chunk_bytes(bytes.clone(), scope)?;
+            assert_eq!(address, expected_address);
+
+            // the larger the file, the longer we have to wait before we start querying
+            let delay =
+                tokio::time::Duration::from_secs(usize::max(1, size / DELAY_DIVIDER) as u64);
+            tokio::time::sleep(delay).await;
+
+            let read_data = client.read_bytes(address).await?;
+            compare(bytes, read_data)?;
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn store_and_download() -> Result<()> {
+        init_test_logger();
+        let _outer_span = tracing::info_span!("store_and_download").entered();
+
+        let client = create_test_client().await?;
+
+        for (size, scope) in [
+            (MIN_BLOB_SIZE / 3, Scope::Private),
+            (5 * 1024 * 1024, Scope::Public),
+            (5 * 1024 * 1024, Scope::Private),
+        ] {
+            let bytes = random_bytes(size);
+            let address = client.upload(bytes.clone(), scope).await?;
+
+            // the larger the file, the longer we have to wait before we start querying
+            let delay =
+                tokio::time::Duration::from_secs(usize::max(1, size / DELAY_DIVIDER) as u64);
+            tokio::time::sleep(delay).await;
+
+            let mut downloaded = vec![];
+            client.download_to(address, &mut downloaded).await?;
+            compare(bytes, Bytes::from(downloaded))?;
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn store_and_read_reports_progress() -> Result<()> {
+        init_test_logger();
+        let _outer_span = tracing::info_span!("store_and_read_reports_progress").entered();
+
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let client = create_test_client().await?.with_progress_sender(sender);
+
+        let size = 5 * 1024 * 1024;
+        let bytes = random_bytes(size);
+        let address = client.upload(bytes.clone(), Scope::Public).await?;
+
+        let delay = tokio::time::Duration::from_secs(usize::max(1, size / DELAY_DIVIDER) as u64);
+        tokio::time::sleep(delay).await;
+
+        let _ = client.read_bytes(address).await?;
+        drop(client);
+
+        let mut events = vec![];
+        while let Some(event) = receiver.recv().await {
+            events.push(event);
+        }
+
+        let last_stored = events.iter().rev().find_map(|event| match event {
+            TransferProgress::ChunksStored { stored, total } => Some((*stored, *total)),
+            _ => None,
+        });
+        assert!(matches!(last_stored, Some((stored, total)) if stored == total));
+
+        assert_eq!(
+            events.last(),
+            Some(&TransferProgress::BytesDecrypted {
+                decrypted: size,
+                total: size
+            })
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn upload_skips_existing_chunks() -> Result<()> {
+        init_test_logger();
+        let _outer_span = tracing::info_span!("upload_skips_existing_chunks").entered();
+
+        let client = create_test_client().await?;
+
+        let size = 5 * 1024 * 1024;
+        let bytes = random_bytes(size);
+        let (_, chunks) = client.chunk_bytes(bytes.clone(), Scope::Public)?;
+        let address = client.upload(bytes.clone(), Scope::Public).await?;
+
+        let delay = tokio::time::Duration::from_secs(usize::max(1, size / DELAY_DIVIDER) as u64);
+        tokio::time::sleep(delay).await;
+
+        let addresses: Vec<_> = chunks.iter().map(|chunk| *chunk.address()).collect();
+        let existing = client.existing_chunks(addresses.clone()).await?;
+        assert_eq!(existing, addresses.into_iter().collect());
+
+        // uploading the same contents again with deduplication sends none of the chunks
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let reporting_client = client
+            .with_upload_deduplication()
+            .with_progress_sender(sender);
+        assert_eq!(
+            reporting_client.upload(bytes, Scope::Public).await?,
+            address
+        );
+        drop(reporting_client);
+
+        let mut events = vec![];
+        while let Some(event) = receiver.recv().await {
+            events.push(event);
+        }
+
+        let bytes = chunks.iter().map(|chunk| chunk.value().len()).sum();
+        assert_eq!(
+            events,
+            vec![
+                TransferProgress::ChunksDeduplicated {
+                    chunks: chunks.len(),
+                    bytes
+                },
+                TransferProgress::ChunksStored {
+                    stored: chunks.len(),
+                    total: chunks.len()
+                }
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn resume_failed_upload() -> Result<()> {
+        init_test_logger();
+        let _outer_span = tracing::info_span!("resume_failed_upload").entered();
+
+        let client = create_test_client().await?;
+
+        let bytes = random_bytes(3 * 1024 * 1024);
+        let (address, chunks) = client.chunk_bytes(bytes.clone(), Scope::Public)?;
+
+        // there is no upload to resume yet
+        assert!(client.resume_upload(address, bytes.clone()).await.is_err());
+
+        // an upload which failed before any chunk was stored leaves them all pending
+        drop(UploadJournal::open_or_create(
+            &client.root_dir,
+            address,
+            chunks.iter().map(|chunk| *chunk.name()),
+        )?);
+
+        // the upload is only resumed with the content it was for
+        let other_bytes = random_bytes(bytes.len());
+        assert!(client.resume_upload(address, other_bytes).await.is_err());
+
+        assert_eq!(client.resume_upload(address, bytes.clone()).await?, address);
+        assert!(UploadJournal::open(&client.root_dir, address)?.is_none());
+
+        let read_data = client.read_bytes(address).await?;
+        compare(bytes.clone(), read_data)?;
+
+        // the upload is complete, so there is nothing left to resume
+        assert!(client.resume_upload(address, bytes).await.is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn upload_returns_store_receipts() -> Result<()> {
+        init_test_logger();
+        let _outer_span = tracing::info_span!("upload_returns_store_receipts").entered();
+
+        let client = create_test_client().await?;
+
+        let bytes = random_bytes(3 * 1024 * 1024);
+        let (_, chunks) = client.chunk_bytes(bytes.clone(), Scope::Public)?;
+        let (address, receipts) = client
+            .upload_with_receipts(bytes.clone(), Scope::Public)
+            .await?;
+
+        assert_eq!(receipts.len(), chunks.len());
+        for receipt in &receipts {
+            assert!(receipt.verify());
+            assert!(!receipt.holders.is_empty());
+            assert!(chunks.iter().any(|chunk| {
+                receipt.address == ReceiptAddress::Chunk(*chunk.address())
+                    && receipt.content == *chunk.name()
+            }));
+        }
+        // each receipt is for the command storing its own chunk
+        let commands: BTreeSet<_> = receipts.iter().map(|r| r.correlation_id).collect();
+        assert_eq!(commands.len(), receipts.len());
+
+        // with every chunk acknowledged, the data can be read back right away
+        let read_data = client.read_bytes(address).await?;
+        compare(bytes, read_data)?;
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn store_cost_sums_quotes_over_chunks() -> Result<()> {
+        init_test_logger();
+        let _outer_span = tracing::info_span!("store_cost_sums_quotes_over_chunks").entered();
+
+        let client = create_test_client().await?;
+
+        let bytes = random_bytes(3 * 1024 * 1024);
+        for scope in [Scope::Public, Scope::Private] {
+            let (_, chunks) = client.chunk_bytes(bytes.clone(), scope)?;
+            let cost = match scope {
+                Scope::Public => client.store_cost_for(bytes.clone()).await?,
+                Scope::Private => client.store_cost_for_scoped(bytes.clone(), scope).await?,
+            };
+
+            // each chunk is quoted somewhere between an empty and a full section's price
+            let quotes_at = |level| {
+                chunks
+                    .iter()
+                    .map(|chunk| store_cost(chunk.payload_size() as u64, level).as_nano())
+                    .sum::<u64>()
+            };
+            assert!(cost >= Token::from_nano(quotes_at(0)));
+            assert!(cost <= Token::from_nano(quotes_at(StorageLevel::MAX)));
+        }
+
+        Ok(())
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     #[ignore = "too heavy for CI"]
     async fn store_and_read_20mb() -> Result<()> {
@@ -0,0 +1,323 @@
+// Copyright 2021 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::client::{Error, Result};
+use crate::types::Chunk;
+use lazy_static::lazy_static;
+use sled::{Db, Tree};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+use xor_name::{XorName, XOR_NAME_LEN};
+
+const CACHE_DIR_NAME: &str = "chunk_cache";
+const CHUNKS_TREE_NAME: &str = "chunks";
+const ACCESS_IDS_TREE_NAME: &str = "access_ids";
+const RECENCY_TREE_NAME: &str = "recency";
+
+lazy_static! {
+    // A sled db can only be opened once at a time, so the cache of each root dir is opened
+    // on first use and then shared by all clients in the process.
+    static ref CACHE_STORES: Mutex<HashMap<PathBuf, Arc<CacheStore>>> = Mutex::new(HashMap::new());
+}
+
+/// Statistics of the client-side chunk cache.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ChunkCacheStats {
+    /// Number of chunk reads served from the cache since the client was started.
+    pub hits: u64,
+    /// Number of chunk reads which had to go to the network since the client was started.
+    pub misses: u64,
+    /// Number of chunks currently in the cache.
+    pub chunks: usize,
+    /// Total size in bytes of the chunks currently in the cache.
+    pub size: u64,
+}
+
+/// On-disk cache of chunks read from the network.
+///
+/// Since chunks are immutable and content-addressed, a cached chunk never goes stale.
+/// When the total size of the cached chunks exceeds the capacity,
+/// the least recently used chunks are evicted.
+///
+/// Clients with the same root dir share the cached chunks, each of them keeping
+/// the cache within its own capacity and counting its own hits and misses.
+#[derive(Debug)]
+pub(crate) struct ChunkCache {
+    store: Arc<CacheStore>,
+    capacity: u64,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+// The cached chunks under a root dir.
+#[derive(Debug)]
+struct CacheStore {
+    db: Db,
+    // chunk name -> chunk content
+    chunks: Tree,
+    // chunk name -> id of its last access
+    access_ids: Tree,
+    // id of last access followed by chunk name -> (), ordered from least to most recently used
+    recency: Tree,
+    size: AtomicU64,
+}
+
+impl ChunkCache {
+    /// Opens the cache under `root_dir`, limiting the total size of the cached chunks to `capacity` bytes.
+    pub(crate) fn open(root_dir: &Path, capacity: u64) -> Result<Self> {
+        let cache = Self {
+            store: cache_store(&root_dir.join(CACHE_DIR_NAME))?,
+            capacity,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        };
+
+        // the capacity might have been lowered since the cache was last used
+        cache.evict_excess()?;
+
+        Ok(cache)
+    }
+
+    /// Returns the chunk with the given name, if it is in the cache.
+    ///
+    /// A cached chunk whose content does not match its name is removed, and is not returned.
+    pub(crate) fn get(&self, name: &XorName) -> Result<Option<Chunk>> {
+        let chunk = match self.store.chunks.get(name.0)? {
+            Some(value) => Chunk::new(value.to_vec().into()),
+            None => {
+                let _ = self.misses.fetch_add(1, Ordering::Relaxed);
+                return Ok(None);
+            }
+        };
+
+        if chunk.name() != name {
+            warn!(
+                "Cached chunk {} does not match its content, removing it from the cache",
+                name
+            );
+            self.remove(name)?;
+            let _ = self.misses.fetch_add(1, Ordering::Relaxed);
+            return Ok(None);
+        }
+
+        self.touch(name)?;
+        let _ = self.hits.fetch_add(1, Ordering::Relaxed);
+
+        Ok(Some(chunk))
+    }
+
+    /// Adds the chunk to the cache, evicting the least recently used chunks if needed.
+    pub(crate) fn insert(&self, chunk: &Chunk) -> Result<()> {
+        let len = chunk.value().len() as u64;
+        if len > self.capacity {
+            return Ok(());
+        }
+
+        let name = chunk.name();
+        if let Some(previous) = self.store.chunks.insert(name.0, chunk.value().as_ref())? {
+            let _ = self
+                .store
+                .size
+                .fetch_sub(previous.len() as u64, Ordering::Relaxed);
+        }
+        let _ = self.store.size.fetch_add(len, Ordering::Relaxed);
+        self.touch(name)?;
+
+        self.evict_excess()
+    }
+
+    /// Returns the current cache statistics.
+    pub(crate) fn stats(&self) -> ChunkCacheStats {
+        ChunkCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            chunks: self.store.chunks.len(),
+            size: self.store.size.load(Ordering::Relaxed),
+        }
+    }
+
+    // Marks the chunk as the most recently used.
+    fn touch(&self, name: &XorName) -> Result<()> {
+        let access_id = self.store.db.generate_id()?.to_be_bytes();
+        if let Some(previous_id) = self.store.access_ids.insert(name.0, &access_id)? {
+            let _ = self.store.recency.remove(recency_key(&previous_id, name))?;
+        }
+        let _ = self
+            .store
+            .recency
+            .insert(recency_key(&access_id, name), &[])?;
+        Ok(())
+    }
+
+    fn remove(&self, name: &XorName) -> Result<()> {
+        if let Some(value) = self.store.chunks.remove(name.0)? {
+            let _ = self
+                .store
+                .size
+                .fetch_sub(value.len() as u64, Ordering::Relaxed);
+        }
+        if let Some(access_id) = self.store.access_ids.remove(name.0)? {
+            let _ = self.store.recency.remove(recency_key(&access_id, name))?;
+        }
+        Ok(())
+    }
+
+    fn evict_excess(&self) -> Result<()> {
+        while self.store.size.load(Ordering::Relaxed) > self.capacity {
+            let key = match self.store.recency.pop_min()? {
+                Some((key, _)) => key,
+                None => break,
+            };
+
+            let mut name = XorName::default();
+            name.0.copy_from_slice(&key[key.len() - XOR_NAME_LEN..]);
+            trace!("Evicting chunk {} from the cache", name);
+
+            if let Some(value) = self.store.chunks.remove(name.0)? {
+                let _ = self
+                    .store
+                    .size
+                    .fetch_sub(value.len() as u64, Ordering::Relaxed);
+            }
+            let _ = self.store.access_ids.remove(name.0)?;
+        }
+        Ok(())
+    }
+}
+
+fn cache_store(path: &Path) -> Result<Arc<CacheStore>> {
+    let mut stores = CACHE_STORES
+        .lock()
+        .map_err(|_| Error::Generic("Chunk cache lock poisoned".to_string()))?;
+    if let Some(store) = stores.get(path) {
+        return Ok(store.clone());
+    }
+
+    let db = sled::open(path)?;
+    let chunks = db.open_tree(CHUNKS_TREE_NAME)?;
+    let access_ids = db.open_tree(ACCESS_IDS_TREE_NAME)?;
+    let recency = db.open_tree(RECENCY_TREE_NAME)?;
+
+    let mut size = 0;
+    for entry in chunks.iter() {
+        let (_, value) = entry?;
+        size += value.len() as u64;
+    }
+
+    let store = Arc::new(CacheStore {
+        db,
+        chunks,
+        access_ids,
+        recency,
+        size: AtomicU64::new(size),
+    });
+    let _ = stores.insert(path.to_path_buf(), store.clone());
+    Ok(store)
+}
+
+fn recency_key(access_id: &[u8], name: &XorName) -> Vec<u8> {
+    access_id.iter().chain(name.0.iter()).copied().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ChunkCache, CACHE_DIR_NAME, CHUNKS_TREE_NAME};
+    use crate::types::{utils::random_bytes, Chunk};
+    use eyre::Result;
+    use tempfile::tempdir;
+    use xor_name::XorName;
+
+    #[test]
+    fn least_recently_used_chunks_are_evicted() -> Result<()> {
+        let root_dir = tempdir()?;
+        let cache = ChunkCache::open(root_dir.path(), 3 * 1024)?;
+
+        let chunks = (0..4)
+            .map(|_| Chunk::new(random_bytes(1024)))
+            .collect::<Vec<_>>();
+
+        cache.insert(&chunks[0])?;
+        cache.insert(&chunks[1])?;
+        cache.insert(&chunks[2])?;
+
+        // using the first chunk makes the second one the least recently used
+        assert_eq!(cache.get(chunks[0].name())?.as_ref(), Some(&chunks[0]));
+        cache.insert(&chunks[3])?;
+
+        assert!(cache.get(chunks[1].name())?.is_none());
+        assert_eq!(cache.get(chunks[0].name())?.as_ref(), Some(&chunks[0]));
+        assert_eq!(cache.get(chunks[2].name())?.as_ref(), Some(&chunks[2]));
+        assert_eq!(cache.get(chunks[3].name())?.as_ref(), Some(&chunks[3]));
+
+        let stats = cache.stats();
+        assert_eq!(stats.hits, 4);
+        assert_eq!(stats.misses, 1);
+        assert_eq!(stats.chunks, 3);
+        assert_eq!(stats.size, 3 * 1024);
+
+        // the contents survive reopening the cache
+        drop(cache);
+        let cache = ChunkCache::open(root_dir.path(), 3 * 1024)?;
+        assert_eq!(cache.stats().size, 3 * 1024);
+        assert_eq!(cache.get(chunks[3].name())?.as_ref(), Some(&chunks[3]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn corrupted_chunks_are_not_returned() -> Result<()> {
+        let root_dir = tempdir()?;
+
+        let name = XorName::random();
+        let db = sled::open(root_dir.path().join(CACHE_DIR_NAME))?;
+        let _ = db
+            .open_tree(CHUNKS_TREE_NAME)?
+            .insert(name.0, random_bytes(1024).as_ref())?;
+        drop(db);
+
+        let cache = ChunkCache::open(root_dir.path(), 1024 * 1024)?;
+        assert_eq!(cache.stats().chunks, 1);
+        assert!(cache.get(&name)?.is_none());
+        assert_eq!(cache.stats().chunks, 0);
+        assert_eq!(cache.stats().size, 0);
+
+        Ok(())
+    }
+    #[test]
+    fn caches_with_the_same_root_dir_share_chunks() -> Result<()> {
+        let root_dir = tempdir()?;
+        let first = ChunkCache::open(root_dir.path(), 2 * 1024)?;
+        let second = ChunkCache::open(root_dir.path(), 2 * 1024)?;
+
+        let chunks = (0..3)
+            .map(|_| Chunk::new(random_bytes(1024)))
+            .collect::<Vec<_>>();
+
+        first.insert(&chunks[0])?;
+        assert_eq!(second.get(chunks[0].name())?.as_ref(), Some(&chunks[0]));
+
+        // the size of the chunks cached by either counts against the capacity
+        second.insert(&chunks[1])?;
+        first.insert(&chunks[2])?;
+        assert!(first.get(chunks[0].name())?.is_none());
+        assert_eq!(second.stats().size, 2 * 1024);
+
+        // hits and misses are counted per cache
+        assert_eq!(first.stats().hits, 0);
+        assert_eq!(first.stats().misses, 1);
+        assert_eq!(second.stats().hits, 1);
+
+        Ok(())
+    }
+}
@@ -9,7 +9,7 @@
 use super::Client;
 use crate::client::Error;
 use crate::messaging::{
-    data::{DataCmd, ServiceMsg},
+    data::{DataCmd, ServiceMsg, StoreReceipt},
     ServiceAuth, WireMsg,
 };
 use crate::types::{PublicKey, Signature};
@@ -54,7 +54,7 @@ impl Client {
         // With 3 we are "guaranteed" 1 correctly functioning Elder.
         let targets = match &cmd {
             DataCmd::StoreChunk(_) => 3, // stored at Adults, so only 1 correctly functioning Elder need to relay
-            DataCmd::Register(_) => 7,   // only stored at Elders, all need a copy
+            DataCmd::Register(_) | DataCmd::StoreSafeKey(_) => 7, // only stored at Elders, all need a copy
         };
 
         let serialised_cmd = {
@@ -66,4 +66,36 @@ impl Client {
         self.send_signed_command(dst_name, client_pk, serialised_cmd, signature, targets)
             .await
     }
+
+    // Send a DataCmd to all Elders of the section holding the data,
+    // awaiting the receipt signed by the section once it has been stored.
+    #[instrument(skip_all, level = "debug", name = "client-api send cmd with receipt")]
+    pub(crate) async fn send_cmd_with_receipt(&self, cmd: DataCmd) -> Result<StoreReceipt, Error> {
+        let dst_name = cmd.dst_name();
+        let receipt_address = cmd.receipt_address();
+        let receipt_content = cmd
+            .receipt_content()
+            .map_err(|err| Error::Generic(err.to_string()))?;
+
+        let serialised_cmd = {
+            let msg = ServiceMsg::CmdWithReceipt(cmd);
+            WireMsg::serialize_msg_payload(&msg)?
+        };
+        let auth = ServiceAuth {
+            public_key: self.public_key(),
+            signature: self.keypair.sign(&serialised_cmd),
+        };
+
+        tokio::time::timeout(
+            self.query_timeout,
+            self.session.send_cmd_with_receipt(
+                dst_name,
+                (receipt_address, receipt_content),
+                auth,
+                serialised_cmd,
+            ),
+        )
+        .await
+        .map_err(|_| Error::NoResponse)?
+    }
 }
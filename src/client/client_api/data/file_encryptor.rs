@@ -0,0 +1,331 @@
+// Copyright 2021 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::client::{Error, Result};
+use crate::types::Chunk;
+use aes::Aes128;
+use block_modes::{block_padding::Pkcs7, BlockMode, Cbc};
+use brotli::enc::BrotliEncoderParams;
+use bytes::Bytes;
+use self_encryption::{
+    ChunkInfo, DataMap, COMPRESSION_QUALITY, MAX_CHUNK_SIZE, MIN_CHUNK_SIZE, MIN_ENCRYPTABLE_BYTES,
+};
+use std::{
+    fs::File,
+    io::{Cursor, Read, Seek, SeekFrom},
+    path::Path,
+    sync::Mutex,
+};
+use xor_name::{XorName, XOR_NAME_LEN};
+
+const KEY_SIZE: usize = 16;
+const IV_SIZE: usize = 16;
+const PAD_SIZE: usize = (XOR_NAME_LEN * 3) - KEY_SIZE - IV_SIZE;
+
+type Aes128Cbc = Cbc<Aes128, Pkcs7>;
+
+/// Self-encrypts a file, or any other seekable content, one chunk at a time.
+///
+/// `self_encryption::encrypt` needs all of the content in memory. However, the encryption of a chunk
+/// only depends on its own content and on the source hashes of its two preceding chunks (wrapping
+/// around for the first two). So once a first pass over the file has collected all source hashes,
+/// every chunk can be read and encrypted on its own, and memory use is bounded by the chunk size.
+///
+/// The resulting chunks and `ChunkInfo`s are identical to those `self_encryption::encrypt` would
+/// produce for the same content. This relies on the compression and encryption of a chunk done
+/// here matching that of the pinned version of `self_encryption`, which is checked by the tests.
+///
+/// All reads are blocking, so the encryptor is to be used off the async executor.
+pub(crate) struct FileEncryptor<R = File> {
+    file: Mutex<R>,
+    file_size: usize,
+    src_hashes: Vec<XorName>,
+}
+
+impl FileEncryptor {
+    /// Reads through the file once, recording the hash of the source content of every chunk.
+    /// The file is then kept open for the chunks to be read again as they are encrypted.
+    pub(crate) fn new(path: &Path) -> Result<Self> {
+        let file = File::open(path)?;
+        let file_size = file.metadata()?.len() as usize;
+        Self::from_reader(file, file_size)
+    }
+}
+
+impl FileEncryptor<Cursor<Bytes>> {
+    /// Encrypts content already in memory, without holding all of its chunks at once
+    /// as `self_encryption::encrypt` does.
+    pub(crate) fn from_bytes(bytes: Bytes) -> Result<Self> {
+        let size = bytes.len();
+        Self::from_reader(Cursor::new(bytes), size)
+    }
+}
+
+impl<R: Read + Seek> FileEncryptor<R> {
+    fn from_reader(mut file: R, file_size: usize) -> Result<Self> {
+        if MIN_ENCRYPTABLE_BYTES > file_size {
+            return Err(Error::Generic(
+                "The provided file is too small to be self-encrypted".to_string(),
+            ));
+        }
+
+        let src_hashes = (0..get_num_chunks(file_size))
+            .map(|index| {
+                read_chunk(&mut file, file_size, index)
+                    .map(|data| XorName::from_content(data.as_ref()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            file: Mutex::new(file),
+            file_size,
+            src_hashes,
+        })
+    }
+
+    /// Encrypts every chunk to learn its name, returning the `DataMap` of the file.
+    /// The chunks themselves are dropped as soon as they are encrypted.
+    pub(crate) fn data_map(&self) -> Result<DataMap> {
+        let infos = (0..self.num_chunks())
+            .map(|index| self.encrypt_chunk(index).map(|(info, _)| info))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(DataMap::new(infos))
+    }
+
+    /// Reads and self-encrypts the chunks at the given indices.
+    pub(crate) fn encrypt_chunks(&self, indices: &[usize]) -> Result<Vec<Chunk>> {
+        indices
+            .iter()
+            .map(|index| self.encrypt_chunk(*index).map(|(_, chunk)| chunk))
+            .collect()
+    }
+
+    /// Number of chunks the file content is split into.
+    pub(crate) fn num_chunks(&self) -> usize {
+        self.src_hashes.len()
+    }
+
+    /// Reads and self-encrypts the chunk at `index`,
+    /// returning its entry for the `DataMap` along with the resulting chunk.
+    pub(crate) fn encrypt_chunk(&self, index: usize) -> Result<(ChunkInfo, Chunk)> {
+        let src_hash = *self
+            .src_hashes
+            .get(index)
+            .ok_or_else(|| Error::Generic(format!("Chunk index {} is out of range", index)))?;
+
+        let data = {
+            let mut file = self
+                .file
+                .lock()
+                .map_err(|_| Error::Generic("File encryptor lock poisoned".to_string()))?;
+            read_chunk(&mut *file, self.file_size, index)?
+        };
+        if XorName::from_content(data.as_ref()) != src_hash {
+            return Err(Error::Generic(
+                "The file was modified while it was being encrypted".to_string(),
+            ));
+        }
+
+        let src_size = data.len();
+        let content = self.encrypt_content(index, data)?;
+        let chunk = Chunk::new(content);
+        let info = ChunkInfo {
+            index,
+            dst_hash: *chunk.name(),
+            src_hash,
+            src_size,
+        };
+
+        Ok((info, chunk))
+    }
+
+    // Compresses, encrypts and then obfuscates the content, as done by `self_encryption`.
+    fn encrypt_content(&self, index: usize, content: Bytes) -> Result<Bytes> {
+        let (pad, key, iv) = self.pad_key_and_iv(index);
+
+        let mut compressed = vec![];
+        let params = BrotliEncoderParams {
+            quality: COMPRESSION_QUALITY,
+            ..Default::default()
+        };
+        let _ =
+            brotli::BrotliCompress(&mut Cursor::new(content.as_ref()), &mut compressed, &params)?;
+
+        let cipher = Aes128Cbc::new_fix(key.as_ref().into(), iv.as_ref().into());
+        let encrypted = cipher.encrypt_vec(&compressed);
+
+        Ok(encrypted
+            .iter()
+            .zip(pad.iter().cycle())
+            .map(|(&a, &b)| a ^ b)
+            .collect())
+    }
+
+    fn pad_key_and_iv(&self, index: usize) -> ([u8; PAD_SIZE], [u8; KEY_SIZE], [u8; IV_SIZE]) {
+        let hashes = &self.src_hashes;
+        let (n_1, n_2) = match index {
+            0 => (hashes.len() - 1, hashes.len() - 2),
+            1 => (0, hashes.len() - 1),
+            n => (n - 1, n - 2),
+        };
+
+        let mut pad = [0u8; PAD_SIZE];
+        let mut key = [0u8; KEY_SIZE];
+        let mut iv = [0u8; IV_SIZE];
+
+        for (pad_el, element) in pad
+            .iter_mut()
+            .zip(hashes[index].iter().chain(hashes[n_2].iter()))
+        {
+            *pad_el = *element;
+        }
+
+        for (key_el, element) in key.iter_mut().chain(iv.iter_mut()).zip(hashes[n_1].iter()) {
+            *key_el = *element;
+        }
+
+        (pad, key, iv)
+    }
+}
+
+fn read_chunk(file: &mut (impl Read + Seek), file_size: usize, index: usize) -> Result<Bytes> {
+    let start = get_start_position(file_size, index);
+    let mut data = vec![0; get_chunk_size(file_size, index)];
+    let _ = file.seek(SeekFrom::Start(start as u64))?;
+    file.read_exact(&mut data)?;
+    Ok(Bytes::from(data))
+}
+
+// The following chunk layout functions match those used by `self_encryption`.
+
+fn get_num_chunks(file_size: usize) -> usize {
+    if file_size < MIN_ENCRYPTABLE_BYTES {
+        0
+    } else if file_size < 3 * MAX_CHUNK_SIZE {
+        3
+    } else if file_size % MAX_CHUNK_SIZE == 0 {
+        file_size / MAX_CHUNK_SIZE
+    } else {
+        (file_size / MAX_CHUNK_SIZE) + 1
+    }
+}
+
+fn get_chunk_size(file_size: usize, index: usize) -> usize {
+    if file_size < MIN_ENCRYPTABLE_BYTES {
+        return 0;
+    }
+    if file_size < 3 * MAX_CHUNK_SIZE {
+        return if index < 2 {
+            file_size / 3
+        } else {
+            file_size - (2 * (file_size / 3))
+        };
+    }
+    let total_chunks = get_num_chunks(file_size);
+    if index < total_chunks - 2 {
+        return MAX_CHUNK_SIZE;
+    }
+    let remainder = file_size % MAX_CHUNK_SIZE;
+    let penultimate = (total_chunks - 2) == index;
+    if remainder == 0 {
+        MAX_CHUNK_SIZE
+    } else if remainder < MIN_CHUNK_SIZE {
+        if penultimate {
+            MAX_CHUNK_SIZE - MIN_CHUNK_SIZE
+        } else {
+            MIN_CHUNK_SIZE + remainder
+        }
+    } else if penultimate {
+        MAX_CHUNK_SIZE
+    } else {
+        remainder
+    }
+}
+
+fn get_start_position(file_size: usize, index: usize) -> usize {
+    let total_chunks = get_num_chunks(file_size);
+    if total_chunks == 0 {
+        return 0;
+    }
+    let first_chunk_size = get_chunk_size(file_size, 0);
+    if (total_chunks - 1) == index {
+        first_chunk_size * (index - 1) + get_chunk_size(file_size, index - 1)
+    } else {
+        first_chunk_size * index
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FileEncryptor;
+    use crate::types::utils::random_bytes;
+    use eyre::Result;
+    use itertools::Itertools;
+    use self_encryption::{MAX_CHUNK_SIZE, MIN_CHUNK_SIZE, MIN_ENCRYPTABLE_BYTES};
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn matches_in_memory_self_encryption() -> Result<()> {
+        let sizes = [
+            MIN_ENCRYPTABLE_BYTES,
+            MAX_CHUNK_SIZE + MIN_CHUNK_SIZE,
+            3 * MAX_CHUNK_SIZE,
+            4 * MAX_CHUNK_SIZE + MIN_CHUNK_SIZE / 2,
+            5 * MAX_CHUNK_SIZE + 3 * MIN_CHUNK_SIZE,
+        ];
+
+        for size in sizes {
+            let bytes = random_bytes(size);
+            let mut file = NamedTempFile::new()?;
+            file.write_all(&bytes)?;
+            file.flush()?;
+
+            let (data_map, encrypted_chunks) = self_encryption::encrypt(bytes.clone())?;
+
+            let encryptor = FileEncryptor::new(file.path())?;
+            assert_eq!(encryptor.num_chunks(), encrypted_chunks.len());
+            assert_eq!(encryptor.data_map()?, data_map);
+
+            let (infos, chunks): (Vec<_>, Vec<_>) = (0..encryptor.num_chunks())
+                .map(|index| encryptor.encrypt_chunk(index))
+                .collect::<Result<Vec<_>, _>>()?
+                .into_iter()
+                .unzip();
+
+            assert_eq!(self_encryption::DataMap::new(infos), data_map);
+
+            // as do the chunks of the same content in memory
+            let in_memory = FileEncryptor::from_bytes(bytes.clone())?;
+            assert_eq!(in_memory.data_map()?, data_map);
+
+            let expected = encrypted_chunks
+                .into_iter()
+                .sorted_by_key(|chunk| chunk.index)
+                .map(|chunk| chunk.content)
+                .collect_vec();
+            let actual = chunks
+                .into_iter()
+                .map(|chunk| chunk.value().clone())
+                .collect_vec();
+            assert_eq!(actual, expected);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn too_small_file_is_rejected() -> Result<()> {
+        let mut file = NamedTempFile::new()?;
+        file.write_all(&random_bytes(MIN_ENCRYPTABLE_BYTES - 1))?;
+        file.flush()?;
+
+        assert!(FileEncryptor::new(file.path()).is_err());
+        Ok(())
+    }
+}
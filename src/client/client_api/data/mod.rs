@@ -6,9 +6,11 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
+mod file_encryptor;
 mod pac_man;
 
-pub(crate) use pac_man::{encrypt_blob, to_chunk, DataMapLevel};
+pub(crate) use file_encryptor::FileEncryptor;
+pub(crate) use pac_man::{encrypt_blob, pack, to_chunk, DataMapLevel};
 
 use crate::client::{Error, Result};
 
@@ -13,7 +13,6 @@ use bytes::Bytes;
 use rayon::prelude::*;
 use self_encryption::{DataMap, EncryptedChunk};
 use serde::{Deserialize, Serialize};
-use std::path::Path;
 
 #[derive(Serialize, Deserialize)]
 pub(crate) enum DataMapLevel {
@@ -25,15 +24,6 @@ pub(crate) enum DataMapLevel {
     Additional(DataMap),
 }
 
-#[allow(unused)]
-pub(crate) fn encrypt_from_path(
-    path: &Path,
-    encryption: Option<&impl Encryption>,
-) -> Result<(BytesAddress, Vec<Chunk>)> {
-    let (data_map, encrypted_chunks) = encrypt_file(path)?;
-    pack(data_map, encrypted_chunks, encryption)
-}
-
 pub(crate) fn encrypt_blob(
     data: Bytes,
     encryption: Option<&impl Encryption>,
@@ -139,11 +129,6 @@ fn pack_data_map(data_map: DataMapLevel) -> Result<Bytes> {
     Ok(Bytes::from(serialize(&data_map)?))
 }
 
-fn encrypt_file(file: &Path) -> Result<(DataMap, Vec<EncryptedChunk>)> {
-    let bytes = Bytes::from(std::fs::read(file).map_err(Error::IoError)?);
-    self_encryption::encrypt(bytes).map_err(Error::SelfEncryption)
-}
-
 fn encrypt_data(bytes: Bytes) -> Result<(DataMap, Vec<EncryptedChunk>)> {
     self_encryption::encrypt(bytes).map_err(Error::SelfEncryption)
 }
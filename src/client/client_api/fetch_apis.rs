@@ -0,0 +1,240 @@
+// Copyright 2021 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::{files_container_apis::files_under, Client, FilesMap, MultimapKeyValue, NrsMap};
+use crate::client::Error;
+use crate::types::{
+    register::{Entry, EntryHash},
+    DataAddress, SafeKey,
+};
+use crate::url::{ContentType, Url, XorUrlBase};
+use bytes::Bytes;
+use std::collections::BTreeSet;
+
+/// Content a `safe://` Url points to, as fetched by [`Client::fetch`].
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug, PartialEq)]
+pub enum SafeData {
+    /// Bytes, e.g. the contents of a file of a FilesContainer,
+    /// along with their media type if the Url has one.
+    Bytes {
+        /// The media type of the Url, if any.
+        media_type: Option<String>,
+        /// The bytes.
+        data: Bytes,
+    },
+    /// The files of a FilesContainer under the path of the Url.
+    FilesContainer(FilesMap),
+    /// An NRS map.
+    NrsMap(NrsMap),
+    /// The items of a Multimap.
+    Multimap(BTreeSet<(EntryHash, MultimapKeyValue)>),
+    /// The entries of a Register in topological order, as of the version of the Url if it has one.
+    Register(Vec<(EntryHash, Entry)>),
+    /// A SafeKey, with the public key stored at its address.
+    SafeKey(SafeKey),
+}
+
+/// Content fetched by [`Client::fetch`], along with the Urls it was resolved through.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FetchedData {
+    /// The content.
+    pub data: SafeData,
+    /// The Urls resolved to reach the content, starting with the one fetched: e.g. an NRS Url,
+    /// the XOR-URL of the FilesContainer it's associated with, then the one of the file at
+    /// its path.
+    pub resolution_chain: Vec<Url>,
+}
+
+impl Client {
+    /// Fetch the content a `safe://` Url points to, be it an NRS Url or an XOR-URL.
+    ///
+    /// NRS Urls are first resolved to the XOR-URL their name is associated with.
+    /// The content is then read according to the data type and content type of the XOR-URL,
+    /// at its version (`?v=`) if it has one, the path of the Url pointing to a file or
+    /// directory in a FilesContainer.
+    ///
+    /// NRS maps and Multimaps are only read at their current version: their items are not
+    /// written on top of each other, so an item's version doesn't tell which other items were
+    /// there at the time. Fetching them with a version is refused with
+    /// [`Error::UnsupportedVersion`]. Versioned NRS Urls are resolved to their target at the
+    /// version though.
+    #[instrument(skip(self), level = "debug")]
+    pub async fn fetch(&self, url: &str) -> Result<FetchedData, Error> {
+        let mut url = Url::from_url(url)?;
+        let mut resolution_chain = vec![url.clone()];
+        if url.is_nrsurl() {
+            url = self.nrs_resolve(&url).await?;
+            resolution_chain.push(url.clone());
+        }
+
+        let path = url.path_decoded()?;
+        let data = match (url.address(), url.content_type()) {
+            (DataAddress::Register(_), ContentType::FilesContainer) => {
+                let mut files = self.get_files_container(&url).await?;
+                match files.remove(&path) {
+                    Some(file) => {
+                        let data = match file.address {
+                            Some(address) => {
+                                let file_url = Url::encode_bytes(
+                                    address,
+                                    ContentType::Raw,
+                                    XorUrlBase::Base32z,
+                                )?;
+                                resolution_chain.push(Url::from_xorurl(&file_url)?);
+                                self.read_bytes(address).await?
+                            }
+                            None => Bytes::new(),
+                        };
+                        SafeData::Bytes {
+                            media_type: None,
+                            data,
+                        }
+                    }
+                    None => {
+                        let files = files_under(files, &path);
+                        if files.is_empty() && !path.is_empty() {
+                            return Err(Error::NoSuchPath(path));
+                        }
+                        SafeData::FilesContainer(files)
+                    }
+                }
+            }
+            // Only FilesContainers have paths
+            _ if !path.is_empty() => return Err(Error::NoSuchPath(path)),
+            (DataAddress::Bytes(address), ContentType::Raw) => SafeData::Bytes {
+                media_type: None,
+                data: self.read_bytes(address).await?,
+            },
+            (DataAddress::Bytes(address), ContentType::MediaType(media_type)) => SafeData::Bytes {
+                media_type: Some(media_type),
+                data: self.read_bytes(address).await?,
+            },
+            (DataAddress::Register(_), ContentType::NrsMapContainer)
+            | (DataAddress::Register(_), ContentType::Multimap)
+                if url.content_version().is_some() =>
+            {
+                return Err(Error::UnsupportedVersion(url.to_string()))
+            }
+            (DataAddress::Register(_), ContentType::NrsMapContainer) => {
+                SafeData::NrsMap(self.nrs_get_map(&url).await?)
+            }
+            (DataAddress::Register(_), ContentType::Multimap) => {
+                SafeData::Multimap(self.read_multimap(&url).await?)
+            }
+            (DataAddress::Register(_), ContentType::Raw) => {
+                SafeData::Register(self.read_register_url(&url).await?)
+            }
+            (DataAddress::SafeKey(address), _) => {
+                SafeData::SafeKey(self.get_safe_key(address).await?)
+            }
+            _ => return Err(Error::UnsupportedContent(url.to_string())),
+        };
+
+        Ok(FetchedData {
+            data,
+            resolution_chain,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SafeData;
+    use crate::client::utils::test_utils::create_test_client;
+    use crate::client::Error;
+    use crate::retry_loop;
+    use crate::url::{ContentType, Scope, Url, VersionHash, XorUrlBase, NRS_MAP_TYPE_TAG};
+    use bytes::Bytes;
+    use eyre::{eyre, Result};
+    use tempfile::tempdir;
+    use xor_name::XorName;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn fetch_resolves_nrs_and_files_container_paths() -> Result<()> {
+        let client = create_test_client().await?;
+
+        let dir = tempdir()?;
+        std::fs::write(dir.path().join("index.html"), b"<html></html>")?;
+        let (container, _) = client
+            .upload_files_container(dir.path(), Scope::Public)
+            .await?;
+
+        let top_name = hex::encode(&XorName::random().0[..8]);
+        let name = Url::from_nrsurl(&format!("safe://site.{}", top_name))?;
+        let _ = client.nrs_create(&name).await?;
+        let _ = retry_loop!(client.nrs_associate(&name, &container));
+
+        let url = format!("safe://site.{}/index.html", top_name);
+        let fetched = retry_loop!(client.fetch(&url));
+        assert_eq!(fetched.resolution_chain.len(), 3);
+        assert_eq!(fetched.resolution_chain[0].to_string(), url);
+        assert_eq!(
+            fetched.data,
+            SafeData::Bytes {
+                media_type: None,
+                data: Bytes::from_static(b"<html></html>"),
+            }
+        );
+
+        // the container itself lists its files
+        match client.fetch(&container.to_string()).await?.data {
+            SafeData::FilesContainer(files) => assert!(files.contains_key("/index.html")),
+            data => return Err(eyre!("Unexpected data {:?}", data)),
+        }
+
+        let missing = format!("safe://site.{}/missing.html", top_name);
+        assert!(matches!(
+            client.fetch(&missing).await,
+            Err(Error::NoSuchPath(_))
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn fetch_nrs_map_refuses_versions() -> Result<()> {
+        let client = create_test_client().await?;
+
+        let top_name = hex::encode(&XorName::random().0[..8]);
+        let top = client
+            .nrs_create(&Url::from_nrsurl(&format!("safe://{}", top_name))?)
+            .await?;
+        let mut target = Url::from_url(&Url::encode_register(
+            XorName::random(),
+            15_000,
+            Scope::Public,
+            ContentType::Raw,
+            XorUrlBase::Base32z,
+        )?)?;
+        target.set_content_version(Some(VersionHash::from(&[1; 32])));
+        let sub = Url::from_nrsurl(&format!("safe://sub.{}", top_name))?;
+        let associated = retry_loop!(client.nrs_associate(&sub, &target));
+
+        let mut map_url = Url::from_url(&Url::encode_register(
+            top.xorname(),
+            NRS_MAP_TYPE_TAG,
+            Scope::Public,
+            ContentType::NrsMapContainer,
+            XorUrlBase::Base32z,
+        )?)?;
+        match retry_loop!(client.fetch(&map_url.to_string())).data {
+            SafeData::NrsMap(map) => assert_eq!(map["sub"], vec![target].into_iter().collect()),
+            data => return Err(eyre!("Unexpected data {:?}", data)),
+        }
+
+        // the map can't be read as of the version of one of its items
+        map_url.set_content_version(associated.content_version());
+        assert!(matches!(
+            client.fetch(&map_url.to_string()).await,
+            Err(Error::UnsupportedVersion(_))
+        ));
+
+        Ok(())
+    }
+}
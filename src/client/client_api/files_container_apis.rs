@@ -0,0 +1,462 @@
+// Copyright 2021 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::Client;
+use crate::client::{utils::retry, Error};
+use crate::types::{
+    register::{Entry, PrivatePermissions, PublicPermissions, User},
+    BytesAddress, DataAddress, RegisterAddress as Address,
+};
+use crate::url::{ContentType, Scope, Url, VersionHash, XorUrlBase, FILES_CONTAINER_TYPE_TAG};
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    path::{Component, Path},
+    time::{Duration, UNIX_EPOCH},
+};
+use tiny_keccak::{Hasher, Sha3};
+use tokio::io::AsyncReadExt;
+use xor_name::{XorName, XOR_NAME_LEN};
+
+/// Interval before the first retry of the initial write to a new FilesContainer,
+/// which then grows exponentially.
+const FILES_CONTAINER_RETRY_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Size of the blocks files are read in to hash their contents.
+const HASH_BLOCK_LEN: usize = 64 * 1024;
+
+/// A file of a FilesContainer.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct FileItem {
+    /// Address of the contents of the file, `None` if the file is empty.
+    pub address: Option<BytesAddress>,
+    /// Size of the file in bytes.
+    pub size: u64,
+    /// SHA3-256 hash of the contents of the file.
+    pub content_hash: XorName,
+    /// Last modification time of the file, in seconds since the Unix epoch.
+    pub modified: u64,
+}
+
+/// The files of a FilesContainer, by their path in it, e.g. `/dir/file.txt`.
+pub type FilesMap = BTreeMap<String, FileItem>;
+
+// A FilesContainer is a Register whose entries are the XOR-URLs of the FilesMaps of each of
+// its versions, stored as Bytes. Each version is written on top of all the current ones.
+impl Client {
+    /// Upload the files of the directory at `dir` and its subdirectories as a new
+    /// FilesContainer, returning its XOR-URL, versioned at the upload, along with its files.
+    #[instrument(skip(self), level = "debug")]
+    pub async fn upload_files_container(
+        &self,
+        dir: &Path,
+        scope: Scope,
+    ) -> Result<(Url, FilesMap), Error> {
+        let files = self.upload_files(dir, scope, &FilesMap::new()).await?;
+
+        let name = XorName::random();
+        let owner = self.public_key();
+        let address = match scope {
+            Scope::Public => {
+                let mut perms = BTreeMap::new();
+                let _ = perms.insert(User::Key(owner), PublicPermissions::new(true));
+                self.store_public_register(name, FILES_CONTAINER_TYPE_TAG, owner.into(), perms)
+                    .await?
+            }
+            Scope::Private => {
+                let mut perms = BTreeMap::new();
+                let _ = perms.insert(owner, PrivatePermissions::new(true, true));
+                self.store_private_register(name, FILES_CONTAINER_TYPE_TAG, owner.into(), perms)
+                    .await?
+            }
+        };
+
+        // The Register may not be readable right away
+        let entry = self.store_files_map(&files, scope).await?;
+        let append = retry(
+            || async {
+                self.append_to_register(address, entry.clone())
+                    .await
+                    .map_err(|error| {
+                        debug!("Writing to FilesContainer failed, will retry: {:?}", error);
+                        backoff::Error::Transient(error)
+                    })
+            },
+            FILES_CONTAINER_RETRY_INTERVAL,
+            self.query_timeout,
+        )
+        .await?;
+
+        let mut url = Url::from_xorurl(&Url::encode_register(
+            name,
+            FILES_CONTAINER_TYPE_TAG,
+            scope,
+            ContentType::FilesContainer,
+            XorUrlBase::Base32z,
+        )?)?;
+        url.set_content_version(Some(VersionHash::from(&append.hash)));
+
+        Ok((url, files))
+    }
+
+    /// Sync a FilesContainer with the directory at `dir`, so that its new version holds
+    /// the files of the directory, returning its XOR-URL versioned at the sync along with
+    /// its files.
+    ///
+    /// Files whose contents are unchanged, as told by their size and hash, are not uploaded again.
+    /// The files are all read to hash them, modification times being too coarse to tell
+    /// whether a file was changed.
+    /// The new version is written on top of all the current ones, resolving any conflict
+    /// present at the time it is written.
+    #[instrument(skip(self), level = "debug")]
+    pub async fn sync_files_container(
+        &self,
+        url: &Url,
+        dir: &Path,
+    ) -> Result<(Url, FilesMap), Error> {
+        let address = files_container_address(url)?;
+
+        let mut known = FilesMap::new();
+        for (_, entry) in self.read_register(address).await? {
+            known.extend(self.read_files_map(&entry).await?);
+        }
+
+        let files = self.upload_files(dir, url.scope(), &known).await?;
+        let entry = self.store_files_map(&files, url.scope()).await?;
+        let append = self.append_to_register(address, entry).await?;
+
+        let mut url = url.clone();
+        url.set_path("");
+        url.set_content_version(Some(VersionHash::from(&append.hash)));
+
+        Ok((url, files))
+    }
+
+    /// Get the files of a FilesContainer, at the version of the Url if it has one (`?v=`),
+    /// otherwise at its current version.
+    #[instrument(skip(self), level = "debug")]
+    pub async fn get_files_container(&self, url: &Url) -> Result<FilesMap, Error> {
+        let address = files_container_address(url)?;
+
+        let entry = match url.content_version() {
+            Some(version) => {
+                self.get_register_entry(address, version.entry_hash())
+                    .await?
+            }
+            None => {
+                let mut current = self.read_register(address).await?.into_iter();
+                match (current.next(), current.next()) {
+                    (Some((_, entry)), None) => entry,
+                    (None, _) => return Ok(FilesMap::new()),
+                    _ => return Err(Error::FilesContainerConflict(url.to_string())),
+                }
+            }
+        };
+
+        self.read_files_map(&entry).await
+    }
+
+    /// List the files of a FilesContainer under the path of the Url,
+    /// all of them if it has no path.
+    #[instrument(skip(self), level = "debug")]
+    pub async fn list_files_container(&self, url: &Url) -> Result<FilesMap, Error> {
+        let files = self.get_files_container(url).await?;
+        Ok(files_under(files, &url.path_decoded()?))
+    }
+
+    /// Get the file of a FilesContainer at the path of the Url.
+    #[instrument(skip(self), level = "debug")]
+    pub async fn resolve_files_container_path(&self, url: &Url) -> Result<FileItem, Error> {
+        let path = url.path_decoded()?;
+
+        self.get_files_container(url)
+            .await?
+            .remove(&path)
+            .ok_or(Error::NoSuchPath(path))
+    }
+
+    /// Fetch the contents of the file of a FilesContainer at the path of the Url.
+    #[instrument(skip(self), level = "debug")]
+    pub async fn fetch_files_container_path(&self, url: &Url) -> Result<Bytes, Error> {
+        match self.resolve_files_container_path(url).await?.address {
+            Some(address) => self.read_bytes(address).await,
+            None => Ok(Bytes::new()),
+        }
+    }
+
+    // Uploads the files under `root`, reusing the items of `known` files which are unchanged.
+    async fn upload_files(
+        &self,
+        root: &Path,
+        scope: Scope,
+        known: &FilesMap,
+    ) -> Result<FilesMap, Error> {
+        let mut files = FilesMap::new();
+        let mut dirs = vec![root.to_path_buf()];
+
+        while let Some(dir) = dirs.pop() {
+            let mut entries = tokio::fs::read_dir(&dir).await?;
+            while let Some(entry) = entries.next_entry().await? {
+                let file_type = entry.file_type().await?;
+                if file_type.is_dir() {
+                    dirs.push(entry.path());
+                    continue;
+                } else if !file_type.is_file() {
+                    debug!("Skipping {:?}, which is not a file", entry.path());
+                    continue;
+                }
+
+                let path = container_path(root, &entry.path())?;
+                let metadata = entry.metadata().await?;
+                let size = metadata.len();
+                let modified = metadata
+                    .modified()
+                    .ok()
+                    .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                    .map(|duration| duration.as_secs())
+                    .unwrap_or_default();
+
+                let content_hash = content_hash(&entry.path()).await?;
+
+                let item = match known.get(&path) {
+                    Some(item) if item.size == size && item.content_hash == content_hash => {
+                        FileItem {
+                            modified,
+                            ..item.clone()
+                        }
+                    }
+                    _ => FileItem {
+                        address: if size == 0 {
+                            None
+                        } else {
+                            Some(self.upload_from_path(&entry.path(), scope).await?)
+                        },
+                        size,
+                        content_hash,
+                        modified,
+                    },
+                };
+                let _ = files.insert(path, item);
+            }
+        }
+
+        Ok(files)
+    }
+
+    // Stores the FilesMap as Bytes, returning the Register entry pointing to it.
+    async fn store_files_map(&self, files: &FilesMap, scope: Scope) -> Result<Entry, Error> {
+        let address = self
+            .upload(Bytes::from(bincode::serialize(files)?), scope)
+            .await?;
+        let url = Url::encode_bytes(address, ContentType::Raw, XorUrlBase::Base32z)?;
+
+        Ok(Entry::Url(Url::from_xorurl(&url)?))
+    }
+
+    async fn read_files_map(&self, entry: &Entry) -> Result<FilesMap, Error> {
+        match entry.as_url().map(Url::address) {
+            Some(DataAddress::Bytes(address)) => {
+                let bytes = self.read_bytes(address).await?;
+                Ok(bincode::deserialize(&bytes)?)
+            }
+            _ => Err(Error::Generic(
+                "Register entry is not a FilesContainer version".to_string(),
+            )),
+        }
+    }
+}
+
+// Address of the Register holding the FilesContainer the Url points to.
+fn files_container_address(url: &Url) -> Result<Address, Error> {
+    match (url.address(), url.content_type()) {
+        (DataAddress::Register(address), ContentType::FilesContainer) => Ok(address),
+        _ => Err(Error::NotAFilesContainerUrl(url.to_string())),
+    }
+}
+
+// The files under the `dir` path of the container, all of them if it's empty.
+pub(super) fn files_under(files: FilesMap, dir: &str) -> FilesMap {
+    let dir = dir.trim_end_matches('/');
+    let prefix = format!("{}/", dir);
+
+    files
+        .into_iter()
+        .filter(|(path, _)| dir.is_empty() || path == dir || path.starts_with(&prefix))
+        .collect()
+}
+
+// SHA3-256 hash of the contents of the file at `path`, read a block at a time.
+async fn content_hash(path: &Path) -> Result<XorName, Error> {
+    let mut file = tokio::fs::File::open(path).await?;
+    let mut hasher = Sha3::v256();
+    let mut block = vec![0; HASH_BLOCK_LEN];
+    loop {
+        let read = file.read(&mut block).await?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&block[..read]);
+    }
+
+    let mut hash = [0; XOR_NAME_LEN];
+    hasher.finalize(&mut hash);
+    Ok(XorName(hash))
+}
+
+// Path of a file in the container, relative to the uploaded directory.
+fn container_path(root: &Path, path: &Path) -> Result<String, Error> {
+    let relative = path
+        .strip_prefix(root)
+        .map_err(|_| Error::Generic(format!("{:?} is not under {:?}", path, root)))?;
+
+    let mut container_path = String::new();
+    for component in relative.components() {
+        match component {
+            Component::Normal(name) => {
+                let name = name
+                    .to_str()
+                    .ok_or_else(|| Error::Generic(format!("{:?} is not valid UTF-8", path)))?;
+                container_path.push('/');
+                container_path.push_str(name);
+            }
+            _ => return Err(Error::Generic(format!("Unexpected path {:?}", path))),
+        }
+    }
+
+    Ok(container_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        container_path, content_hash, files_container_address, files_under, FileItem, FilesMap,
+    };
+    use crate::client::utils::test_utils::{create_test_client, init_test_logger};
+    use crate::retry_loop_for_pattern;
+    use crate::url::{ContentType, Scope, Url, XorUrlBase};
+    use eyre::Result;
+    use std::path::Path;
+    use tempfile::tempdir;
+    use xor_name::XorName;
+
+    #[test]
+    fn files_container_paths() -> Result<()> {
+        let root = Path::new("/tmp/upload");
+        assert_eq!(
+            container_path(root, &root.join("dir").join("file.txt"))?,
+            "/dir/file.txt"
+        );
+        assert!(container_path(root, Path::new("/tmp/other/file.txt")).is_err());
+
+        let item = FileItem {
+            address: None,
+            size: 0,
+            content_hash: XorName::random(),
+            modified: 0,
+        };
+        let files: FilesMap = vec!["/dir/a", "/dir/sub/b", "/dirs/c", "/d"]
+            .into_iter()
+            .map(|path| (path.to_string(), item.clone()))
+            .collect();
+        let under = files_under(files.clone(), "/dir/");
+        assert_eq!(
+            under.keys().collect::<Vec<_>>(),
+            vec!["/dir/a", "/dir/sub/b"]
+        );
+        assert_eq!(files_under(files.clone(), ""), files);
+
+        let url = Url::from_url(&Url::encode_register(
+            XorName::random(),
+            15_000,
+            Scope::Public,
+            ContentType::Raw,
+            XorUrlBase::Base32z,
+        )?)?;
+        assert!(files_container_address(&url).is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn files_container_content_hash() -> Result<()> {
+        let dir = tempdir()?;
+        let path = dir.path().join("file");
+
+        // larger than a block, so that it's hashed in parts
+        let contents = vec![7; super::HASH_BLOCK_LEN + 1];
+        std::fs::write(&path, &contents)?;
+        assert_eq!(content_hash(&path).await?, XorName::from_content(&contents));
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn files_container_upload_and_sync() -> Result<()> {
+        init_test_logger();
+        let _outer_span = tracing::info_span!("test__files_container_upload_and_sync").entered();
+
+        let client = create_test_client().await?;
+
+        let dir = tempdir()?;
+        std::fs::create_dir(dir.path().join("sub"))?;
+        std::fs::write(dir.path().join("index.html"), b"<html></html>")?;
+        std::fs::write(dir.path().join("sub").join("data.txt"), b"some data")?;
+        std::fs::write(dir.path().join("empty"), b"")?;
+
+        let (url, files) = client
+            .upload_files_container(dir.path(), Scope::Public)
+            .await?;
+        assert_eq!(files.len(), 3);
+
+        let mut file_url = url.clone();
+        file_url.set_path("/sub/data.txt");
+        assert_eq!(
+            client.fetch_files_container_path(&file_url).await?,
+            &b"some data"[..]
+        );
+        file_url.set_path("/empty");
+        assert!(client
+            .fetch_files_container_path(&file_url)
+            .await?
+            .is_empty());
+
+        let mut sub_url = url.clone();
+        sub_url.set_path("/sub");
+        let listed = client.list_files_container(&sub_url).await?;
+        assert_eq!(listed.keys().collect::<Vec<_>>(), vec!["/sub/data.txt"]);
+
+        // syncing uploads the changes, the first version staying as it was
+        std::fs::remove_file(dir.path().join("empty"))?;
+        std::fs::write(dir.path().join("new.txt"), b"new file")?;
+        // a change keeping the size of the file, likely within the same second
+        std::fs::write(dir.path().join("sub").join("data.txt"), b"more data")?;
+        let (synced_url, files) = client.sync_files_container(&url, dir.path()).await?;
+        assert_eq!(files.len(), 3);
+        assert!(files.contains_key("/new.txt"));
+        assert!(!files.contains_key("/empty"));
+        file_url.set_path("/sub/data.txt");
+        file_url.set_content_version(synced_url.content_version());
+        assert_eq!(
+            client.fetch_files_container_path(&file_url).await?,
+            &b"more data"[..]
+        );
+
+        let mut current = url.clone();
+        current.set_content_version(None);
+        let current = retry_loop_for_pattern!(client.get_files_container(&current), Ok(current) if current.contains_key("/new.txt"))?;
+        assert_eq!(current, files);
+        assert_eq!(client.get_files_container(&synced_url).await?, files);
+        assert!(client
+            .get_files_container(&url)
+            .await?
+            .contains_key("/empty"));
+
+        Ok(())
+    }
+}
@@ -7,23 +7,48 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 mod blob_apis;
+mod chunk_cache;
 mod commands;
 mod data;
+mod fetch_apis;
+mod files_container_apis;
+mod multimap_apis;
+mod multisig_apis;
+mod nrs_apis;
+mod progress;
 mod queries;
 mod register_apis;
+mod safe_key_apis;
+mod subscription_apis;
+mod upload_journal;
+
+pub use chunk_cache::ChunkCacheStats;
+pub use fetch_apis::{FetchedData, SafeData};
+pub use files_container_apis::{FileItem, FilesMap};
+pub use multimap_apis::MultimapKeyValue;
+pub use multisig_apis::MultisigRegisterOp;
+pub use nrs_apis::NrsMap;
+pub use progress::TransferProgress;
+pub use register_apis::RegisterAppend;
+pub use subscription_apis::RegisterEdits;
 
 use crate::client::{connections::Session, errors::Error, Config};
 use crate::messaging::data::{CmdError, DataQuery, ServiceMsg};
 use crate::types::{ChunkAddress, Keypair, PublicKey};
+use chunk_cache::ChunkCache;
 
 use crate::messaging::{ServiceAuth, WireMsg};
 use itertools::Itertools;
 use rand::rngs::OsRng;
 use std::collections::BTreeSet;
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::{
-    sync::{mpsc::Receiver, RwLock},
+    sync::{
+        mpsc::{Receiver, UnboundedSender},
+        RwLock,
+    },
     time::Duration,
 };
 use tracing::{debug, info};
@@ -36,6 +61,11 @@ pub struct Client {
     incoming_errors: Arc<RwLock<Receiver<CmdError>>>,
     session: Session,
     pub(crate) query_timeout: Duration,
+    chunk_fetch_concurrency: usize,
+    root_dir: PathBuf,
+    chunk_cache: Option<Arc<ChunkCache>>,
+    progress: Option<UnboundedSender<TransferProgress>>,
+    deduplicate_uploads: bool,
 }
 
 /// Easily manage connections to/from The Safe Network with the client and its APIs.
@@ -87,6 +117,11 @@ impl Client {
             hex::encode(config.genesis_key.to_bytes())
         );
 
+        let chunk_cache = match config.chunk_cache_capacity {
+            Some(capacity) => Some(Arc::new(ChunkCache::open(&config.root_dir, capacity)?)),
+            None => None,
+        };
+
         // Session wait time. We want this relative to query timeout for now. Which defaults to 90s
         let standard_wait = config.query_timeout / 10;
 
@@ -106,6 +141,11 @@ impl Client {
             session,
             incoming_errors: Arc::new(RwLock::new(err_receiver)),
             query_timeout: config.query_timeout,
+            chunk_fetch_concurrency: usize::max(1, config.chunk_fetch_concurrency),
+            root_dir: config.root_dir,
+            chunk_cache,
+            progress: None,
+            deduplicate_uploads: false,
         };
 
         // TODO: The message being sent below is a temporary solution to fetch network info for
@@ -162,14 +202,20 @@ impl Client {
     pub fn public_key(&self) -> PublicKey {
         self.keypair().public_key()
     }
+
+    /// Return the statistics of the client's chunk cache, if it has been enabled in the config.
+    pub fn chunk_cache_stats(&self) -> Option<ChunkCacheStats> {
+        self.chunk_cache.as_ref().map(|cache| cache.stats())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::client::utils::test_utils::{
-        create_test_client, create_test_client_with, init_test_logger,
+        create_test_client, create_test_client_with, init_test_logger, read_network_conn_info,
     };
+    use crate::retry_loop;
     use crate::types::utils::random_bytes;
     use crate::url::Scope;
     use eyre::Result;
@@ -215,6 +261,30 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn clients_share_the_chunk_cache_of_their_root_dir() -> Result<()> {
+        init_test_logger();
+
+        let root_dir = tempfile::tempdir()?;
+        let (genesis_key, bootstrap_nodes) = read_network_conn_info()?;
+        let mut config = Config::new(Some(root_dir.path()), None, genesis_key, None, None).await;
+        config.chunk_cache_capacity = Some(10 * 1024 * 1024);
+
+        let client = Client::new(config.clone(), bootstrap_nodes.clone(), None).await?;
+        let other = Client::new(config, bootstrap_nodes, None).await?;
+
+        let bytes = random_bytes(self_encryption::MIN_ENCRYPTABLE_BYTES / 2);
+        let address = client.upload(bytes.clone(), Scope::Public).await?;
+        let read = retry_loop!(client.read_bytes(address));
+        assert_eq!(read, bytes);
+
+        // the chunk read by the first client is served to the other from the cache
+        assert_eq!(other.read_bytes(address).await?, bytes);
+        assert_eq!(other.chunk_cache_stats().map(|stats| stats.hits), Some(1));
+
+        Ok(())
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn long_lived_connection_survives() -> Result<()> {
         init_test_logger();
@@ -0,0 +1,293 @@
+// Copyright 2021 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::Client;
+use crate::client::Error;
+use crate::types::{
+    register::{Entry, EntryHash, PrivatePermissions, PublicPermissions, User},
+    DataAddress, RegisterAddress as Address,
+};
+use crate::url::{ContentType, Scope, Url, XorUrlBase};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use xor_name::XorName;
+
+/// A key and one of its values in a Multimap.
+pub type MultimapKeyValue = (Vec<u8>, Vec<u8>);
+
+// How the Multimap items are encoded in the entries of the Register holding it.
+//
+// Each current entry of the Register is an item of the Multimap, so that items inserted
+// concurrently are all kept. Items are replaced or removed by writing an entry on top of them,
+// a tombstone in the case of removals.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+enum MultimapEntry {
+    KeyValue(MultimapKeyValue),
+    Tombstone,
+}
+
+impl MultimapEntry {
+    fn encode(&self) -> Result<Entry, Error> {
+        Ok(Entry::Bytes(bincode::serialize(self)?))
+    }
+
+    fn decode(entry: &Entry) -> Result<Self, Error> {
+        match entry {
+            Entry::Bytes(bytes) => Ok(bincode::deserialize(bytes)?),
+            _ => Err(Error::Generic(
+                "Register entry is not a Multimap item".to_string(),
+            )),
+        }
+    }
+}
+
+impl Client {
+    /// Store a new Multimap on the Network, a map of keys to multiple values held in a Register,
+    /// returning its XOR-URL.
+    ///
+    /// Only the client's key can write to the Multimap, until other users are granted permissions
+    /// on the Register with [`Client::set_register_permissions_for_user`]. Public Multimaps can be
+    /// read by anyone, private ones only by their owner.
+    #[instrument(skip(self), level = "debug")]
+    pub async fn store_multimap(
+        &self,
+        name: XorName,
+        tag: u64,
+        scope: Scope,
+    ) -> Result<Url, Error> {
+        let owner = self.public_key();
+        match scope {
+            Scope::Public => {
+                let mut perms = BTreeMap::new();
+                let _ = perms.insert(User::Key(owner), PublicPermissions::new(true));
+                let _ = self
+                    .store_public_register(name, tag, owner.into(), perms)
+                    .await?;
+            }
+            Scope::Private => {
+                let mut perms = BTreeMap::new();
+                let _ = perms.insert(owner, PrivatePermissions::new(true, true));
+                let _ = self
+                    .store_private_register(name, tag, owner.into(), perms)
+                    .await?;
+            }
+        }
+
+        let xorurl =
+            Url::encode_register(name, tag, scope, ContentType::Multimap, XorUrlBase::Base32z)?;
+
+        Ok(Url::from_url(&xorurl)?)
+    }
+
+    /// Insert a key-value pair into a Multimap, replacing the items with the given hashes,
+    /// returning the hash of the new item.
+    ///
+    /// Values inserted for the same key without replacing each other, e.g. concurrently
+    /// by different writers, are all kept.
+    #[instrument(skip(self, key_value), level = "debug")]
+    pub async fn multimap_insert(
+        &self,
+        url: &Url,
+        key_value: MultimapKeyValue,
+        replace: BTreeSet<EntryHash>,
+    ) -> Result<EntryHash, Error> {
+        let address = multimap_address(url)?;
+        let entry = MultimapEntry::KeyValue(key_value).encode()?;
+
+        self.write_to_register(address, entry, replace).await
+    }
+
+    /// Remove the items with the given hashes from a Multimap,
+    /// returning the hash of the tombstone recording the removal.
+    #[instrument(skip(self), level = "debug")]
+    pub async fn multimap_remove(
+        &self,
+        url: &Url,
+        to_remove: BTreeSet<EntryHash>,
+    ) -> Result<EntryHash, Error> {
+        let address = multimap_address(url)?;
+        let entry = MultimapEntry::Tombstone.encode()?;
+
+        self.write_to_register(address, entry, to_remove).await
+    }
+
+    /// Read all the items of a Multimap.
+    #[instrument(skip(self), level = "debug")]
+    pub async fn read_multimap(
+        &self,
+        url: &Url,
+    ) -> Result<BTreeSet<(EntryHash, MultimapKeyValue)>, Error> {
+        let address = multimap_address(url)?;
+
+        let mut items = BTreeSet::new();
+        for (hash, entry) in self.read_register(address).await? {
+            if let MultimapEntry::KeyValue(key_value) = MultimapEntry::decode(&entry)? {
+                let _ = items.insert((hash, key_value));
+            }
+        }
+
+        Ok(items)
+    }
+
+    /// Read all the values of a key in a Multimap, along with the hashes of their items.
+    #[instrument(skip(self, key), level = "debug")]
+    pub async fn multimap_get_by_key(
+        &self,
+        url: &Url,
+        key: &[u8],
+    ) -> Result<BTreeSet<(EntryHash, Vec<u8>)>, Error> {
+        Ok(self
+            .read_multimap(url)
+            .await?
+            .into_iter()
+            .filter(|(_, (item_key, _))| item_key == key)
+            .map(|(hash, (_, value))| (hash, value))
+            .collect())
+    }
+
+    /// Get an item of a Multimap by its hash, even if it was replaced or removed since.
+    #[instrument(skip(self), level = "debug")]
+    pub async fn multimap_get_by_hash(
+        &self,
+        url: &Url,
+        hash: EntryHash,
+    ) -> Result<MultimapKeyValue, Error> {
+        let address = multimap_address(url)?;
+        let entry = self.get_register_entry(address, hash).await?;
+
+        match MultimapEntry::decode(&entry)? {
+            MultimapEntry::KeyValue(key_value) => Ok(key_value),
+            MultimapEntry::Tombstone => Err(Error::from(crate::types::Error::NoSuchEntry)),
+        }
+    }
+}
+
+// Address of the Register holding the Multimap the Url points to.
+// NRS maps are Multimaps of sub-names to targets.
+fn multimap_address(url: &Url) -> Result<Address, Error> {
+    match (url.address(), url.content_type()) {
+        (DataAddress::Register(address), ContentType::Multimap)
+        | (DataAddress::Register(address), ContentType::NrsMapContainer) => Ok(address),
+        _ => Err(Error::NotAMultimapUrl(url.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{multimap_address, MultimapEntry};
+    use crate::client::utils::test_utils::{
+        create_test_client, init_test_logger, run_w_backoff_delayed,
+    };
+    use crate::types::register::{Entry, Permissions, PublicPermissions, User};
+    use crate::url::{ContentType, Scope, Url, XorUrlBase};
+    use crate::{retry_loop, retry_loop_for_pattern};
+    use eyre::Result;
+    use std::collections::BTreeSet;
+    use xor_name::XorName;
+
+    #[test]
+    fn multimap_entries_encoding() -> Result<()> {
+        let item = MultimapEntry::KeyValue((b"key".to_vec(), b"value".to_vec()));
+        assert_eq!(MultimapEntry::decode(&item.encode()?)?, item);
+
+        let tombstone = MultimapEntry::Tombstone;
+        assert_eq!(MultimapEntry::decode(&tombstone.encode()?)?, tombstone);
+
+        let url = Url::from_url(&Url::encode_register(
+            XorName::random(),
+            15_000,
+            Scope::Public,
+            ContentType::Raw,
+            XorUrlBase::Base32z,
+        )?)?;
+        assert!(MultimapEntry::decode(&Entry::Url(url.clone())).is_err());
+        assert!(multimap_address(&url).is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn multimap_concurrent_writers() -> Result<()> {
+        init_test_logger();
+        let _outer_span = tracing::info_span!("test__multimap_concurrent_writers").entered();
+
+        let client = create_test_client().await?;
+        let other_client = create_test_client().await?;
+
+        let url = client
+            .store_multimap(XorName::random(), 15_000, Scope::Public)
+            .await?;
+        let key = b"key".to_vec();
+
+        // the owner lets the other client write to the Multimap too
+        let address = multimap_address(&url)?;
+        let other = other_client.public_key();
+        let perms = Permissions::Public(PublicPermissions::new(true));
+        run_w_backoff_delayed(
+            || async {
+                Ok(client
+                    .set_register_permissions_for_user(address, User::Key(other), perms.clone())
+                    .await?)
+            },
+            10,
+            1,
+        )
+        .await?;
+        let _ = retry_loop_for_pattern!(other_client.get_register_permissions_for_user(address, other), Ok(other_perms) if *other_perms == perms)?;
+
+        // both writers insert a value for the key concurrently, neither replacing the other
+        let (first, second) = tokio::join!(
+            async {
+                retry_loop!(client.multimap_insert(
+                    &url,
+                    (key.clone(), b"first".to_vec()),
+                    BTreeSet::new()
+                ))
+            },
+            async {
+                retry_loop!(other_client.multimap_insert(
+                    &url,
+                    (key.clone(), b"second".to_vec()),
+                    BTreeSet::new()
+                ))
+            }
+        );
+
+        let values = retry_loop_for_pattern!(client.multimap_get_by_key(&url, &key), Ok(values) if values.len() == 2)?;
+        let expected: BTreeSet<_> = vec![(first, b"first".to_vec()), (second, b"second".to_vec())]
+            .into_iter()
+            .collect();
+        assert_eq!(values, expected);
+
+        // one of them then replaces both values
+        let replacement = client
+            .multimap_insert(
+                &url,
+                (key.clone(), b"third".to_vec()),
+                vec![first, second].into_iter().collect(),
+            )
+            .await?;
+        let values = retry_loop_for_pattern!(client.multimap_get_by_key(&url, &key), Ok(values) if values.len() == 1)?;
+        assert_eq!(
+            values,
+            vec![(replacement, b"third".to_vec())].into_iter().collect()
+        );
+        assert_eq!(
+            client.multimap_get_by_hash(&url, first).await?,
+            (key.clone(), b"first".to_vec())
+        );
+
+        // and the other removes it
+        let _ = other_client
+            .multimap_remove(&url, vec![replacement].into_iter().collect())
+            .await?;
+        let _ = retry_loop_for_pattern!(client.read_multimap(&url), Ok(items) if items.is_empty())?;
+
+        Ok(())
+    }
+}
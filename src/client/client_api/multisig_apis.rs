@@ -0,0 +1,289 @@
+// Copyright 2021 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::Client;
+use crate::client::Error;
+use crate::messaging::data::{DataCmd, RegisterWrite};
+use crate::types::{
+    register::{Entry, EntryHash, Policy},
+    Error as DtError, Keypair, PublicKey, RegisterAddress as Address, Signature, SignatureShare,
+};
+use bls::PublicKeySet;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A Register operation on behalf of a group of owners holding shares of the keys of
+/// a `PublicKeySet`, collecting the signature shares of its members.
+///
+/// It is created by one of the members, passed around for the others to sign with
+/// [`MultisigRegisterOp::sign`], and can be sent to the network once more than
+/// `threshold` shares were added.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MultisigRegisterOp {
+    cmd: RegisterWrite,
+    key_set: PublicKeySet,
+    shares: BTreeMap<usize, bls::SignatureShare>,
+}
+
+impl MultisigRegisterOp {
+    fn new(cmd: RegisterWrite, key_set: PublicKeySet) -> Self {
+        Self {
+            cmd,
+            key_set,
+            shares: BTreeMap::new(),
+        }
+    }
+
+    /// Address of the Register the operation targets.
+    pub fn address(&self) -> Address {
+        *self.cmd.address()
+    }
+
+    /// The keys of the group of owners.
+    pub fn key_set(&self) -> &PublicKeySet {
+        &self.key_set
+    }
+
+    /// Returns the bytes each member of the group signs.
+    pub fn bytes_to_sign(&self) -> Result<Vec<u8>, Error> {
+        match &self.cmd {
+            RegisterWrite::Edit(op) => Ok(bincode::serialize(&op.crdt_op)?),
+            RegisterWrite::EditPolicy(op) => Ok(op.bytes_to_sign()?),
+            _ => Err(Error::Generic(
+                "Only edits can be signed by a group of owners".to_string(),
+            )),
+        }
+    }
+
+    /// Signs the operation with the share of the keys held by `keypair`.
+    pub fn sign(&self, keypair: &Keypair) -> Result<SignatureShare, Error> {
+        match keypair {
+            Keypair::BlsShare(share) if share.public_key_set == self.key_set => {
+                match keypair.sign(&self.bytes_to_sign()?) {
+                    Signature::BlsShare(share) => Ok(share),
+                    _ => Err(Error::NetworkDataError(DtError::SigningKeyTypeMismatch)),
+                }
+            }
+            _ => Err(Error::NetworkDataError(DtError::SigningKeyTypeMismatch)),
+        }
+    }
+
+    /// Adds the signature share of a member of the group, once verified.
+    /// Returns whether enough shares were collected for the operation to be sent.
+    pub fn add_share(&mut self, share: SignatureShare) -> Result<bool, Error> {
+        let bytes = self.bytes_to_sign()?;
+        if !self
+            .key_set
+            .public_key_share(share.index)
+            .verify(&share.share, &bytes)
+        {
+            return Err(Error::NetworkDataError(DtError::InvalidSignature));
+        }
+        let _ = self.shares.insert(share.index, share.share);
+
+        Ok(self.is_complete())
+    }
+
+    /// Whether enough shares were collected for the operation to be sent.
+    pub fn is_complete(&self) -> bool {
+        self.shares.len() > self.key_set.threshold()
+    }
+
+    /// Combines the shares into the signature of the group, returning the signed command.
+    fn into_cmd(self) -> Result<DataCmd, Error> {
+        let signature = self
+            .key_set
+            .combine_signatures(self.shares.iter().map(|(index, share)| (*index, share)))
+            .map_err(|err| Error::Aggregation(err.to_string()))?;
+        let signature = Some(Signature::Bls(signature));
+
+        let cmd = match self.cmd {
+            RegisterWrite::Edit(mut op) => {
+                op.signature = signature;
+                RegisterWrite::Edit(op)
+            }
+            RegisterWrite::EditPolicy(mut op) => {
+                op.signature = signature;
+                RegisterWrite::EditPolicy(op)
+            }
+            cmd => cmd,
+        };
+
+        Ok(DataCmd::Register(cmd))
+    }
+}
+
+impl Client {
+    /// Prepare writing an entry to a Register owned by a group of owners, on top of
+    /// the given children, returning the hash of the entry and the operation for the
+    /// members of the group to sign.
+    ///
+    /// Send it with [`Client::send_multisig_register_op`] once signed by enough members.
+    #[instrument(skip(self), level = "debug")]
+    pub async fn prepare_multisig_register_write(
+        &self,
+        address: Address,
+        entry: Entry,
+        children: BTreeSet<EntryHash>,
+    ) -> Result<(EntryHash, MultisigRegisterOp), Error> {
+        let mut register = self.get_register(address).await?;
+        let key_set = register.owner_type().public_key_set()?;
+
+        let (hash, op) =
+            register.write_as(entry, children, PublicKey::Bls(key_set.public_key()))?;
+
+        Ok((
+            hash,
+            MultisigRegisterOp::new(RegisterWrite::Edit(op), key_set),
+        ))
+    }
+
+    /// Prepare replacing the policy of a Register owned by a group of owners,
+    /// returning the operation for the members of the group to sign.
+    ///
+    /// Send it with [`Client::send_multisig_register_op`] once signed by enough members.
+    #[instrument(skip(self), level = "debug")]
+    pub async fn prepare_multisig_register_policy_edit(
+        &self,
+        address: Address,
+        policy: Policy,
+    ) -> Result<MultisigRegisterOp, Error> {
+        let mut register = self.get_register(address).await?;
+        let key_set = register.owner_type().public_key_set()?;
+
+        let op = register.edit_policy(policy, Some(PublicKey::Bls(key_set.public_key())))?;
+
+        Ok(MultisigRegisterOp::new(
+            RegisterWrite::EditPolicy(op),
+            key_set,
+        ))
+    }
+
+    /// Send a Register operation signed by enough members of the group of owners.
+    ///
+    /// The client sending it must itself be allowed to write to the Register,
+    /// so the owners are expected to grant write permissions to the members' keys.
+    #[instrument(skip(self), level = "debug")]
+    pub async fn send_multisig_register_op(&self, op: MultisigRegisterOp) -> Result<(), Error> {
+        let cmd = op.into_cmd()?;
+        self.send_cmd(cmd).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MultisigRegisterOp;
+    use crate::client::utils::test_utils::create_test_client;
+    use crate::messaging::data::{DataCmd, RegisterWrite};
+    use crate::types::{
+        register::{Entry, PrivatePolicy, PublicPermissions, Register, User},
+        Keypair, PublicKey,
+    };
+    use crate::{retry_loop, retry_loop_for_pattern};
+    use bls::SecretKeySet;
+    use eyre::{eyre, Result};
+    use rand::rngs::OsRng;
+    use std::collections::{BTreeMap, BTreeSet};
+    use xor_name::XorName;
+
+    fn multisig_keypairs(threshold: usize, count: usize) -> Vec<Keypair> {
+        let secret_key_set = SecretKeySet::random(threshold, &mut OsRng);
+        (0..count)
+            .map(|index| {
+                Keypair::new_bls_share(
+                    index,
+                    secret_key_set.secret_key_share(index),
+                    secret_key_set.public_keys(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn multisig_register_op_needs_enough_shares() -> Result<()> {
+        let keypairs = multisig_keypairs(1, 3);
+        let key_set = match &keypairs[0] {
+            Keypair::BlsShare(share) => share.public_key_set.clone(),
+            _ => unreachable!(),
+        };
+        let group_key = PublicKey::Bls(key_set.public_key());
+
+        let policy = PrivatePolicy {
+            owner: key_set.clone().into(),
+            permissions: BTreeMap::new(),
+        };
+        let mut register =
+            Register::new_private(group_key, XorName::random(), 43_000, Some(policy));
+        let mut replica = register.clone();
+
+        let entry = Entry::from(b"signed by the group".to_vec());
+        let (_, op) = register.write_as(entry, BTreeSet::new(), group_key)?;
+        let mut multisig_op = MultisigRegisterOp::new(RegisterWrite::Edit(op), key_set);
+
+        // A share from a keypair of another group is rejected
+        let other_keypairs = multisig_keypairs(1, 3);
+        assert!(multisig_op.sign(&other_keypairs[0]).is_err());
+
+        assert!(!multisig_op.add_share(multisig_op.sign(&keypairs[0])?)?);
+        assert!(multisig_op.clone().into_cmd().is_err());
+
+        assert!(multisig_op.add_share(multisig_op.sign(&keypairs[2])?)?);
+        match multisig_op.into_cmd()? {
+            DataCmd::Register(RegisterWrite::Edit(op)) => {
+                replica.apply_op(op)?;
+            }
+            cmd => return Err(eyre!("Unexpected command {:?}", cmd)),
+        }
+        assert_eq!(replica.size(None)?, 1);
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn register_multisig_write() -> Result<()> {
+        let client = create_test_client().await?;
+        let keypairs = multisig_keypairs(1, 3);
+        let key_set = match &keypairs[0] {
+            Keypair::BlsShare(share) => share.public_key_set.clone(),
+            _ => unreachable!(),
+        };
+
+        // the client sending the ops of the group needs to be allowed to write
+        let permissions = vec![(User::Key(client.public_key()), PublicPermissions::new(true))]
+            .into_iter()
+            .collect();
+        let name = XorName::random();
+        let tag = 15_000;
+        let address = client
+            .store_public_register(name, tag, key_set.into(), permissions)
+            .await?;
+
+        let entry = Entry::from(b"signed by the group".to_vec());
+        let (hash, mut op) = retry_loop!(client.prepare_multisig_register_write(
+            address,
+            entry.clone(),
+            BTreeSet::new()
+        ));
+        for keypair in &keypairs[1..] {
+            let share = op.sign(keypair)?;
+            let _ = op.add_share(share)?;
+        }
+        client.send_multisig_register_op(op).await?;
+
+        let current = retry_loop_for_pattern!(client.read_register(address), Ok(entries) if !entries.is_empty())?;
+        assert_eq!(current, vec![(hash, entry)].into_iter().collect());
+
+        // The ownership can't be transferred without the signatures of the group
+        assert!(client
+            .transfer_register_ownership(address, client.public_key().into())
+            .await
+            .is_err());
+
+        Ok(())
+    }
+}
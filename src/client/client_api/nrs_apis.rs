@@ -0,0 +1,249 @@
+// Copyright 2021 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::Client;
+use crate::client::Error;
+use crate::types::register::{EntryHash, PublicPermissions, User};
+use crate::url::{ContentType, DataType, Url, VersionHash, NRS_MAP_TYPE_TAG};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The names registered in an NRS map, mapped to the XOR-URLs they resolve to.
+///
+/// Names are the sub-names of the public names, the top name itself being the empty name.
+/// A name mapped to several targets was associated with them concurrently,
+/// associating it again resolves the conflict.
+pub type NrsMap = BTreeMap<String, BTreeSet<Url>>;
+
+// An NRS map is a public Multimap stored at the address derived from its top name,
+// of the sub-names to the targets they resolve to.
+impl Client {
+    /// Create the NRS map of the top name of an NRS Url, e.g. `safe://name`,
+    /// returning the Url of the top name.
+    ///
+    /// Only the client's key can then associate names with targets in it.
+    #[instrument(skip(self), level = "debug")]
+    pub async fn nrs_create(&self, url: &Url) -> Result<Url, Error> {
+        check_nrs_url(url)?;
+
+        let owner = self.public_key();
+        let mut perms = BTreeMap::new();
+        let _ = perms.insert(User::Key(owner), PublicPermissions::new(true));
+        let _ = self
+            .store_public_register(url.xorname(), NRS_MAP_TYPE_TAG, owner.into(), perms)
+            .await?;
+
+        Ok(Url::from_nrsurl(&format!("safe://{}", url.top_name()))?)
+    }
+
+    /// Associate the public name of an NRS Url, e.g. `safe://sub.name`, with a target
+    /// in the NRS map of its top name, returning the Url of the name versioned at the
+    /// association.
+    ///
+    /// The target must be an XOR-URL, with a version if it points to a Register.
+    /// Any target the name was associated with is replaced.
+    #[instrument(skip(self), level = "debug")]
+    pub async fn nrs_associate(&self, url: &Url, target: &Url) -> Result<Url, Error> {
+        check_nrs_url(url)?;
+        let versioned =
+            target.content_version().is_some() || target.data_type() != DataType::Register;
+        if !target.is_xorurl() || !versioned {
+            return Err(Error::InvalidNrsTarget(target.to_string()));
+        }
+
+        let name = url.sub_names().as_bytes().to_vec();
+        let replace = self
+            .multimap_get_by_key(url, &name)
+            .await?
+            .into_iter()
+            .map(|(hash, _)| hash)
+            .collect();
+        let hash = self
+            .multimap_insert(url, (name, target.to_string().into_bytes()), replace)
+            .await?;
+
+        versioned_nrs_url(url, hash)
+    }
+
+    /// Remove the public name of an NRS Url from the NRS map of its top name,
+    /// returning the Url of the name versioned at the removal.
+    #[instrument(skip(self), level = "debug")]
+    pub async fn nrs_remove(&self, url: &Url) -> Result<Url, Error> {
+        check_nrs_url(url)?;
+
+        let to_remove: BTreeSet<_> = self
+            .multimap_get_by_key(url, url.sub_names().as_bytes())
+            .await?
+            .into_iter()
+            .map(|(hash, _)| hash)
+            .collect();
+        if to_remove.is_empty() {
+            return Err(Error::NrsNameNotFound(url.public_name().to_string()));
+        }
+        let hash = self.multimap_remove(url, to_remove).await?;
+
+        versioned_nrs_url(url, hash)
+    }
+
+    /// Read the NRS map of the top name of an NRS Url, or the one an XOR-URL points to.
+    #[instrument(skip(self), level = "debug")]
+    pub async fn nrs_get_map(&self, url: &Url) -> Result<NrsMap, Error> {
+        if url.content_type() != ContentType::NrsMapContainer {
+            return Err(Error::NotAnNrsUrl(url.to_string()));
+        }
+
+        let mut map = NrsMap::new();
+        for (_, (name, target)) in self.read_multimap(url).await? {
+            let name = String::from_utf8(name)
+                .map_err(|_| Error::Generic("NRS map name is not valid UTF-8".to_string()))?;
+            let _ = map.entry(name).or_default().insert(decode_target(&target)?);
+        }
+
+        Ok(map)
+    }
+
+    /// Resolve an NRS Url to the XOR-URL its public name is associated with,
+    /// the path of the NRS Url being appended to the one of the target.
+    ///
+    /// If the NRS Url has a version (`?v=`), the name is resolved to the target it was
+    /// associated with at that version, otherwise to its current target.
+    #[instrument(skip(self), level = "debug")]
+    pub async fn nrs_resolve(&self, url: &Url) -> Result<Url, Error> {
+        check_nrs_url(url)?;
+        let name = url.sub_names().as_bytes();
+
+        let target = match url.content_version() {
+            Some(version) => match self.multimap_get_by_hash(url, version.entry_hash()).await? {
+                (key, target) if key == name => target,
+                _ => return Err(Error::NrsNameNotFound(url.public_name().to_string())),
+            },
+            None => {
+                let mut targets = self.multimap_get_by_key(url, name).await?.into_iter();
+                match (targets.next(), targets.next()) {
+                    (Some((_, target)), None) => target,
+                    (None, _) => return Err(Error::NrsNameNotFound(url.public_name().to_string())),
+                    _ => return Err(Error::NrsNameConflict(url.public_name().to_string())),
+                }
+            }
+        };
+
+        let mut target = decode_target(&target)?;
+        if !url.path().is_empty() {
+            let path = format!("{}{}", target.path_decoded()?, url.path_decoded()?);
+            target.set_path(&path);
+        }
+
+        Ok(target)
+    }
+}
+
+fn check_nrs_url(url: &Url) -> Result<(), Error> {
+    if url.is_nrsurl() {
+        Ok(())
+    } else {
+        Err(Error::NotAnNrsUrl(url.to_string()))
+    }
+}
+
+// Url of the public name of `url`, at the version of the NRS map entry `hash`.
+fn versioned_nrs_url(url: &Url, hash: EntryHash) -> Result<Url, Error> {
+    let mut versioned = Url::from_nrsurl(&format!("safe://{}", url.public_name()))?;
+    versioned.set_content_version(Some(VersionHash::from(&hash)));
+    Ok(versioned)
+}
+
+fn decode_target(target: &[u8]) -> Result<Url, Error> {
+    let target = std::str::from_utf8(target)
+        .map_err(|_| Error::Generic("NRS map target is not valid UTF-8".to_string()))?;
+    Ok(Url::from_xorurl(target)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_nrs_url, versioned_nrs_url};
+    use crate::client::utils::test_utils::create_test_client;
+    use crate::client::Error;
+    use crate::url::{ContentType, Scope, Url, VersionHash, XorUrlBase};
+    use crate::{retry_loop, retry_loop_for_pattern};
+    use eyre::{eyre, Result};
+    use xor_name::XorName;
+
+    #[test]
+    fn nrs_urls_are_versioned_by_entry() -> Result<()> {
+        let url = Url::from_nrsurl("safe://sub.name/some/path?x=1")?;
+        check_nrs_url(&url)?;
+
+        let hash = [7; 32];
+        let versioned = versioned_nrs_url(&url, hash)?;
+        assert_eq!(versioned.public_name(), "sub.name");
+        assert_eq!(versioned.path(), "");
+        assert_eq!(versioned.content_version(), Some(VersionHash::from(&hash)));
+
+        let xorurl = Url::from_url(&Url::encode_register(
+            XorName::random(),
+            15_000,
+            Scope::Public,
+            ContentType::NrsMapContainer,
+            XorUrlBase::Base32z,
+        )?)?;
+        assert!(matches!(check_nrs_url(&xorurl), Err(Error::NotAnNrsUrl(_))));
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn nrs_associate_and_resolve() -> Result<()> {
+        let client = create_test_client().await?;
+        let top_name = hex::encode(&XorName::random().0[..8]);
+        let top = client
+            .nrs_create(&Url::from_nrsurl(&format!("safe://{}", top_name))?)
+            .await?;
+
+        // Registers must be versioned to be targets
+        let register_url = Url::from_url(&Url::encode_register(
+            XorName::random(),
+            15_000,
+            Scope::Public,
+            ContentType::Raw,
+            XorUrlBase::Base32z,
+        )?)?;
+        let sub = Url::from_nrsurl(&format!("safe://sub.{}", top_name))?;
+        assert!(matches!(
+            client.nrs_associate(&sub, &register_url).await,
+            Err(Error::InvalidNrsTarget(_))
+        ));
+
+        let mut first = register_url.clone();
+        first.set_content_version(Some(VersionHash::from(&[1; 32])));
+        let first_version = retry_loop!(client.nrs_associate(&sub, &first));
+        let _ = retry_loop_for_pattern!(client.nrs_resolve(&sub), Ok(url) if *url == first)?;
+
+        // associating the name again replaces its target
+        let mut second = register_url;
+        second.set_content_version(Some(VersionHash::from(&[2; 32])));
+        let _ = client.nrs_associate(&sub, &second).await?;
+
+        let resolved =
+            retry_loop_for_pattern!(client.nrs_resolve(&sub), Ok(url) if *url == second)?;
+        assert_eq!(resolved, second);
+
+        // the path is carried over, and a versioned name resolves to its target at the time
+        let with_path = Url::from_nrsurl(&format!("safe://sub.{}/a/file", top_name))?;
+        assert_eq!(client.nrs_resolve(&with_path).await?.path(), "/a/file");
+        assert_eq!(client.nrs_resolve(&first_version).await?, first);
+
+        let map = client.nrs_get_map(&top).await?;
+        assert_eq!(map.len(), 1);
+        assert_eq!(map["sub"], vec![second].into_iter().collect());
+
+        let _ = client.nrs_remove(&sub).await?;
+        match retry_loop_for_pattern!(client.nrs_resolve(&sub), Err(Error::NrsNameNotFound(_))) {
+            Err(Error::NrsNameNotFound(_)) => Ok(()),
+            result => Err(eyre!("Unexpected resolution {:?}", result)),
+        }
+    }
+}
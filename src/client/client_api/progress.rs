@@ -0,0 +1,67 @@
+// Copyright 2021 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::Client;
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Progress of data being transferred to or from the network,
+/// as reported by a [`Client`] created with [`Client::with_progress_sender`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransferProgress {
+    /// `stored` out of `total` chunks have been sent to the network.
+    ///
+    /// When uploading from a file, the chunks holding the data map are only known once all
+    /// content chunks are stored, so `total` then grows by their number towards the end.
+    ChunksStored {
+        /// Number of chunks sent so far.
+        stored: usize,
+        /// Number of chunks to send.
+        total: usize,
+    },
+    /// `chunks` chunks, adding up to `bytes` bytes, were found to be already stored on the network,
+    /// so they were not sent again. They are also counted as stored in [`TransferProgress::ChunksStored`].
+    ChunksDeduplicated {
+        /// Number of chunks already stored.
+        chunks: usize,
+        /// Total size in bytes of the chunks already stored.
+        bytes: usize,
+    },
+    /// `fetched` out of `total` chunks have been received from the network.
+    ChunksFetched {
+        /// Number of chunks received so far.
+        fetched: usize,
+        /// Number of chunks to receive.
+        total: usize,
+    },
+    /// `decrypted` out of `total` bytes have been decrypted from the fetched chunks.
+    BytesDecrypted {
+        /// Number of bytes decrypted so far.
+        decrypted: usize,
+        /// Number of bytes to decrypt.
+        total: usize,
+    },
+}
+
+impl Client {
+    /// Returns a client which reports the progress of its blob uploads and downloads to `sender`.
+    ///
+    /// The returned client shares the connections of this one,
+    /// so a new one can be cheaply created for each transfer to be followed.
+    pub fn with_progress_sender(&self, sender: UnboundedSender<TransferProgress>) -> Self {
+        let mut client = self.clone();
+        client.progress = Some(sender);
+        client
+    }
+
+    pub(crate) fn report_progress(&self, progress: TransferProgress) {
+        if let Some(sender) = &self.progress {
+            // the receiving end not being interested anymore is not an error
+            let _ = sender.send(progress);
+        }
+    }
+}
@@ -23,6 +23,17 @@ impl Client {
     // This function is a helper private to this module.
     #[instrument(skip(self), level = "debug")]
     pub(crate) async fn send_query(&self, query: DataQuery) -> Result<QueryResult, Error> {
+        self.send_query_skipping_elders(query, 0).await
+    }
+
+    // Send a Query to the network and await a response, as `send_query` does,
+    // without sending it to the `elders_to_skip` Elders closest to the destination.
+    #[instrument(skip(self), level = "debug")]
+    pub(crate) async fn send_query_skipping_elders(
+        &self,
+        query: DataQuery,
+        elders_to_skip: usize,
+    ) -> Result<QueryResult, Error> {
         let client_pk = self.public_key();
         let msg = ServiceMsg::Query(query.clone());
         let serialised_query = WireMsg::serialize_msg_payload(&msg)?;
@@ -56,6 +67,7 @@ impl Client {
                             client_pk,
                             serialised_query.clone(),
                             signature.clone(),
+                            elders_to_skip,
                         ),
                     )
                     .await;
@@ -84,6 +96,7 @@ impl Client {
         client_pk: PublicKey,
         serialised_query: Bytes,
         signature: Signature,
+        elders_to_skip: usize,
     ) -> Result<QueryResult, Error> {
         debug!("Sending Query: {:?}", query);
         let auth = ServiceAuth {
@@ -91,6 +104,8 @@ impl Client {
             signature,
         };
 
-        self.session.send_query(query, auth, serialised_query).await
+        self.session
+            .send_query(query, auth, serialised_query, elders_to_skip)
+            .await
     }
 }
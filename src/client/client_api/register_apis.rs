@@ -8,17 +8,63 @@
 
 use super::Client;
 use crate::client::Error;
-use crate::messaging::data::{DataCmd, DataQuery, QueryResponse, RegisterRead, RegisterWrite};
+use crate::messaging::data::{
+    DataCmd, DataQuery, QueryResponse, RegisterQuotaUsage, RegisterRead, RegisterWrite,
+    StoreReceipt,
+};
 use crate::types::{
     register::{
         Entry, EntryHash, Permissions, Policy, PrivatePermissions, PrivatePolicy,
-        PublicPermissions, PublicPolicy, Register, User,
+        PublicPermissions, PublicPolicy, Register, User, MAX_REG_HISTORY_PAGE_LEN,
     },
-    PublicKey, RegisterAddress as Address,
+    DataAddress, OwnerType, PublicKey, RegisterAddress as Address,
+};
+use crate::url::Url;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    time::Duration,
 };
-use std::collections::{BTreeMap, BTreeSet};
 use xor_name::XorName;
 
+/// Maximum number of times a Register is read back, waiting for an appended entry
+/// to be applied to it.
+const MAX_APPEND_READS: usize = 5;
+
+/// Interval between reads of a Register, waiting for an appended entry to be applied to it.
+const APPEND_READ_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Outcome of [`Client::append_to_register`].
+///
+/// The entry is written once only. Should an entry written concurrently have forked the
+/// Register again, [`RegisterAppend::forked`] tells so, and it is up to the caller to
+/// append another entry to merge the branches, if it needs a single current entry.
+/// Writing the same entry again instead would duplicate its payload in the history.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RegisterAppend {
+    /// Hash of the entry written.
+    pub hash: EntryHash,
+    /// The entries the entry was written on top of, i.e. the current entries of the Register
+    /// when it was written.
+    pub children: BTreeSet<EntryHash>,
+    /// The current entries of the Register as read back after the entry was written,
+    /// or `None` if the entry could not be read back.
+    pub current: Option<BTreeSet<EntryHash>>,
+}
+
+impl RegisterAppend {
+    /// Whether the Register had several current entries, written concurrently,
+    /// which the entry merged back into a single branch, as read back after writing it.
+    pub fn resolved_fork(&self) -> bool {
+        self.children.len() > 1 && !self.forked()
+    }
+
+    /// Whether the Register was still left with several current entries,
+    /// as read back after writing the entry.
+    pub fn forked(&self) -> bool {
+        matches!(&self.current, Some(current) if current.len() > 1)
+    }
+}
+
 impl Client {
     //----------------------
     // Write Operations
@@ -36,7 +82,7 @@ impl Client {
         &self,
         name: XorName,
         tag: u64,
-        owner: PublicKey,
+        owner: OwnerType,
         permissions: BTreeMap<PublicKey, PrivatePermissions>,
     ) -> Result<Address, Error> {
         let pk = self.public_key();
@@ -62,7 +108,7 @@ impl Client {
         &self,
         name: XorName,
         tag: u64,
-        owner: PublicKey,
+        owner: OwnerType,
         permissions: BTreeMap<User, PublicPermissions>,
     ) -> Result<Address, Error> {
         let pk = self.public_key();
@@ -95,21 +141,119 @@ impl Client {
         entry: Entry,
         children: BTreeSet<EntryHash>,
     ) -> Result<EntryHash, Error> {
+        let (hash, cmd) = self.register_write_cmd(address, entry, children).await?;
+
+        // Finally we can send the mutation to the network's replicas
+        self.send_cmd(cmd).await?;
+
+        Ok(hash)
+    }
+
+    /// Write to Register, awaiting a receipt
+    ///
+    /// Writes to the Register as [`Client::write_to_register`] does, returning along with the
+    /// hash of the entry the receipt signed by the section holding the Register,
+    /// as proof that the write was stored.
+    #[instrument(skip(self, children), level = "debug")]
+    pub async fn write_to_register_with_receipt(
+        &self,
+        address: Address,
+        entry: Entry,
+        children: BTreeSet<EntryHash>,
+    ) -> Result<(EntryHash, StoreReceipt), Error> {
+        let (hash, cmd) = self.register_write_cmd(address, entry, children).await?;
+
+        let receipt = self.send_cmd_with_receipt(cmd).await?;
+
+        Ok((hash, receipt))
+    }
+
+    /// Append an entry to a Register, on top of all its current entries.
+    ///
+    /// If the Register was forked by concurrent writes, the entry merges the branches back
+    /// into one. The Register is then read back, and the returned [`RegisterAppend`] reports
+    /// its current entries, which tell whether an entry written concurrently to ours forked it
+    /// again. The entry is not written again in that case, see [`RegisterAppend`].
+    #[instrument(skip(self, entry), level = "debug")]
+    pub async fn append_to_register(
+        &self,
+        address: Address,
+        entry: Entry,
+    ) -> Result<RegisterAppend, Error> {
+        let mut register = self.get_register(address).await?;
+        let children = current_hashes(&register)?;
+        let (hash, cmd) = self.sign_register_write(&mut register, entry, children.clone())?;
+        self.send_cmd(cmd).await?;
+
+        let current = match self.read_back_register(address, hash).await? {
+            Some(written) => Some(current_hashes(&written)?),
+            None => {
+                warn!(
+                    "Entry appended to Register {:?} could not be read back yet",
+                    address
+                );
+                None
+            }
+        };
+        if matches!(&current, Some(current) if current.len() > 1) {
+            debug!(
+                "Register {:?} was forked again by a concurrent write",
+                address
+            );
+        }
+
+        Ok(RegisterAppend {
+            hash,
+            children,
+            current,
+        })
+    }
+
+    // Reads the Register until the entry is applied to it, for a few times.
+    async fn read_back_register(
+        &self,
+        address: Address,
+        hash: EntryHash,
+    ) -> Result<Option<Register>, Error> {
+        for _ in 0..MAX_APPEND_READS {
+            let register = self.get_register(address).await?;
+            if register.get(hash, None)?.is_some() {
+                return Ok(Some(register));
+            }
+            tokio::time::sleep(APPEND_READ_INTERVAL).await;
+        }
+
+        Ok(None)
+    }
+
+    // Forms the signed command writing the entry to the Register.
+    async fn register_write_cmd(
+        &self,
+        address: Address,
+        entry: Entry,
+        children: BTreeSet<EntryHash>,
+    ) -> Result<(EntryHash, DataCmd), Error> {
         // First we fetch it so we can get the causality info,
         // either from local CRDT replica or from the network if not found
         let mut register = self.get_register(address).await?;
 
+        self.sign_register_write(&mut register, entry, children)
+    }
+
+    // Writes the entry to our replica of the Register, signing the resulting op.
+    fn sign_register_write(
+        &self,
+        register: &mut Register,
+        entry: Entry,
+        children: BTreeSet<EntryHash>,
+    ) -> Result<(EntryHash, DataCmd), Error> {
         // We can now write the entry to the Register
-        let (hash, mut op) = register.write(entry, children)?;
+        let (hash, mut op) = register.write_as(entry, children, self.public_key())?;
         let bytes = bincode::serialize(&op.crdt_op)?;
         let signature = self.keypair.sign(&bytes);
         op.signature = Some(signature);
 
-        // Finally we can send the mutation to the network's replicas
-        let cmd = DataCmd::Register(RegisterWrite::Edit(op));
-        self.send_cmd(cmd).await?;
-
-        Ok(hash)
+        Ok((hash, DataCmd::Register(RegisterWrite::Edit(op))))
     }
 
     /// Store a new Register data object
@@ -168,6 +312,175 @@ impl Client {
         Ok(entry.to_owned())
     }
 
+    //----------------------
+    // History
+    //---------------------
+
+    /// Get the entries written on top of an entry of a Register on the Network,
+    /// i.e. the entries which have it as a child.
+    #[instrument(skip(self), level = "debug")]
+    pub async fn get_register_entry_parents(
+        &self,
+        address: Address,
+        hash: EntryHash,
+    ) -> Result<BTreeSet<(EntryHash, Entry)>, Error> {
+        let query = DataQuery::Register(RegisterRead::GetEntryParents { address, hash });
+        let query_result = self.send_query(query).await?;
+        match query_result.response {
+            QueryResponse::GetRegisterEntryParents((res, op_id)) => {
+                res.map_err(|err| Error::ErrorMessage { source: err, op_id })
+            }
+            _ => Err(Error::ReceivedUnexpectedEvent),
+        }
+    }
+
+    /// Get the entries an entry of a Register on the Network was written on top of.
+    #[instrument(skip(self), level = "debug")]
+    pub async fn get_register_entry_children(
+        &self,
+        address: Address,
+        hash: EntryHash,
+    ) -> Result<BTreeSet<(EntryHash, Entry)>, Error> {
+        let query = DataQuery::Register(RegisterRead::GetEntryChildren { address, hash });
+        let query_result = self.send_query(query).await?;
+        match query_result.response {
+            QueryResponse::GetRegisterEntryChildren((res, op_id)) => {
+                res.map_err(|err| Error::ErrorMessage { source: err, op_id })
+            }
+            _ => Err(Error::ReceivedUnexpectedEvent),
+        }
+    }
+
+    /// Get all the entries of a Register on the Network in topological order,
+    /// the first entries written first and the current entries last.
+    ///
+    /// The history is read page by page with [`Client::get_register_history_page`], so
+    /// entries written to the Register meanwhile may be missed or returned twice.
+    #[instrument(skip(self), level = "debug")]
+    pub async fn get_register_history(
+        &self,
+        address: Address,
+    ) -> Result<Vec<(EntryHash, Entry)>, Error> {
+        let mut history = vec![];
+        loop {
+            let page = self
+                .get_register_history_page(address, history.len())
+                .await?;
+            let is_last = page.len() < MAX_REG_HISTORY_PAGE_LEN;
+            history.extend(page);
+            if is_last {
+                return Ok(history);
+            }
+        }
+    }
+
+    /// Get a page of the entries of a Register on the Network in topological order, i.e. at most
+    /// [`MAX_REG_HISTORY_PAGE_LEN`] entries starting at the `start` index of its history.
+    /// A page shorter than [`MAX_REG_HISTORY_PAGE_LEN`] is the last one.
+    #[instrument(skip(self), level = "debug")]
+    pub async fn get_register_history_page(
+        &self,
+        address: Address,
+        start: usize,
+    ) -> Result<Vec<(EntryHash, Entry)>, Error> {
+        let query = DataQuery::Register(RegisterRead::GetHistory { address, start });
+        let query_result = self.send_query(query).await?;
+        match query_result.response {
+            QueryResponse::GetRegisterHistory((res, op_id)) => {
+                res.map_err(|err| Error::ErrorMessage { source: err, op_id })
+            }
+            _ => Err(Error::ReceivedUnexpectedEvent),
+        }
+    }
+
+    /// Get the entries preceding an entry of a Register on the Network,
+    /// up to `depth` levels back, the nearest ones first.
+    ///
+    /// At most [`MAX_REG_ANCESTORS_DEPTH`] levels and [`MAX_REG_HISTORY_PAGE_LEN`] entries are
+    /// returned, the farther ones can be read by asking for the ancestors of the last entries.
+    ///
+    /// [`MAX_REG_ANCESTORS_DEPTH`]: crate::types::register::MAX_REG_ANCESTORS_DEPTH
+    #[instrument(skip(self), level = "debug")]
+    pub async fn get_register_ancestors(
+        &self,
+        address: Address,
+        hash: EntryHash,
+        depth: usize,
+    ) -> Result<Vec<(EntryHash, Entry)>, Error> {
+        let query = DataQuery::Register(RegisterRead::GetAncestors {
+            address,
+            hash,
+            depth,
+        });
+        let query_result = self.send_query(query).await?;
+        match query_result.response {
+            QueryResponse::GetRegisterAncestors((res, op_id)) => {
+                res.map_err(|err| Error::ErrorMessage { source: err, op_id })
+            }
+            _ => Err(Error::ReceivedUnexpectedEvent),
+        }
+    }
+
+    /// Read a Register on the Network as of a version, i.e. the entry with the `version` hash
+    /// along with all the entries it was written on top of, in topological order with the
+    /// entry last. Entries written concurrently or on top of it are left out.
+    #[instrument(skip(self), level = "debug")]
+    pub async fn read_register_at(
+        &self,
+        address: Address,
+        version: EntryHash,
+    ) -> Result<Vec<(EntryHash, Entry)>, Error> {
+        let query = DataQuery::Register(RegisterRead::GetAt { address, version });
+        let query_result = self.send_query(query).await?;
+        match query_result.response {
+            QueryResponse::GetRegisterAt((res, op_id)) => {
+                res.map_err(|err| Error::ErrorMessage { source: err, op_id })
+            }
+            _ => Err(Error::ReceivedUnexpectedEvent),
+        }
+    }
+
+    /// Read the Register a `safe://` Url points to, in topological order.
+    ///
+    /// If the Url has a content version (`?v=`), the Register is read as of that version
+    /// with [`Client::read_register_at`], otherwise its whole history is returned.
+    #[instrument(skip(self), level = "debug")]
+    pub async fn read_register_url(&self, url: &Url) -> Result<Vec<(EntryHash, Entry)>, Error> {
+        let address = match url.address() {
+            DataAddress::Register(address) => address,
+            _ => return Err(Error::NotARegisterUrl(url.to_string())),
+        };
+
+        match url.content_version() {
+            Some(version) => self.read_register_at(address, version.entry_hash()).await,
+            None => self.get_register_history(address).await,
+        }
+    }
+
+    //----------------------
+    // Quotas
+    //---------------------
+
+    /// Get our usage of the Register quotas at the section holding the given address,
+    /// along with the quotas it enforces.
+    ///
+    /// Writes to the Registers we own are charged to us, whoever sends them,
+    /// and those going over any of the quotas are refused by the section.
+    #[instrument(skip(self), level = "debug")]
+    pub async fn get_register_quota_usage(
+        &self,
+        address: Address,
+    ) -> Result<RegisterQuotaUsage, Error> {
+        let query = DataQuery::Register(RegisterRead::GetQuotaUsage(address));
+        let query_result = self.send_query(query).await?;
+        match query_result.response {
+            QueryResponse::GetRegisterQuotaUsage((res, op_id)) => {
+                res.map_err(|err| Error::ErrorMessage { source: err, op_id })
+            }
+            _ => Err(Error::ReceivedUnexpectedEvent),
+        }
+    }
+
     //----------------------
     // Ownership
     //---------------------
@@ -185,6 +498,68 @@ impl Client {
     // Permissions
     //---------------------
 
+    /// Replace the Policy of a Register on the Network.
+    ///
+    /// Only the current owner of the Register can edit its Policy.
+    #[instrument(skip(self), level = "debug")]
+    pub async fn edit_register_policy(
+        &self,
+        address: Address,
+        policy: Policy,
+    ) -> Result<(), Error> {
+        let mut register = self.get_register(address).await?;
+
+        let mut op = register.edit_policy(policy, Some(self.public_key()))?;
+        let bytes = op.bytes_to_sign()?;
+        op.signature = Some(self.keypair.sign(&bytes));
+
+        let cmd = DataCmd::Register(RegisterWrite::EditPolicy(op));
+        self.send_cmd(cmd).await
+    }
+
+    /// Grant a user the given Permissions on a Register on the Network,
+    /// replacing those it had if any.
+    #[instrument(skip(self), level = "debug")]
+    pub async fn set_register_permissions_for_user(
+        &self,
+        address: Address,
+        user: User,
+        permissions: Permissions,
+    ) -> Result<(), Error> {
+        let mut policy = self.get_register_policy(address).await?;
+        policy.set_permissions(user, permissions)?;
+
+        self.edit_register_policy(address, policy).await
+    }
+
+    /// Revoke the Permissions of a user on a Register on the Network.
+    #[instrument(skip(self), level = "debug")]
+    pub async fn revoke_register_permissions_for_user(
+        &self,
+        address: Address,
+        user: User,
+    ) -> Result<(), Error> {
+        let mut policy = self.get_register_policy(address).await?;
+        let _ = policy
+            .remove_permissions(user)
+            .ok_or_else(|| Error::from(crate::types::Error::NoSuchEntry))?;
+
+        self.edit_register_policy(address, policy).await
+    }
+
+    /// Transfer the ownership of a Register on the Network to a new owner.
+    #[instrument(skip(self), level = "debug")]
+    pub async fn transfer_register_ownership(
+        &self,
+        address: Address,
+        new_owner: OwnerType,
+    ) -> Result<(), Error> {
+        let mut policy = self.get_register_policy(address).await?;
+        policy.set_owner(new_owner);
+
+        self.edit_register_policy(address, policy).await
+    }
+
     /// Get the set of Permissions in a Register for a specific user.
     #[instrument(skip(self), level = "debug")]
     pub async fn get_register_permissions_for_user(
@@ -208,23 +583,32 @@ impl Client {
     }
 }
 
+// Hashes of the current entries of the Register.
+fn current_hashes(register: &Register) -> Result<BTreeSet<EntryHash>, Error> {
+    Ok(register
+        .read(None)?
+        .into_iter()
+        .map(|(hash, _)| hash)
+        .collect())
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::messaging::data::Error as ErrorMessage;
+    use crate::client::{
+        utils::test_utils::{
+            create_test_client, gen_ed_keypair, init_test_logger, run_w_backoff_delayed,
+        },
+        Error,
+    };
+    use crate::messaging::data::{Error as ErrorMessage, ReceiptAddress};
     use crate::routing::log_markers::LogMarker;
     use crate::types::{
-        register::{Action, EntryHash, Permissions, PrivatePermissions, PublicPermissions, User},
-        BytesAddress, Error as DtError, PublicKey,
-    };
-    use crate::{
-        client::{
-            utils::test_utils::{
-                create_test_client, gen_ed_keypair, init_test_logger, run_w_backoff_delayed,
-            },
-            Error,
+        register::{
+            Action, Entry, EntryHash, Permissions, PrivatePermissions, PublicPermissions, User,
         },
-        url::Url,
+        BytesAddress, Error as DtError, PublicKey,
     };
+    use crate::url::{ContentType, Scope, Url, VersionHash, XorUrlBase};
     use crate::{retry_loop, retry_loop_for_pattern};
     use eyre::{bail, eyre, Result};
     use std::{
@@ -260,7 +644,7 @@ mod tests {
         let mut perms = BTreeMap::<PublicKey, PrivatePermissions>::new();
         let _ = perms.insert(owner, PrivatePermissions::new(true, true));
         let address = client
-            .store_private_register(name, tag, owner, perms)
+            .store_private_register(name, tag, owner.into(), perms)
             .await?;
 
         // small delay to ensure logs have written
@@ -298,10 +682,10 @@ mod tests {
         let _ = perms.insert(User::Key(owner), PublicPermissions::new(true));
 
         let address = client
-            .store_public_register(name, tag, owner, perms)
+            .store_public_register(name, tag, owner.into(), perms)
             .await?;
 
-        let value_1 = random_url()?;
+        let value_1 = random_entry()?;
 
         for i in 0..1000_usize {
             let now = Instant::now();
@@ -344,7 +728,7 @@ mod tests {
         let mut perms = BTreeMap::<PublicKey, PrivatePermissions>::new();
         let _ = perms.insert(owner, PrivatePermissions::new(true, true));
         let address = client
-            .store_private_register(name, tag, owner, perms)
+            .store_private_register(name, tag, owner.into(), perms)
             .await?;
 
         let delay = tokio::time::Duration::from_secs(1);
@@ -362,7 +746,7 @@ mod tests {
         let mut perms = BTreeMap::<User, PublicPermissions>::new();
         let _ = perms.insert(User::Anyone, PublicPermissions::new(true));
         let address = client
-            .store_public_register(name, tag, owner, perms)
+            .store_public_register(name, tag, owner.into(), perms)
             .await?;
 
         tokio::time::sleep(delay).await;
@@ -389,7 +773,7 @@ mod tests {
         let mut perms = BTreeMap::<PublicKey, PrivatePermissions>::new();
         let _ = perms.insert(owner, PrivatePermissions::new(true, true));
         let address = client
-            .store_private_register(name, tag, owner, perms)
+            .store_private_register(name, tag, owner.into(), perms)
             .await?;
 
         let delay = tokio::time::Duration::from_secs(1);
@@ -441,7 +825,7 @@ mod tests {
         let mut perms = BTreeMap::<User, PublicPermissions>::new();
         let _ = perms.insert(User::Key(owner), PublicPermissions::new(None));
         let address = client
-            .store_public_register(name, tag, owner, perms)
+            .store_public_register(name, tag, owner.into(), perms)
             .await?;
 
         let delay = tokio::time::Duration::from_secs(1);
@@ -491,10 +875,10 @@ mod tests {
         let _ = perms.insert(User::Key(owner), PublicPermissions::new(true));
 
         let address = client
-            .store_public_register(name, tag, owner, perms)
+            .store_public_register(name, tag, owner.into(), perms)
             .await?;
 
-        let value_1 = random_url()?;
+        let value_1 = random_entry()?;
 
         // write to the register
         let value1_hash = run_w_backoff_delayed(
@@ -515,7 +899,7 @@ mod tests {
         let current = hashes.iter().next();
         assert_eq!(current, Some(&(value1_hash, value_1.clone())));
 
-        let value_2 = random_url()?;
+        let value_2 = random_entry()?;
 
         drop(start_span);
         let _second_span = tracing::info_span!("test__register_write__second_write").entered();
@@ -568,6 +952,207 @@ mod tests {
         }
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn register_append_merges_forks() -> Result<()> {
+        init_test_logger();
+        let _outer_span = tracing::info_span!("test__register_append_merges_forks").entered();
+
+        let tag = 10;
+        let name = XorName(rand::random());
+        let client = create_test_client().await?;
+
+        let owner = client.public_key();
+        let mut perms = BTreeMap::<User, PublicPermissions>::new();
+        let _ = perms.insert(User::Key(owner), PublicPermissions::new(true));
+
+        let address = client
+            .store_public_register(name, tag, owner.into(), perms)
+            .await?;
+
+        // fork the Register with two concurrent entries
+        let mut fork = BTreeSet::new();
+        for _ in 0..2 {
+            let value = random_entry()?;
+            let hash = run_w_backoff_delayed(
+                || async {
+                    Ok(client
+                        .write_to_register(address, value.clone(), BTreeSet::new())
+                        .await?)
+                },
+                10,
+                1,
+            )
+            .await?;
+            let _ = fork.insert(hash);
+        }
+        let _ = retry_loop_for_pattern!(client.read_register(address), Ok(entries) if entries.len() == 2)?;
+
+        let merge = client.append_to_register(address, random_entry()?).await?;
+        assert!(merge.resolved_fork());
+        assert_eq!(merge.children, fork);
+        assert_eq!(merge.current, Some(vec![merge.hash].into_iter().collect()));
+        let current = retry_loop_for_pattern!(client.read_register(address), Ok(entries) if entries.len() == 1)?;
+        assert!(current.iter().all(|(hash, _)| *hash == merge.hash));
+
+        let append = client.append_to_register(address, random_entry()?).await?;
+        assert!(!append.resolved_fork());
+        assert_eq!(append.children, vec![merge.hash].into_iter().collect());
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn register_write_bytes() -> Result<()> {
+        init_test_logger();
+        let _outer_span = tracing::info_span!("test__register_write_bytes").entered();
+
+        let tag = 10;
+        let name = XorName(rand::random());
+        let client = create_test_client().await?;
+
+        let owner = client.public_key();
+        let mut perms = BTreeMap::<User, PublicPermissions>::new();
+        let _ = perms.insert(User::Key(owner), PublicPermissions::new(true));
+
+        let address = client
+            .store_public_register(name, tag, owner.into(), perms)
+            .await?;
+
+        let entry = Entry::Bytes(b"some small document".to_vec());
+        let hash = run_w_backoff_delayed(
+            || async {
+                Ok(client
+                    .write_to_register(address, entry.clone(), BTreeSet::new())
+                    .await?)
+            },
+            10,
+            1,
+        )
+        .await?;
+
+        let retrieved = retry_loop!(client.get_register_entry(address, hash));
+        assert_eq!(retrieved, entry);
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn register_write_returns_receipt() -> Result<()> {
+        init_test_logger();
+        let _outer_span = tracing::info_span!("test__register_write_returns_receipt").entered();
+
+        let tag = 10;
+        let name = XorName(rand::random());
+        let client = create_test_client().await?;
+
+        let owner = client.public_key();
+        let mut perms = BTreeMap::<User, PublicPermissions>::new();
+        let _ = perms.insert(User::Key(owner), PublicPermissions::new(true));
+
+        let address = client
+            .store_public_register(name, tag, owner.into(), perms)
+            .await?;
+
+        let mut receipts = vec![];
+        for entry in [b"first".to_vec(), b"second".to_vec()] {
+            let (hash, receipt) = run_w_backoff_delayed(
+                || async {
+                    Ok(client
+                        .write_to_register_with_receipt(
+                            address,
+                            Entry::Bytes(entry.clone()),
+                            BTreeSet::new(),
+                        )
+                        .await?)
+                },
+                10,
+                1,
+            )
+            .await?;
+
+            assert!(receipt.verify());
+            assert_eq!(receipt.address, ReceiptAddress::Register(address));
+            assert_eq!(receipt.content, XorName(hash));
+            assert!(!receipt.holders.is_empty());
+            receipts.push(receipt);
+        }
+
+        // each write gets a receipt of its own
+        assert_ne!(receipts[0], receipts[1]);
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn register_history() -> Result<()> {
+        init_test_logger();
+        let _outer_span = tracing::info_span!("test__register_history").entered();
+
+        let tag = 10;
+        let name = XorName(rand::random());
+        let client = create_test_client().await?;
+
+        let owner = client.public_key();
+        let mut perms = BTreeMap::<User, PublicPermissions>::new();
+        let _ = perms.insert(User::Key(owner), PublicPermissions::new(true));
+
+        let address = client
+            .store_public_register(name, tag, owner.into(), perms)
+            .await?;
+
+        // write three versions, each on top of the previous one
+        let mut versions = vec![];
+        let mut children = BTreeSet::new();
+        for _ in 0..3 {
+            let value = random_entry()?;
+            let hash = run_w_backoff_delayed(
+                || async {
+                    Ok(client
+                        .write_to_register(address, value.clone(), children.clone())
+                        .await?)
+                },
+                10,
+                1,
+            )
+            .await?;
+            let _ = retry_loop_for_pattern!(client.read_register(address), Ok(entries) if entries.iter().any(|(h, _)| *h == hash))?;
+            versions.push((hash, value));
+            children = vec![hash].into_iter().collect();
+        }
+
+        let history = retry_loop!(client.get_register_history(address));
+        assert_eq!(history, versions);
+
+        let parents = retry_loop!(client.get_register_entry_parents(address, versions[0].0));
+        assert_eq!(parents, vec![versions[1].clone()].into_iter().collect());
+
+        let children = retry_loop!(client.get_register_entry_children(address, versions[2].0));
+        assert_eq!(children, vec![versions[1].clone()].into_iter().collect());
+
+        let ancestors = retry_loop!(client.get_register_ancestors(address, versions[2].0, 1));
+        assert_eq!(ancestors, vec![versions[1].clone()]);
+
+        let at = retry_loop!(client.read_register_at(address, versions[1].0));
+        assert_eq!(at, versions[..2].to_vec());
+
+        // a versioned Url resolves to the Register as of that version
+        let mut url = Url::from_url(&Url::encode_register(
+            name,
+            tag,
+            Scope::Public,
+            ContentType::Raw,
+            XorUrlBase::Base32z,
+        )?)?;
+        assert_eq!(client.read_register_url(&url).await?, versions);
+        url.set_content_version(Some(VersionHash::from(&versions[0].0)));
+        assert_eq!(
+            client.read_register_url(&url).await?,
+            versions[..1].to_vec()
+        );
+
+        Ok(())
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn register_owner() -> Result<()> {
         init_test_logger();
@@ -581,7 +1166,7 @@ mod tests {
         let mut perms = BTreeMap::<PublicKey, PrivatePermissions>::new();
         let _ = perms.insert(owner, PrivatePermissions::new(true, true));
         let address = client
-            .store_private_register(name, tag, owner, perms)
+            .store_private_register(name, tag, owner.into(), perms)
             .await?;
 
         // Assert that the data is stored.
@@ -592,6 +1177,62 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn register_edit_policy_and_transfer_ownership() -> Result<()> {
+        init_test_logger();
+        let _outer_span =
+            tracing::info_span!("test__register_edit_policy_and_transfer_ownership").entered();
+        let tag = 10;
+
+        let name = XorName(rand::random());
+        let client = create_test_client().await?;
+        let new_owner = gen_ed_keypair().public_key();
+
+        let owner = client.public_key();
+        let mut perms = BTreeMap::<PublicKey, PrivatePermissions>::new();
+        let _ = perms.insert(owner, PrivatePermissions::new(true, true));
+        let address = client
+            .store_private_register(name, tag, owner.into(), perms)
+            .await?;
+
+        // grant permissions to the new owner
+        let new_owner_perms = Permissions::Private(PrivatePermissions::new(true, false));
+        run_w_backoff_delayed(
+            || async {
+                Ok(client
+                    .set_register_permissions_for_user(
+                        address,
+                        User::Key(new_owner),
+                        new_owner_perms.clone(),
+                    )
+                    .await?)
+            },
+            10,
+            1,
+        )
+        .await?;
+        let perms = retry_loop_for_pattern!(client.get_register_permissions_for_user(address, new_owner), Ok(perms) if *perms == new_owner_perms)?;
+        assert_eq!(perms, new_owner_perms);
+
+        // then hand the Register over
+        client
+            .transfer_register_ownership(address, new_owner.into())
+            .await?;
+        let current_owner = retry_loop_for_pattern!(client.get_register_owner(address), Ok(owner) if *owner == new_owner)?;
+        assert_eq!(current_owner, new_owner);
+
+        // we're no longer the owner, so we can't edit the policy anymore
+        match client
+            .transfer_register_ownership(address, owner.into())
+            .await
+        {
+            Err(_) => Ok(()),
+            Ok(()) => Err(eyre!(
+                "Unexpectedly edited the policy of a Register we don't own"
+            )),
+        }
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn register_can_delete_private() -> Result<()> {
         init_test_logger();
@@ -606,7 +1247,7 @@ mod tests {
         let mut perms = BTreeMap::<PublicKey, PrivatePermissions>::new();
         let _ = perms.insert(owner, PrivatePermissions::new(true, true));
         let address = client
-            .store_private_register(name, tag, owner, perms)
+            .store_private_register(name, tag, owner.into(), perms)
             .await?;
 
         let delay = tokio::time::Duration::from_secs(1);
@@ -652,7 +1293,7 @@ mod tests {
         let mut perms = BTreeMap::<User, PublicPermissions>::new();
         let _ = perms.insert(User::Anyone, PublicPermissions::new(true));
         let address = client
-            .store_public_register(name, tag, owner, perms)
+            .store_public_register(name, tag, owner.into(), perms)
             .await?;
 
         let delay = tokio::time::Duration::from_secs(1);
@@ -680,7 +1321,7 @@ mod tests {
         Ok(())
     }
 
-    fn random_url() -> Result<Url> {
+    fn random_entry() -> Result<Entry> {
         use crate::url::*;
         let xor_name = XorName::random();
         let url = match Url::encode_bytes(
@@ -696,7 +1337,7 @@ mod tests {
         };
 
         match Url::from_url(&url) {
-            Ok(url) => Ok(url),
+            Ok(url) => Ok(Entry::Url(url)),
             Err(e) => bail!(
                 "Unexpected error returned when attempting to parse url string: {}",
                 e
@@ -0,0 +1,99 @@
+// Copyright 2021 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::Client;
+use crate::client::Error;
+use crate::messaging::data::{DataCmd, DataQuery, QueryResponse};
+use crate::types::{DataAddress, Keypair, SafeKey, SafeKeyAddress};
+use crate::url::{ContentType, Scope, Url, XorUrlBase};
+
+impl Client {
+    /// Store the public key of a keypair on the Network, at the address derived from it,
+    /// returning the XOR-URL of the SafeKey.
+    ///
+    /// The SafeKey is signed with the secret key of the keypair, proving it was stored by
+    /// its owner. Public SafeKeys can be read by anyone, private ones only by their own key.
+    #[instrument(skip(self, keypair), level = "debug")]
+    pub async fn store_safe_key(&self, keypair: &Keypair, scope: Scope) -> Result<Url, Error> {
+        let safe_key = SafeKey::new(keypair, scope)?;
+        let address = *safe_key.address();
+
+        self.send_cmd(DataCmd::StoreSafeKey(safe_key)).await?;
+
+        let xorurl = Url::encode(
+            DataAddress::SafeKey(address),
+            None,
+            0,
+            ContentType::Raw,
+            None,
+            None,
+            None,
+            None,
+            None,
+            XorUrlBase::Base32z,
+        )?;
+
+        Ok(Url::from_url(&xorurl)?)
+    }
+
+    /// Get a SafeKey from the Network, verifying it was signed by its own key.
+    #[instrument(skip(self), level = "debug")]
+    pub async fn get_safe_key(&self, address: SafeKeyAddress) -> Result<SafeKey, Error> {
+        let query = DataQuery::GetSafeKey(address);
+        let query_result = self.send_query(query).await?;
+        let safe_key = match query_result.response {
+            QueryResponse::GetSafeKey((res, op_id)) => {
+                res.map_err(|err| Error::ErrorMessage { source: err, op_id })?
+            }
+            _ => return Err(Error::ReceivedUnexpectedEvent),
+        };
+
+        if safe_key.address() != &address || !safe_key.verify() {
+            return Err(Error::Generic(format!(
+                "Invalid SafeKey received for {:?}",
+                address
+            )));
+        }
+
+        Ok(safe_key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::SafeData;
+    use crate::client::utils::test_utils::create_test_client;
+    use crate::retry_loop;
+    use crate::types::{DataAddress, Keypair};
+    use crate::url::Scope;
+    use eyre::{eyre, Result};
+    use rand::rngs::OsRng;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn safe_key_store_and_fetch() -> Result<()> {
+        let client = create_test_client().await?;
+        let keypair = Keypair::new_ed25519(&mut OsRng);
+
+        let url = client.store_safe_key(&keypair, Scope::Public).await?;
+        let address = match url.address() {
+            DataAddress::SafeKey(address) => address,
+            address => return Err(eyre!("Unexpected address {:?}", address)),
+        };
+
+        let safe_key = retry_loop!(client.get_safe_key(address));
+        assert_eq!(safe_key.public_key(), keypair.public_key());
+
+        // fetching its XOR-URL returns the public key info
+        match client.fetch(&url.to_string()).await?.data {
+            SafeData::SafeKey(fetched) => assert_eq!(fetched, safe_key),
+            data => return Err(eyre!("Unexpected data {:?}", data)),
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,186 @@
+// Copyright 2021 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::Client;
+use crate::client::Error;
+use crate::messaging::data::{DataQuery, QueryResponse, RegisterRead};
+use crate::types::{
+    register::{Entry, EntryHash, RegisterOp},
+    RegisterAddress as Address,
+};
+use futures::{
+    task::{Context, Poll},
+    Stream,
+};
+use std::{collections::BTreeSet, pin::Pin, time::Duration};
+use tokio::sync::mpsc::{channel, Receiver};
+
+/// Number of seconds subscriptions are requested for, they are renewed halfway through.
+const SUBSCRIPTION_DURATION_SECS: u64 = 120;
+/// Number of edits buffered for a subscription before they are consumed.
+const SUBSCRIPTION_BUFFER: usize = 64;
+
+/// Stream of the edits applied to a Register, as pushed by the Elders holding it.
+///
+/// The subscription is renewed as long as the stream is alive. The stream ends if it
+/// can no longer be renewed, e.g. when the Register was deleted.
+#[derive(Debug)]
+pub struct RegisterEdits {
+    receiver: Receiver<RegisterOp<Entry>>,
+    // Several Elders push each edit
+    seen: BTreeSet<EntryHash>,
+}
+
+impl Stream for RegisterEdits {
+    type Item = RegisterOp<Entry>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            let op = match self.receiver.poll_recv(cx) {
+                Poll::Ready(Some(op)) => op,
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            };
+
+            if !is_signed_by_source(&op) {
+                warn!("Ignoring Register edit with an invalid signature: {:?}", op);
+                continue;
+            }
+            if self.seen.insert(op.crdt_op.hash()) {
+                return Poll::Ready(Some(op));
+            }
+        }
+    }
+}
+
+fn is_signed_by_source(op: &RegisterOp<Entry>) -> bool {
+    match (&op.signature, bincode::serialize(&op.crdt_op)) {
+        (Some(signature), Ok(bytes)) => op.source.verify(signature, &bytes).is_ok(),
+        _ => false,
+    }
+}
+
+impl Client {
+    /// Subscribe to the edits of a Register, which the Elders holding it push to us
+    /// instead of us having to poll it.
+    ///
+    /// Only edits applied after subscribing are streamed, and Elders limit the number
+    /// of Registers a client can be subscribed to at once. Edits are dropped if the
+    /// stream is not consumed as fast as they come.
+    ///
+    /// Edits are checked to be signed by their source, but the Elders don't sign them, so an
+    /// edit streamed is not a proof the section applied it: read the Register to confirm it.
+    #[instrument(skip(self), level = "debug")]
+    pub async fn subscribe_to_register(&self, address: Address) -> Result<RegisterEdits, Error> {
+        let mut duration = self
+            .send_register_subscription(address, SUBSCRIPTION_DURATION_SECS)
+            .await?;
+
+        let (sender, receiver) = channel(SUBSCRIPTION_BUFFER);
+        self.session
+            .add_register_subscriber(address, sender.clone())
+            .await;
+
+        // Renew the subscription before it expires, until the stream is dropped
+        let client = self.clone();
+        let _handle = tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(Duration::from_secs(duration) / 2).await;
+                if sender.is_closed() {
+                    break;
+                }
+                match client
+                    .send_register_subscription(address, SUBSCRIPTION_DURATION_SECS)
+                    .await
+                {
+                    Ok(granted) => duration = granted,
+                    Err(error) => {
+                        warn!(
+                            "Failed to renew subscription to Register {:?}: {:?}",
+                            address, error
+                        );
+                        break;
+                    }
+                }
+            }
+
+            if !client
+                .session
+                .remove_register_subscriber(address, &sender)
+                .await
+            {
+                let _ = client.send_register_subscription(address, 0).await;
+            }
+        });
+
+        Ok(RegisterEdits {
+            receiver,
+            seen: BTreeSet::new(),
+        })
+    }
+
+    // Subscribes to the Register for the given duration, returning the duration granted.
+    async fn send_register_subscription(
+        &self,
+        address: Address,
+        duration_secs: u64,
+    ) -> Result<u64, Error> {
+        let query = DataQuery::Register(RegisterRead::Subscribe {
+            address,
+            duration_secs,
+        });
+        let query_result = self.send_query(query).await?;
+        match query_result.response {
+            QueryResponse::SubscribeToRegister((res, op_id)) => {
+                res.map_err(|err| Error::ErrorMessage { source: err, op_id })
+            }
+            _ => Err(Error::ReceivedUnexpectedEvent),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::utils::test_utils::create_test_client;
+    use crate::retry_loop;
+    use crate::types::register::{Entry, PublicPermissions, User};
+    use eyre::{eyre, Result};
+    use futures::StreamExt;
+    use std::{collections::BTreeMap, collections::BTreeSet, time::Duration};
+    use tokio::time::timeout;
+    use xor_name::XorName;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn register_subscription_streams_edits() -> Result<()> {
+        let client = create_test_client().await?;
+        let writer = create_test_client().await?;
+
+        let name = XorName::random();
+        let tag = 15_000;
+        let mut perms = BTreeMap::<User, PublicPermissions>::new();
+        let _ = perms.insert(User::Anyone, PublicPermissions::new(true));
+        let address = client
+            .store_public_register(name, tag, client.public_key().into(), perms)
+            .await?;
+
+        let mut edits = retry_loop!(client.subscribe_to_register(address));
+
+        let entry = Entry::from(b"pushed to subscribers".to_vec());
+        let hash = writer
+            .write_to_register(address, entry.clone(), BTreeSet::new())
+            .await?;
+
+        let op = timeout(Duration::from_secs(30), edits.next())
+            .await?
+            .ok_or_else(|| eyre!("Subscription ended unexpectedly"))?;
+        assert_eq!(op.crdt_op.hash(), hash);
+        assert_eq!(op.crdt_op.value, entry);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,288 @@
+// Copyright 2021 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::client::{Error, Result};
+use crate::dbs::serialise;
+use crate::types::BytesAddress;
+use lazy_static::lazy_static;
+use sled::{Db, Tree};
+use std::{
+    collections::{BTreeSet, HashMap},
+    convert::TryFrom,
+    path::{Path, PathBuf},
+    sync::{Mutex, MutexGuard},
+};
+use xor_name::{XorName, XOR_NAME_LEN};
+
+const JOURNAL_DIR_NAME: &str = "upload_journal";
+const UPLOADS_TREE_NAME: &str = "uploads";
+const PENDING_TREE_NAME: &str = "pending";
+
+lazy_static! {
+    // A sled db can only be opened once at a time, so the journal db of each root dir
+    // is opened on first use and then shared by all uploads of all clients in the process.
+    static ref JOURNAL_DBS: Mutex<HashMap<PathBuf, Db>> = Mutex::new(HashMap::new());
+    // Number of uploads holding each journal, by journal db path and upload address,
+    // so that a journal shared by concurrent uploads is only removed by the last of them.
+    static ref JOURNAL_HOLDERS: Mutex<JournalHolders> = Mutex::new(HashMap::new());
+}
+
+// Number of holders by journal db path and key prefix of the upload.
+type JournalHolders = HashMap<(PathBuf, Vec<u8>), usize>;
+
+/// On-disk record of an upload in progress.
+///
+/// Holds the names of the chunks that are still to be stored to the network for the data at `address`.
+/// The chunks themselves are not kept: they are derived again from the content when the upload
+/// is resumed, by uploading the same content once more.
+/// The journals of all uploads under a root dir share a single db, so that concurrent
+/// uploads of the same content just pick up the same journal.
+pub(crate) struct UploadJournal {
+    address: BytesAddress,
+    prefix: Vec<u8>,
+    path: PathBuf,
+    db: Db,
+    uploads: Tree,
+    pending: Tree,
+}
+
+impl UploadJournal {
+    /// Opens the journal of an upload to `address`, creating it with the chunks named `names`
+    /// as pending if there is none. An existing journal is left as it is, its pending chunks being
+    /// those which were not yet stored by previous attempts.
+    pub(crate) fn open_or_create(
+        root_dir: &Path,
+        address: BytesAddress,
+        names: impl IntoIterator<Item = XorName>,
+    ) -> Result<Self> {
+        let journal = Self::hold(root_dir, address)?;
+
+        if journal.uploads.contains_key(&journal.prefix)? {
+            debug!(
+                "Resuming upload to {:?}, {} chunks are yet to be stored",
+                address,
+                journal.pending_count()
+            );
+            return Ok(journal);
+        }
+
+        let mut batch = sled::Batch::default();
+        for name in names {
+            batch.insert(journal.key(&name), &[]);
+        }
+        journal.pending.apply_batch(batch)?;
+        // the upload is only recorded once all its chunks are
+        let _ = journal.uploads.insert(&journal.prefix, &[])?;
+        journal.flush()?;
+
+        Ok(journal)
+    }
+
+    /// Opens the journal of an upload to `address`, if there is one.
+    pub(crate) fn open(root_dir: &Path, address: BytesAddress) -> Result<Option<Self>> {
+        let journal = Self::hold(root_dir, address)?;
+        if journal.uploads.contains_key(&journal.prefix)? {
+            Ok(Some(journal))
+        } else {
+            Ok(None)
+        }
+    }
+
+    // Counts one more holder of the journal of an upload to `address`, whether it exists or not.
+    fn hold(root_dir: &Path, address: BytesAddress) -> Result<Self> {
+        let path = root_dir.join(JOURNAL_DIR_NAME);
+        let db = journal_db(&path)?;
+        let uploads = db.open_tree(UPLOADS_TREE_NAME)?;
+        let pending = db.open_tree(PENDING_TREE_NAME)?;
+        let prefix = serialise(&address)?;
+
+        *journal_holders()?
+            .entry((path.clone(), prefix.clone()))
+            .or_default() += 1;
+
+        Ok(Self {
+            address,
+            prefix,
+            path,
+            db,
+            uploads,
+            pending,
+        })
+    }
+
+    /// The address of the data being uploaded.
+    pub(crate) fn address(&self) -> BytesAddress {
+        self.address
+    }
+
+    /// Returns the names of the chunks which have not yet been stored.
+    pub(crate) fn pending_names(&self) -> Result<BTreeSet<XorName>> {
+        self.pending
+            .scan_prefix(&self.prefix)
+            .keys()
+            .map(|key| {
+                let key = key?;
+                let name = <[u8; XOR_NAME_LEN]>::try_from(&key[self.prefix.len()..])
+                    .map_err(|_| Error::Generic("Invalid upload journal entry".to_string()))?;
+                Ok(XorName(name))
+            })
+            .collect()
+    }
+
+    /// Number of chunks which have not yet been stored.
+    pub(crate) fn pending_count(&self) -> usize {
+        self.pending.scan_prefix(&self.prefix).count()
+    }
+
+    /// Records that the chunk with the given name was successfully stored.
+    ///
+    /// This is not written to disk until the next [`UploadJournal::flush`],
+    /// so that a large upload does not cause a disk flush per chunk.
+    pub(crate) fn mark_stored(&self, name: &XorName) -> Result<()> {
+        let _ = self.pending.remove(self.key(name))?;
+        Ok(())
+    }
+
+    /// Writes the chunks marked as stored so far to disk.
+    pub(crate) fn flush(&self) -> Result<()> {
+        let _ = self.db.flush()?;
+        Ok(())
+    }
+
+    /// Removes the journal, along with any chunks left pending,
+    /// unless other uploads of the same content still hold it.
+    pub(crate) fn remove(self) -> Result<()> {
+        // held until the journal is removed, so that it is not opened again meanwhile
+        let holders = journal_holders()?;
+        if matches!(holders.get(&(self.path.clone(), self.prefix.clone())), Some(count) if *count > 1)
+        {
+            trace!(
+                "Upload journal of {:?} is still in use, not removing it",
+                self.address
+            );
+            return Ok(());
+        }
+
+        let mut batch = sled::Batch::default();
+        for key in self.pending.scan_prefix(&self.prefix).keys() {
+            batch.remove(key?);
+        }
+        self.pending.apply_batch(batch)?;
+        let _ = self.uploads.remove(&self.prefix)?;
+        self.flush()
+    }
+
+    fn key(&self, name: &XorName) -> Vec<u8> {
+        let mut key = self.prefix.clone();
+        key.extend_from_slice(&name.0);
+        key
+    }
+}
+
+impl Drop for UploadJournal {
+    fn drop(&mut self) {
+        if let Ok(mut holders) = journal_holders() {
+            let key = (self.path.clone(), self.prefix.clone());
+            if let Some(count) = holders.get_mut(&key) {
+                *count -= 1;
+                if *count == 0 {
+                    let _ = holders.remove(&key);
+                }
+            }
+        }
+    }
+}
+
+fn journal_db(path: &Path) -> Result<Db> {
+    let mut dbs = JOURNAL_DBS
+        .lock()
+        .map_err(|_| Error::Generic("Upload journal lock poisoned".to_string()))?;
+    if let Some(db) = dbs.get(path) {
+        return Ok(db.clone());
+    }
+    let db = sled::open(path)?;
+    let _ = dbs.insert(path.to_path_buf(), db.clone());
+    Ok(db)
+}
+
+fn journal_holders() -> Result<MutexGuard<'static, JournalHolders>> {
+    JOURNAL_HOLDERS
+        .lock()
+        .map_err(|_| Error::Generic("Upload journal lock poisoned".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UploadJournal;
+    use crate::types::BytesAddress;
+    use eyre::Result;
+    use std::collections::BTreeSet;
+    use tempfile::tempdir;
+    use xor_name::XorName;
+
+    #[test]
+    fn pending_chunks_survive_reopening() -> Result<()> {
+        let root_dir = tempdir()?;
+        let address = BytesAddress::Public(XorName::random());
+        let names = (0..5).map(|_| XorName::random()).collect::<Vec<_>>();
+
+        let journal = UploadJournal::open_or_create(root_dir.path(), address, names.clone())?;
+        assert_eq!(journal.pending_count(), names.len());
+
+        journal.mark_stored(&names[0])?;
+        journal.mark_stored(&names[3])?;
+        journal.flush()?;
+        drop(journal);
+
+        // an upload of other content is journaled separately
+        let other = BytesAddress::Public(XorName::random());
+        let other_journal =
+            UploadJournal::open_or_create(root_dir.path(), other, vec![XorName::random()])?;
+        assert_eq!(other_journal.pending_count(), 1);
+
+        // the names given when reopening are ignored in favour of those still pending
+        let journal = UploadJournal::open_or_create(root_dir.path(), address, names.clone())?;
+        assert_eq!(journal.address(), address);
+
+        let expected: BTreeSet<_> = vec![names[1], names[2], names[4]].into_iter().collect();
+        assert_eq!(journal.pending_names()?, expected);
+
+        journal.remove()?;
+        assert!(UploadJournal::open(root_dir.path(), address)?.is_none());
+        let journal = UploadJournal::open_or_create(root_dir.path(), address, vec![])?;
+        assert_eq!(journal.pending_count(), 0);
+        assert_eq!(other_journal.pending_count(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn journal_is_removed_by_its_last_holder() -> Result<()> {
+        let root_dir = tempdir()?;
+        let address = BytesAddress::Public(XorName::random());
+        let names = (0..3).map(|_| XorName::random()).collect::<Vec<_>>();
+
+        // two uploads of the same content share the journal
+        let first = UploadJournal::open_or_create(root_dir.path(), address, names.clone())?;
+        let second = UploadJournal::open_or_create(root_dir.path(), address, names.clone())?;
+        for name in &names[..2] {
+            first.mark_stored(name)?;
+        }
+
+        // the first one to be done leaves the journal to the other
+        first.remove()?;
+        assert!(UploadJournal::open(root_dir.path(), address)?.is_some());
+        let expected: BTreeSet<_> = vec![names[2]].into_iter().collect();
+        assert_eq!(second.pending_names()?, expected);
+
+        second.remove()?;
+        assert!(UploadJournal::open(root_dir.path(), address)?.is_none());
+
+        Ok(())
+    }
+}
@@ -25,6 +25,9 @@ const DEFAULT_LOCAL_ADDR: (Ipv4Addr, u16) = (Ipv4Addr::UNSPECIFIED, 0);
 /// Defaul amount of time to wait for responses to queries before giving up and returning an error.
 pub const DEFAULT_QUERY_TIMEOUT: Duration = Duration::from_secs(90);
 
+/// Default number of chunks fetched concurrently when reading data.
+pub const DEFAULT_CHUNK_FETCH_CONCURRENCY: usize = 8;
+
 const DEFAULT_ROOT_DIR_NAME: &str = "root_dir";
 
 /// Configuration for sn_client.
@@ -40,6 +43,11 @@ pub struct Config {
     pub qp2p: QuicP2pConfig,
     /// The amount of time to wait for responses to queries before giving up and returning an error.
     pub query_timeout: Duration,
+    /// The maximum number of chunks fetched concurrently when reading data.
+    pub chunk_fetch_concurrency: usize,
+    /// The maximum total size in bytes of chunks kept in the on-disk chunk cache under `root_dir`.
+    /// The cache is disabled if this is not set.
+    pub chunk_cache_capacity: Option<u64>,
 }
 
 impl Config {
@@ -53,6 +61,9 @@ impl Config {
     /// port).
     ///
     /// If `query_timeout` is not specified, [`DEFAULT_QUERY_TIMEOUT`] will be used.
+    ///
+    /// The chunk fetch concurrency is set to [`DEFAULT_CHUNK_FETCH_CONCURRENCY`], and the chunk cache
+    /// is disabled. Both can be overridden on the returned instance.
     pub async fn new(
         root_dir: Option<&Path>,
         local_addr: Option<SocketAddr>,
@@ -79,6 +90,8 @@ impl Config {
             genesis_key,
             qp2p,
             query_timeout: query_timeout.unwrap_or(DEFAULT_QUERY_TIMEOUT),
+            chunk_fetch_concurrency: DEFAULT_CHUNK_FETCH_CONCURRENCY,
+            chunk_cache_capacity: None,
         }
     }
 }
@@ -172,6 +185,8 @@ mod tests {
             genesis_key,
             qp2p: QuicP2pConfig::default(),
             query_timeout: DEFAULT_QUERY_TIMEOUT,
+            chunk_fetch_concurrency: DEFAULT_CHUNK_FETCH_CONCURRENCY,
+            chunk_cache_capacity: None,
         };
         assert_eq!(serialize(&config)?, serialize(&expected_config)?);
 
@@ -142,6 +142,8 @@ impl Session {
     ) -> Result<Session, Error> {
         debug!("ServiceMsg with id {:?} received from {:?}", msg_id, src);
         let queries = session.pending_queries.clone();
+        let receipts = session.pending_receipts.clone();
+        let register_subscribers = session.register_subscribers.clone();
         let error_sender = session.incoming_err_sender.clone();
 
         let _ = tokio::spawn(async move {
@@ -173,6 +175,35 @@ impl Session {
                         warn!("Ignoring query response without operation id");
                     }
                 }
+                ServiceMsg::CmdReceipt {
+                    receipt,
+                    correlation_id,
+                } => {
+                    if let Some(sender) = receipts.read().await.get(&correlation_id) {
+                        trace!(
+                            "Sending receipt for cmd w/{:?} via channel.",
+                            correlation_id
+                        );
+                        let _ = sender.send(receipt).await;
+                    } else {
+                        trace!("No channel found for receipt of cmd {:?}", correlation_id);
+                    }
+                }
+                ServiceMsg::RegisterEdited(op) => {
+                    let senders = register_subscribers.read().await.get(&op.address).cloned();
+                    if let Some(senders) = senders {
+                        trace!("Sending edit of Register {:?} via channels.", op.address);
+                        // A subscriber not keeping up misses edits rather than holding up
+                        // the handling of all other incoming messages.
+                        for sender in senders {
+                            if sender.try_send(op.clone()).is_err() {
+                                warn!("Dropped edit of Register {:?} for a subscriber", op.address);
+                            }
+                        }
+                    } else {
+                        trace!("No channel found for edits of Register {:?}", op.address);
+                    }
+                }
                 ServiceMsg::CmdError {
                     error,
                     correlation_id,
@@ -389,9 +420,11 @@ impl Session {
             ServiceMsg::Cmd(cmd) => {
                 match &cmd {
                     DataCmd::StoreChunk(_) => (3, cmd.dst_name()), // stored at Adults, so only 1 correctly functioning Elder need to relay
-                    DataCmd::Register(_) => (7, cmd.dst_name()), // only stored at Elders, all need a copy
+                    DataCmd::Register(_) | DataCmd::StoreSafeKey(_) => (7, cmd.dst_name()), // only stored at Elders, all need a copy
                 }
             }
+            // all Elders need to sign the receipt
+            ServiceMsg::CmdWithReceipt(cmd) => (7, cmd.dst_name()),
             ServiceMsg::Query(query) => (NUM_OF_ELDERS_SUBSET_FOR_QUERIES, query.dst_name()),
             _ => {
                 warn!(
@@ -11,12 +11,15 @@ use super::{QueryResult, Session};
 use super::AeCache;
 use crate::client::Error;
 use crate::messaging::{
-    data::{CmdError, DataQuery, QueryResponse},
+    data::{CmdError, DataQuery, QueryResponse, ReceiptAddress, StoreReceipt},
     signature_aggregator::SignatureAggregator,
     DstLocation, MessageId, MsgKind, ServiceAuth, WireMsg,
 };
 use crate::prefix_map::NetworkPrefixMap;
-use crate::types::PublicKey;
+use crate::types::{
+    register::{Entry, RegisterOp},
+    PublicKey, RegisterAddress,
+};
 use bytes::Bytes;
 use futures::{future::join_all, stream::FuturesUnordered, TryFutureExt};
 use itertools::Itertools;
@@ -31,7 +34,7 @@ use tokio::{
     task::JoinHandle,
 };
 use tracing::{debug, error, trace, warn, Instrument};
-use xor_name::XorName;
+use xor_name::{Prefix, XorName};
 
 // Number of Elders subset to send queries to
 pub(crate) const NUM_OF_ELDERS_SUBSET_FOR_QUERIES: usize = 3;
@@ -61,6 +64,8 @@ impl Session {
         let session = Session {
             client_pk,
             pending_queries: Arc::new(RwLock::new(HashMap::default())),
+            pending_receipts: Arc::new(RwLock::new(HashMap::default())),
+            register_subscribers: Arc::new(RwLock::new(HashMap::default())),
             incoming_err_sender: Arc::new(err_sender),
             endpoint,
             network: Arc::new(NetworkPrefixMap::new(genesis_key)),
@@ -138,13 +143,90 @@ impl Session {
         res
     }
 
+    /// Send a `ServiceMsg::CmdWithReceipt` to all Elders of the section holding the data,
+    /// awaiting the receipt signed by the section for the content stored at the address
+    /// in `receipt_for`.
+    #[instrument(skip(self, auth, payload), level = "debug")]
+    pub(crate) async fn send_cmd_with_receipt(
+        &self,
+        dst_address: XorName,
+        receipt_for: (ReceiptAddress, XorName),
+        auth: ServiceAuth,
+        payload: Bytes,
+    ) -> Result<StoreReceipt, Error> {
+        let (elders, section_pk) = if let Some(sap) = self.network.closest_or_opposite(&dst_address)
+        {
+            (
+                sap.value
+                    .elders
+                    .values()
+                    .cloned()
+                    .collect::<Vec<SocketAddr>>(),
+                sap.value.public_key_set.public_key(),
+            )
+        } else {
+            return Err(Error::NoNetworkKnowledge);
+        };
+
+        let msg_id = MessageId::new();
+
+        debug!(
+            "Sending command w/id {:?} awaiting a receipt, to {} Elders w/ dst: {:?}",
+            msg_id,
+            elders.len(),
+            dst_address
+        );
+
+        let (sender, mut receiver) = channel::<StoreReceipt>(7);
+        let _ = self.pending_receipts.write().await.insert(msg_id, sender);
+
+        let dst_location = DstLocation::Section {
+            name: dst_address,
+            section_pk,
+        };
+        let msg_kind = MsgKind::ServiceMsg(auth);
+        let wire_msg = WireMsg::new_msg(msg_id, payload, msg_kind, dst_location)?;
+
+        let result = match send_message(elders, wire_msg, self.endpoint.clone(), msg_id).await {
+            Ok(()) => loop {
+                match receiver.recv().await {
+                    // Elders could have been changed since we sent the command,
+                    // in which case we'd know the new section key from AE messages by now.
+                    Some(receipt)
+                        if (receipt.address, receipt.content) == receipt_for
+                            && receipt.correlation_id == msg_id
+                            && !receipt.holders.is_empty()
+                            && receipt.verify()
+                            && self
+                                .network
+                                .section_keys()
+                                .contains(&receipt.sig.public_key) =>
+                    {
+                        trace!("Valid store receipt received for {:?}", msg_id);
+                        break Ok(receipt);
+                    }
+                    Some(receipt) => {
+                        warn!("Ignoring invalid store receipt: {:?}", receipt);
+                    }
+                    None => break Err(Error::NoResponse),
+                }
+            },
+            Err(error) => Err(error),
+        };
+
+        let _ = self.pending_receipts.write().await.remove(&msg_id);
+        result
+    }
+
     #[instrument(skip_all, level = "debug")]
     /// Send a `ServiceMsg` to the network awaiting for the response.
+    /// The `elders_to_skip` Elders closest to the destination are not sent the query.
     pub(crate) async fn send_query(
         &self,
         query: DataQuery,
         auth: ServiceAuth,
         payload: Bytes,
+        elders_to_skip: usize,
     ) -> Result<QueryResult, Error> {
         let endpoint = self.endpoint.clone();
         let pending_queries = self.pending_queries.clone();
@@ -164,16 +246,19 @@ impl Session {
             return Err(Error::NoNetworkKnowledge);
         };
 
-        // We select the NUM_OF_ELDERS_SUBSET_FOR_QUERIES closest Elders we are querying
+        // We select the NUM_OF_ELDERS_SUBSET_FOR_QUERIES closest Elders we are querying,
+        // after those we were asked to skip
         let chosen_elders = elders
             .into_iter()
             .sorted_by(|(lhs_name, _), (rhs_name, _)| dst.cmp_distance(lhs_name, rhs_name))
             .map(|(_, addr)| addr)
+            .skip(elders_to_skip)
             .take(NUM_OF_ELDERS_SUBSET_FOR_QUERIES)
             .collect::<Vec<SocketAddr>>();
 
+        // having skipped them all, there is no one left to wait for a response from
         let elders_len = chosen_elders.len();
-        if elders_len < NUM_OF_ELDERS_SUBSET_FOR_QUERIES && elders_len > 1 {
+        if elders_len == 0 || (elders_len < NUM_OF_ELDERS_SUBSET_FOR_QUERIES && elders_len > 1) {
             return Err(Error::InsufficientElderConnections(
                 elders_len,
                 NUM_OF_ELDERS_SUBSET_FOR_QUERIES,
@@ -265,6 +350,7 @@ impl Session {
         // from byzantine nodes, however for mutable data (non-Chunk responses) we will
         // have to review the approach.
         let mut discarded_responses: usize = 0;
+        let mut invalid_chunk = None;
 
         // Send all queries concurrently
         let results = join_all(tasks).await;
@@ -291,6 +377,7 @@ impl Session {
                         // the Chunk content doesn't match its XorName,
                         // this is suspicious and it could be a byzantine node
                         warn!("We received an invalid Chunk response from one of the nodes");
+                        invalid_chunk = Some((*chunk_addr.name(), *chunk.name()));
                         discarded_responses += 1;
                     }
                 }
@@ -298,11 +385,20 @@ impl Session {
                 // Saving error, but not returning until we have more responses in
                 // (note, this will overwrite prior errors, so we'll just return whichever was last received)
                 (response @ Some(QueryResponse::GetChunk(Err(_))), Some(_))
+                | (response @ Some(QueryResponse::ChunkExists((Err(_), _))), None)
                 | (response @ Some(QueryResponse::GetRegister((Err(_), _))), None)
                 | (response @ Some(QueryResponse::GetRegisterPolicy((Err(_), _))), None)
                 | (response @ Some(QueryResponse::GetRegisterOwner((Err(_), _))), None)
-                | (response @ Some(QueryResponse::GetRegisterUserPermissions((Err(_), _))), None) =>
-                {
+                | (response @ Some(QueryResponse::GetRegisterUserPermissions((Err(_), _))), None)
+                | (response @ Some(QueryResponse::GetRegisterEntryParents((Err(_), _))), None)
+                | (response @ Some(QueryResponse::GetRegisterEntryChildren((Err(_), _))), None)
+                | (response @ Some(QueryResponse::GetRegisterHistory((Err(_), _))), None)
+                | (response @ Some(QueryResponse::GetRegisterAncestors((Err(_), _))), None)
+                | (response @ Some(QueryResponse::GetRegisterAt((Err(_), _))), None)
+                | (response @ Some(QueryResponse::GetRegisterQuotaUsage((Err(_), _))), None)
+                | (response @ Some(QueryResponse::SubscribeToRegister((Err(_), _))), None)
+                | (response @ Some(QueryResponse::GetSafeKey((Err(_), _))), None)
+                | (response @ Some(QueryResponse::GetStoreCost((Err(_), _))), None) => {
                     debug!("QueryResponse error received (but may be overridden by a non-error response from another elder): {:#?}", &response);
                     error_response = response;
                     discarded_responses += 1;
@@ -346,7 +442,11 @@ impl Session {
                     operation_id,
                 })
             }
-            None => Err(Error::NoResponse),
+            None => match invalid_chunk {
+                // all we got were invalid chunks
+                Some((expected, actual)) => Err(Error::ChunkIntegrity { expected, actual }),
+                None => Err(Error::NoResponse),
+            },
         }
     }
 
@@ -473,6 +573,49 @@ impl Session {
         }
         Ok(())
     }
+
+    /// Forward the edits of the Register at `address` received from the network to `sender`.
+    pub(crate) async fn add_register_subscriber(
+        &self,
+        address: RegisterAddress,
+        sender: Sender<RegisterOp<Entry>>,
+    ) {
+        self.register_subscribers
+            .write()
+            .await
+            .entry(address)
+            .or_default()
+            .push(sender);
+    }
+
+    /// Stop forwarding the edits of the Register at `address` to `sender`,
+    /// returning whether they are still forwarded to other channels.
+    pub(crate) async fn remove_register_subscriber(
+        &self,
+        address: RegisterAddress,
+        sender: &Sender<RegisterOp<Entry>>,
+    ) -> bool {
+        let mut subscribers = self.register_subscribers.write().await;
+        let remaining = subscribers
+            .get_mut(&address)
+            .map(|senders| {
+                senders.retain(|other| !other.same_channel(sender) && !other.is_closed());
+                senders.len()
+            })
+            .unwrap_or_default();
+        if remaining == 0 {
+            let _ = subscribers.remove(&address);
+        }
+
+        remaining > 0
+    }
+
+    /// Returns the prefix of the section which data with the given name is sent to, if known.
+    pub(crate) fn section_prefix(&self, name: &XorName) -> Option<Prefix> {
+        self.network
+            .closest_or_opposite(name)
+            .map(|sap| sap.value.prefix)
+    }
 }
 
 #[instrument(skip_all, level = "trace")]
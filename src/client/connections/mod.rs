@@ -9,13 +9,18 @@
 mod listeners;
 mod messaging;
 
+pub(crate) use messaging::NUM_OF_ELDERS_SUBSET_FOR_QUERIES;
+
 use crate::messaging::{
-    data::{CmdError, OperationId, QueryResponse},
+    data::{CmdError, OperationId, QueryResponse, StoreReceipt},
     signature_aggregator::SignatureAggregator,
     MessageId,
 };
 use crate::prefix_map::NetworkPrefixMap;
-use crate::types::PublicKey;
+use crate::types::{
+    register::{Entry, RegisterOp},
+    PublicKey, RegisterAddress,
+};
 use bls::PublicKey as BlsPublicKey;
 use bytes::Bytes;
 use qp2p::Endpoint;
@@ -24,6 +29,8 @@ use tokio::sync::{mpsc::Sender, RwLock};
 use xor_name::XorName;
 type QueryResponseSender = Sender<QueryResponse>;
 type PendingQueryResponses = Arc<RwLock<HashMap<OperationId, QueryResponseSender>>>;
+type PendingStoreReceipts = Arc<RwLock<HashMap<MessageId, Sender<StoreReceipt>>>>;
+type RegisterSubscribers = Arc<RwLock<HashMap<RegisterAddress, Vec<Sender<RegisterOp<Entry>>>>>>;
 use tokio::time::Duration;
 use uluru::LRUCache;
 
@@ -44,6 +51,10 @@ pub(super) struct Session {
     endpoint: Endpoint<XorName>,
     // Channels for sending responses to upper layers
     pending_queries: PendingQueryResponses,
+    // Channels for sending store receipts to upper layers
+    pending_receipts: PendingStoreReceipts,
+    // Channels for sending the edits of the Registers subscribed to to upper layers
+    register_subscribers: RegisterSubscribers,
     // Channels for sending errors to upper layer
     incoming_err_sender: Arc<Sender<CmdError>>,
     /// All elders we know about from AE messages
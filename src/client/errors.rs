@@ -11,9 +11,10 @@ use crate::messaging::{
     data::{CmdError, OperationId, QueryResponse},
     Error as MessagingError,
 };
-use crate::types::Error as DtError;
+use crate::types::{BytesAddress, Error as DtError};
 use std::io;
 use thiserror::Error;
+use xor_name::XorName;
 
 /// Specialisation of `std::Result` for Client.
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -142,6 +143,55 @@ pub enum Error {
     /// Could not retrieve all chunks required to decrypt the data. (Expected, Actual)
     #[error("Not enough chunks! Required {}, but we have {}.)", _0, _1)]
     NotEnoughChunks(usize, usize),
+    /// A chunk received from the network failed integrity verification,
+    /// its content hashing to `actual` instead of the `expected` name.
+    #[error("Chunk integrity verification failed, expected {expected} but the content hashes to {actual}")]
+    ChunkIntegrity {
+        /// The name the content was expected to hash to.
+        expected: XorName,
+        /// The name the content actually hashes to.
+        actual: XorName,
+    },
+    /// Not all chunks of an upload could be stored.
+    /// The upload can be completed by uploading the same content again, or with `Client::resume_upload`.
+    #[error("Upload to {0:?} is incomplete, {1} chunks are yet to be stored.")]
+    UploadIncomplete(BytesAddress, usize),
+    /// The Url does not point to a Register.
+    #[error("Url {0} does not point to a Register")]
+    NotARegisterUrl(String),
+    /// The Url does not point to a Multimap.
+    #[error("Url {0} does not point to a Multimap")]
+    NotAMultimapUrl(String),
+    /// The Url is not an NRS Url.
+    #[error("Url {0} is not an NRS Url")]
+    NotAnNrsUrl(String),
+    /// The NRS name is not associated with any target.
+    #[error("NRS name {0} is not associated with any target")]
+    NrsNameNotFound(String),
+    /// The NRS name was associated with several targets concurrently.
+    #[error("NRS name {0} is associated with several targets, associate it again to resolve the conflict")]
+    NrsNameConflict(String),
+    /// NRS names can only be associated with XOR-URLs, versioned if the content is mutable.
+    #[error("Url {0} is not a valid NRS target, it must be an XOR-URL, versioned if the content is mutable")]
+    InvalidNrsTarget(String),
+    /// The Url does not point to a FilesContainer.
+    #[error("Url {0} does not point to a FilesContainer")]
+    NotAFilesContainerUrl(String),
+    /// The FilesContainer has several current versions.
+    #[error("FilesContainer {0} has several current versions, sync it to resolve the conflict")]
+    FilesContainerConflict(String),
+    /// There is no file at the path in the FilesContainer.
+    #[error("No file at path {0} in the FilesContainer")]
+    NoSuchPath(String),
+    /// The content the Url points to can't be fetched.
+    #[error("Url {0} points to content which can't be fetched")]
+    UnsupportedContent(String),
+    /// The content the Url points to can't be read at the version of the Url.
+    #[error("Url {0} points to content which can't be read at a version")]
+    UnsupportedVersion(String),
+    /// Url encoding or parsing error.
+    #[error(transparent)]
+    Url(#[from] crate::url::Error),
     /// Could not chunk all the data required to encrypt the data. (Expected, Actual)
     #[error("Not all data was chunked! Required {}, but we have {}.)", _0, _1)]
     NotAllDataWasChunked(usize, usize),
@@ -32,8 +32,11 @@ mod errors;
 
 // Export public API.
 
-pub use client_api::Client;
-pub use config_handler::{Config, DEFAULT_QUERY_TIMEOUT};
+pub use client_api::{
+    ChunkCacheStats, Client, FetchedData, FileItem, FilesMap, MultimapKeyValue, MultisigRegisterOp,
+    NrsMap, RegisterAppend, RegisterEdits, SafeData, TransferProgress,
+};
+pub use config_handler::{Config, DEFAULT_CHUNK_FETCH_CONCURRENCY, DEFAULT_QUERY_TIMEOUT};
 pub use errors::ErrorMessage;
 pub use errors::{Error, Result};
 pub use qp2p::Config as QuicP2pConfig;
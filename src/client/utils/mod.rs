@@ -10,13 +10,19 @@
 #[cfg(any(test, feature = "test-utils"))]
 pub mod test_utils;
 
-use crate::types::{Encryption, PublicKey, Result as TypesResult};
+use crate::types::{
+    Encryption, Error as TypesError, Keypair, PublicKey, Result as TypesResult, Signature,
+};
 use crate::url::Scope;
+use aes::Aes256;
 use backoff::ExponentialBackoff;
+use block_modes::{block_padding::Pkcs7, BlockMode, Cbc};
 use bytes::Bytes;
 use futures::Future;
 use rand::{self, distributions::Alphanumeric, rngs::OsRng, Rng};
 use std::time::Duration;
+use subtle::ConstantTimeEq;
+use tiny_keccak::{Hasher, Sha3};
 
 pub(crate) fn retry<R, E, Fn, Fut>(
     op: Fn,
@@ -37,6 +43,127 @@ where
     backoff::future::retry(backoff, op)
 }
 
+/// Domain separation for the message signed to derive the encryption keys of a client.
+const ENCRYPTION_KEY_DOMAIN: &[u8] = b"safe_network private data encryption key";
+
+/// Prefix of data encrypted by [`keypair_encryption`], telling it apart from the plain contents
+/// private data was stored as before, which carry no prefix.
+const ENCRYPTED_DATA_PREFIX: &[u8] = b"SNPE\x01";
+
+const KEY_SIZE: usize = 32;
+const IV_SIZE: usize = 16;
+const TAG_SIZE: usize = 32;
+
+type Aes256Cbc = Cbc<Aes256, Pkcs7>;
+
+/// Authenticated encryption of private data, keyed from the client `Keypair`.
+///
+/// The keys are derived from the keypair's signature over a fixed message, which is deterministic
+/// for both Ed25519 and BLS keys. Contents are encrypted with AES-256-CBC under an IV synthesised
+/// from a keyed hash of the plain text, so that the same contents always encrypt to the same chunk
+/// and uploads stay deterministic. The IV and cipher text are then authenticated with a keyed hash
+/// under a separate key (encrypt-then-MAC), which is checked in constant time before anything
+/// is decrypted.
+struct KeypairEncryption {
+    public_key: PublicKey,
+    cipher_key: [u8; KEY_SIZE],
+    iv_key: [u8; KEY_SIZE],
+    mac_key: [u8; KEY_SIZE],
+}
+
+impl KeypairEncryption {
+    pub(crate) fn new(keypair: &Keypair) -> Self {
+        let seed = match keypair.sign(ENCRYPTION_KEY_DOMAIN) {
+            Signature::Ed25519(sig) => sig.to_bytes().to_vec(),
+            Signature::BlsShare(share) => share.share.to_bytes().to_vec(),
+            Signature::Bls(sig) => sig.to_bytes().to_vec(),
+        };
+
+        Self {
+            public_key: keypair.public_key(),
+            cipher_key: derive_key(&seed, b"cipher"),
+            iv_key: derive_key(&seed, b"iv"),
+            mac_key: derive_key(&seed, b"mac"),
+        }
+    }
+}
+
+impl Encryption for KeypairEncryption {
+    fn public_key(&self) -> &PublicKey {
+        &self.public_key
+    }
+
+    fn encrypt(&self, data: Bytes) -> TypesResult<Bytes> {
+        let mut iv = [0; IV_SIZE];
+        iv.copy_from_slice(&keyed_hash(&self.iv_key, &[&data])[..IV_SIZE]);
+        let cipher = Aes256Cbc::new_fix(self.cipher_key.as_ref().into(), iv.as_ref().into());
+
+        let mut encrypted = ENCRYPTED_DATA_PREFIX.to_vec();
+        encrypted.extend_from_slice(&iv);
+        encrypted.extend(cipher.encrypt_vec(&data));
+        let tag = keyed_hash(&self.mac_key, &[&encrypted]);
+        encrypted.extend_from_slice(&tag);
+        Ok(Bytes::from(encrypted))
+    }
+
+    /// Decrypts data encrypted by [`KeypairEncryption::encrypt`].
+    ///
+    /// Data without the encryption prefix was stored as plain contents by earlier versions,
+    /// and is returned as it is.
+    fn decrypt(&self, encrypted_data: Bytes) -> TypesResult<Bytes> {
+        if !encrypted_data.starts_with(ENCRYPTED_DATA_PREFIX) {
+            return Ok(encrypted_data);
+        }
+
+        // all failures are reported alike, so as not to tell which check failed
+        let failed = || TypesError::FailedToDecrypt("Invalid encrypted data".to_string());
+
+        if encrypted_data.len() < ENCRYPTED_DATA_PREFIX.len() + IV_SIZE + TAG_SIZE {
+            return Err(failed());
+        }
+        let (authenticated, tag) = encrypted_data.split_at(encrypted_data.len() - TAG_SIZE);
+        let expected_tag = keyed_hash(&self.mac_key, &[authenticated]);
+        if !bool::from(expected_tag.ct_eq(tag)) {
+            return Err(failed());
+        }
+
+        let (iv, content) = authenticated[ENCRYPTED_DATA_PREFIX.len()..].split_at(IV_SIZE);
+        let cipher = Aes256Cbc::new_fix(self.cipher_key.as_ref().into(), iv.into());
+        let data = cipher.decrypt_vec(content).map_err(|_| failed())?;
+
+        Ok(Bytes::from(data))
+    }
+}
+
+// Derives a key for the given purpose from the secret seed.
+fn derive_key(seed: &[u8], purpose: &[u8]) -> [u8; KEY_SIZE] {
+    keyed_hash(seed, &[purpose])
+}
+
+// SHA3 of the key followed by the parts, which as SHA3 is not subject to length extension,
+// is a MAC of the parts.
+fn keyed_hash(key: &[u8], parts: &[&[u8]]) -> [u8; KEY_SIZE] {
+    let mut hasher = Sha3::v256();
+    let mut hash = [0; KEY_SIZE];
+    hasher.update(key);
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher.finalize(&mut hash);
+    hash
+}
+
+/// Returns the encryption of data of the given scope: none for public data,
+/// and for private data one keyed from the client `Keypair`.
+///
+/// Private data stored as plain contents by earlier versions can still be decrypted with it.
+pub fn keypair_encryption(scope: Scope, keypair: &Keypair) -> Option<impl Encryption> {
+    match scope {
+        Scope::Public => None,
+        Scope::Private => Some(KeypairEncryption::new(keypair)),
+    }
+}
+
 struct DummyEncryption {
     public_key: PublicKey,
 }
@@ -60,6 +187,9 @@ impl Encryption for DummyEncryption {
 }
 
 ///
+#[deprecated(
+    note = "private data is not actually encrypted by it, use `keypair_encryption` instead"
+)]
 pub fn encryption(scope: Scope, public_key: PublicKey) -> Option<impl Encryption> {
     match scope {
         Scope::Public => None,
@@ -134,6 +264,65 @@ mod tests {
         assert_eq!(str2.chars().count(), SIZE);
     }
 
+    #[test]
+    fn private_data_is_encrypted() -> Result<(), TypesError> {
+        let keypair = Keypair::new_ed25519(&mut OsRng);
+        let data = random_bytes(1024);
+
+        let owner =
+            keypair_encryption(Scope::Private, &keypair).ok_or(TypesError::InvalidOperation)?;
+        let encrypted = owner.encrypt(data.clone())?;
+        assert!(!encrypted
+            .windows(data.len())
+            .any(|window| window == data.as_ref()));
+
+        // the same contents encrypt the same, so that chunking stays deterministic
+        assert_eq!(owner.encrypt(data.clone())?, encrypted);
+        assert_eq!(owner.decrypt(encrypted)?, data);
+
+        Ok(())
+    }
+
+    #[test]
+    fn private_data_is_unreadable_without_the_key() -> Result<(), TypesError> {
+        let keypair = Keypair::new_ed25519(&mut OsRng);
+        let other_keypair = Keypair::new_ed25519(&mut OsRng);
+        let data = random_bytes(1024);
+
+        let owner =
+            keypair_encryption(Scope::Private, &keypair).ok_or(TypesError::InvalidOperation)?;
+        let other = keypair_encryption(Scope::Private, &other_keypair)
+            .ok_or(TypesError::InvalidOperation)?;
+        let encrypted = owner.encrypt(data)?;
+
+        assert!(matches!(
+            other.decrypt(encrypted.clone()),
+            Err(TypesError::FailedToDecrypt(_))
+        ));
+
+        // tampered contents are rejected too
+        let mut tampered = encrypted.to_vec();
+        tampered[ENCRYPTED_DATA_PREFIX.len() + IV_SIZE] ^= 1;
+        assert!(matches!(
+            owner.decrypt(Bytes::from(tampered)),
+            Err(TypesError::FailedToDecrypt(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn private_data_stored_in_plain_stays_readable() -> Result<(), TypesError> {
+        let keypair = Keypair::new_ed25519(&mut OsRng);
+        let data = random_bytes(1024);
+
+        let owner =
+            keypair_encryption(Scope::Private, &keypair).ok_or(TypesError::InvalidOperation)?;
+        assert_eq!(owner.decrypt(data.clone())?, data);
+
+        Ok(())
+    }
+
     // Test `random_bytes` and that the results are not repeated.
     #[test]
     fn random_vector() {
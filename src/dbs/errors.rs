@@ -6,7 +6,7 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use crate::messaging::data::Error as ErrorMessage;
+use crate::messaging::data::{Error as ErrorMessage, RegisterQuotas};
 use crate::types::{convert_dt_error_to_error_message, DataAddress, PublicKey};
 use std::io;
 use thiserror::Error;
@@ -29,6 +29,9 @@ pub enum Error {
     /// Not enough space to store the value.
     #[error("Not enough space")]
     NotEnoughSpace,
+    /// The owner of the Register reached one of its Register quotas, which are given.
+    #[error("Register quota exceeded, the limits are {0:?}")]
+    RegisterQuotaExceeded(RegisterQuotas),
     /// Key not found.
     #[error("Key not found: {0:?}")]
     KeyNotFound(String),
@@ -95,6 +98,7 @@ pub enum Error {
 pub(crate) fn convert_to_error_message(error: Error) -> ErrorMessage {
     match error {
         Error::NotEnoughSpace => ErrorMessage::FailedToWriteFile,
+        Error::RegisterQuotaExceeded(limits) => ErrorMessage::RegisterQuotaExceeded(limits),
         Error::DataIdNotFound(address) => ErrorMessage::DataNotFound(address),
         Error::NoSuchData(address) => ErrorMessage::DataNotFound(address),
         Error::ChunkNotFound(xorname) => ErrorMessage::ChunkNotFound(xorname),
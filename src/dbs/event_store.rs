@@ -55,6 +55,16 @@ where
         Ok(events)
     }
 
+    /// Number of events stored in db
+    pub(crate) fn len(&self) -> usize {
+        self.tree.len()
+    }
+
+    /// Remove all events stored in db
+    pub(crate) fn clear(&mut self) -> Result<()> {
+        self.tree.clear().map_err(Error::Sled)
+    }
+
     /// append a new entry
     pub(crate) fn append(&mut self, event: TEvent) -> Result<()> {
         let key = &self.tree.len().to_string();
@@ -10,7 +10,9 @@ use super::{
     super::encoding::{deserialise, serialise},
     Error, Key, Result, Value,
 };
-use crate::types::{Chunk, ChunkAddress, Keypair, PublicKey, RegisterAddress};
+use crate::types::{
+    BytesAddress, Chunk, ChunkAddress, Keypair, PublicKey, RegisterAddress, SafeKey, SafeKeyAddress,
+};
 use serde::{de::DeserializeOwned, Serialize};
 use xor_name::XorName;
 
@@ -28,13 +30,16 @@ pub(crate) fn from_db_key<T: DeserializeOwned>(key: &str) -> Result<T> {
     deserialise(&decoded)
 }
 
+impl ToDbKey for BytesAddress {}
 impl ToDbKey for RegisterAddress {}
 impl ToDbKey for Keypair {}
 impl ToDbKey for ChunkAddress {}
+impl ToDbKey for SafeKeyAddress {}
 impl ToDbKey for PublicKey {}
 impl ToDbKey for XorName {}
 
 impl Key for ChunkAddress {}
+impl Key for SafeKeyAddress {}
 
 impl Value for Chunk {
     type Key = ChunkAddress;
@@ -44,6 +49,14 @@ impl Value for Chunk {
     }
 }
 
+impl Value for SafeKey {
+    type Key = SafeKeyAddress;
+
+    fn key(&self) -> &Self::Key {
+        self.address()
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
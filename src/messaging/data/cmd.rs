@@ -6,8 +6,8 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use super::{register::RegisterWrite, CmdError, Error};
-use crate::types::Chunk;
+use super::{register::RegisterWrite, CmdError, Error, ReceiptAddress};
+use crate::types::{Chunk, SafeKey};
 use serde::{Deserialize, Serialize};
 use xor_name::XorName;
 
@@ -28,6 +28,10 @@ pub enum DataCmd {
     ///
     /// [`Register`]: crate::types::register::Register
     Register(RegisterWrite),
+    /// [`SafeKey`] write operation.
+    ///
+    /// [`SafeKey`]: crate::types::SafeKey
+    StoreSafeKey(SafeKey),
 }
 
 impl DataCmd {
@@ -38,6 +42,7 @@ impl DataCmd {
         match self {
             StoreChunk(_) => CmdError::Data(error),
             Register(c) => c.error(error),
+            StoreSafeKey(_) => CmdError::Data(error),
         }
     }
 
@@ -47,6 +52,31 @@ impl DataCmd {
         match self {
             StoreChunk(c) => *c.name(),
             Register(c) => c.dst_name(),
+            StoreSafeKey(c) => *c.name(),
+        }
+    }
+
+    /// Returns the address a [`StoreReceipt`] for this command is issued for.
+    ///
+    /// [`StoreReceipt`]: super::StoreReceipt
+    pub fn receipt_address(&self) -> ReceiptAddress {
+        use DataCmd::*;
+        match self {
+            StoreChunk(c) => ReceiptAddress::Chunk(*c.address()),
+            Register(c) => ReceiptAddress::Register(*c.address()),
+            StoreSafeKey(c) => ReceiptAddress::SafeKey(*c.address()),
+        }
+    }
+    /// Returns what a [`StoreReceipt`] for this command names as stored:
+    /// the name of the chunk or SafeKey, or the hash of the Register write.
+    ///
+    /// [`StoreReceipt`]: super::StoreReceipt
+    pub fn receipt_content(&self) -> Result<XorName, Error> {
+        use DataCmd::*;
+        match self {
+            StoreChunk(c) => Ok(*c.name()),
+            Register(c) => c.hash(),
+            StoreSafeKey(c) => Ok(*c.name()),
         }
     }
 }
@@ -6,8 +6,8 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use super::RegisterCmd;
-use crate::types::{Error, PublicKey, Result};
+use super::{RegisterCmd, RegisterWrite};
+use crate::types::{register::EntryHash, Error, PublicKey, Result, SafeKey};
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, BTreeSet};
 use xor_name::XorName;
@@ -36,6 +36,8 @@ pub struct DataExchange {
     pub chunk_data: ChunkDataExchange,
     /// Register data exchange.
     pub reg_data: RegisterDataExchange,
+    /// SafeKey data exchange.
+    pub safe_key_data: SafeKeyDataExchange,
 }
 
 /// Chunk data exchange.
@@ -47,7 +49,72 @@ pub struct ChunkDataExchange {
 
 /// Register data exchange.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub struct RegisterDataExchange(pub BTreeMap<XorName, Vec<RegisterCmd>>);
+pub struct RegisterDataExchange(pub BTreeMap<XorName, RegisterLog>);
+
+/// SafeKey data exchange.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SafeKeyDataExchange(pub Vec<SafeKey>);
+
+/// The replicated state of a Register: the latest snapshot of it, if any,
+/// and the ops applied since the snapshot was taken.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegisterLog {
+    /// Snapshot the ops are applied on top of.
+    pub snapshot: Option<RegisterSnapshot>,
+    /// Ops applied since the snapshot was taken, or all of them if there is no snapshot.
+    pub ops: Vec<RegisterCmd>,
+}
+
+/// Compacted ops of a Register, standing for the ops applied to it up to the point it was taken.
+///
+/// It is made of signed ops only, so that the Register can be rebuilt from it without
+/// trusting the Elder which took it: the command which created the Register, signed by the
+/// client which sent it, followed by the edits applied since, each signed by its source.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegisterSnapshot {
+    /// The command which created the Register.
+    pub new: RegisterCmd,
+    /// Edits applied to the Register since it was created, in the order they were applied.
+    /// They are only `Edit` and `EditPolicy` writes, without the commands which carried them.
+    pub edits: Vec<RegisterWrite>,
+}
+
+impl RegisterSnapshot {
+    /// Starts a snapshot from the command which created the Register.
+    /// Fails with `InvalidOperation` if it is not a `New` command.
+    pub fn new(new: RegisterCmd) -> Result<Self> {
+        match new.write {
+            RegisterWrite::New(_) => Ok(Self { new, edits: vec![] }),
+            _ => Err(Error::InvalidOperation),
+        }
+    }
+
+    /// Adds edits applied to the Register, leaving out those already part of the snapshot.
+    /// Fails with `InvalidOperation` if one is not an `Edit` or `EditPolicy` write.
+    pub fn extend(&mut self, edits: impl IntoIterator<Item = RegisterWrite>) -> Result<()> {
+        let mut entries: BTreeSet<EntryHash> = self
+            .edits
+            .iter()
+            .filter_map(|edit| match edit {
+                RegisterWrite::Edit(op) => Some(op.crdt_op.hash()),
+                _ => None,
+            })
+            .collect();
+
+        for edit in edits {
+            let is_new = match &edit {
+                RegisterWrite::Edit(op) => entries.insert(op.crdt_op.hash()),
+                RegisterWrite::EditPolicy(_) => !self.edits.contains(&edit),
+                _ => return Err(Error::InvalidOperation),
+            };
+            if is_new {
+                self.edits.push(edit);
+            }
+        }
+
+        Ok(())
+    }
+}
 
 /// The degree to which storage has been used.
 /// Expressed in values between 0-10, where each unit represents 10-percentage points.
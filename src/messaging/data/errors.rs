@@ -6,6 +6,7 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
+use super::RegisterQuotas;
 use crate::types::DataAddress;
 use crate::types::PublicKey;
 use serde::{Deserialize, Serialize};
@@ -51,6 +52,13 @@ pub enum Error {
     /// Invalid Operation such as a POST on ImmutableData
     #[error("Invalid operation: {0}")]
     InvalidOperation(String),
+    /// The client, or the Elders for all clients, reached the maximum number of subscriptions
+    /// they can hold
+    #[error("Too many subscriptions, at most {0} can be held")]
+    TooManySubscriptions(usize),
+    /// The owner of the Register reached one of its Register quotas at the section, which are given
+    #[error("Register quota exceeded, the limits are {0:?}")]
+    RegisterQuotaExceeded(RegisterQuotas),
     /// There was an error forming the OperationId
     #[error("Operation id could not be derived.")]
     NoOperationId,
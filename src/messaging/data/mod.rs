@@ -12,22 +12,26 @@ mod cmd;
 mod data_exchange;
 mod errors;
 mod query;
+mod receipt;
 mod register;
+mod store_cost;
 
 pub use self::{
     cmd::DataCmd,
     data_exchange::{
         ChunkDataExchange, ChunkMetadata, DataExchange, HolderMetadata, RegisterDataExchange,
-        StorageLevel,
+        RegisterLog, RegisterSnapshot, SafeKeyDataExchange, StorageLevel,
     },
     errors::{Error, Result},
     query::DataQuery,
-    register::{RegisterCmd, RegisterRead, RegisterWrite},
+    receipt::{ReceiptAddress, StoreReceipt},
+    register::{RegisterCmd, RegisterQuotaUsage, RegisterQuotas, RegisterRead, RegisterWrite},
+    store_cost::{store_cost, STORE_COST_NANOS_PER_BYTE},
 };
 
 use crate::types::{
-    register::{Entry, EntryHash, Permissions, Policy, Register},
-    Chunk, ChunkAddress, DataAddress, PublicKey,
+    register::{Entry, EntryHash, Permissions, Policy, Register, RegisterOp},
+    Chunk, ChunkAddress, DataAddress, PublicKey, SafeKey, SafeKeyAddress, Token,
 };
 use crate::{
     messaging::{data::Error as ErrorMessage, MessageId},
@@ -47,6 +51,36 @@ pub fn operation_id(address: &ChunkAddress) -> Result<OperationId> {
     utils::encode(address).map_err(|_| Error::NoOperationId)
 }
 
+/// Return operation Id of a query for the existence of the chunks at the given addresses
+pub fn chunk_existence_operation_id(addresses: &[ChunkAddress]) -> Result<OperationId> {
+    let serialised = utils::serialise(&addresses).map_err(|_| Error::NoOperationId)?;
+    Ok(format!(
+        "ChunkExists-{:?}",
+        XorName::from_content(&serialised)
+    ))
+}
+
+/// Return operation Id of a query for the cost of storing `bytes` at the given address
+pub fn store_cost_operation_id(bytes: u64, address: &DataAddress) -> Result<OperationId> {
+    Ok(format!(
+        "GetStoreCost-{}-{:?}",
+        bytes,
+        address
+            .encode_to_zbase32()
+            .map_err(|_| Error::NoOperationId)?
+    ))
+}
+
+/// Return operation Id of a query for the SafeKey at the given address
+pub fn safe_key_operation_id(address: &SafeKeyAddress) -> Result<OperationId> {
+    Ok(format!(
+        "GetSafeKey-{:?}",
+        DataAddress::SafeKey(*address)
+            .encode_to_zbase32()
+            .map_err(|_| Error::NoOperationId)?
+    ))
+}
+
 /// A message indicating that an error occurred as a node was handling a client's message.
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
@@ -72,6 +106,23 @@ pub enum ServiceMsg {
     /// the eventually consistent nature of the network, it may be necessary to continually retry
     /// operations that depend on the effects of mutations.
     Cmd(DataCmd),
+    /// Messages that lead to mutation, for which a [`CmdReceipt`] is sent back once the
+    /// Elders of the section holding the data have handled them.
+    ///
+    /// This must be sent to all of the Elders, so that enough of them can sign the receipt.
+    /// [`CmdReceipt`]: Self::CmdReceipt
+    CmdWithReceipt(DataCmd),
+    /// The section-signed receipt for a [`CmdWithReceipt`].
+    ///
+    /// [`CmdWithReceipt`]: Self::CmdWithReceipt
+    CmdReceipt {
+        /// Proof that the data was stored.
+        receipt: StoreReceipt,
+        /// ID of the [`CmdWithReceipt`] message.
+        ///
+        /// [`CmdWithReceipt`]: Self::CmdWithReceipt
+        correlation_id: MessageId,
+    },
     /// A read-only operation.
     ///
     /// Senders should eventually receive either a corresponding [`QueryResponse`] or an error in
@@ -98,13 +149,21 @@ pub enum ServiceMsg {
     },
     /// A message indicating that an error occurred as a node was handling a client's message.
     ServiceError(ServiceError),
+    /// An edit applied to a [`Register`] the client subscribed to with [`RegisterRead::Subscribe`].
+    ///
+    /// The op carries the signature of its source, but the message itself is not signed by
+    /// the Elders, so it does not prove the op was applied by the section: it is a
+    /// notification, to be confirmed by reading the Register.
+    ///
+    /// [`Register`]: crate::types::register::Register
+    RegisterEdited(RegisterOp<Entry>),
 }
 
 impl ServiceMsg {
     /// Returns the destination address for Commands and Queries only.
     pub fn dst_address(&self) -> Option<XorName> {
         match self {
-            Self::Cmd(cmd) => Some(cmd.dst_name()),
+            Self::Cmd(cmd) | Self::CmdWithReceipt(cmd) => Some(cmd.dst_name()),
             Self::Query(query) => Some(query.dst_name()),
             _ => None,
         }
@@ -131,6 +190,8 @@ pub enum QueryResponse {
     //
     /// Response to [`ChunkRead::Get`].
     GetChunk(Result<Chunk>),
+    /// Response to [`DataQuery::ChunkExists`], with the addresses of the chunks found.
+    ChunkExists((Result<BTreeSet<ChunkAddress>>, OperationId)),
     //
     // ===== Register Data =====
     //
@@ -144,6 +205,31 @@ pub enum QueryResponse {
     GetRegisterPolicy((Result<Policy>, OperationId)),
     /// Response to [`RegisterRead::GetUserPermissions`].
     GetRegisterUserPermissions((Result<Permissions>, OperationId)),
+    /// Response to [`RegisterRead::GetEntryParents`].
+    GetRegisterEntryParents((Result<BTreeSet<(EntryHash, Entry)>>, OperationId)),
+    /// Response to [`RegisterRead::GetEntryChildren`].
+    GetRegisterEntryChildren((Result<BTreeSet<(EntryHash, Entry)>>, OperationId)),
+    /// Response to [`RegisterRead::GetHistory`].
+    GetRegisterHistory((Result<Vec<(EntryHash, Entry)>>, OperationId)),
+    /// Response to [`RegisterRead::GetAncestors`].
+    GetRegisterAncestors((Result<Vec<(EntryHash, Entry)>>, OperationId)),
+    /// Response to [`RegisterRead::GetAt`].
+    GetRegisterAt((Result<Vec<(EntryHash, Entry)>>, OperationId)),
+    /// Response to [`RegisterRead::GetQuotaUsage`].
+    GetRegisterQuotaUsage((Result<RegisterQuotaUsage>, OperationId)),
+    /// Response to [`RegisterRead::Subscribe`], with the number of seconds the subscription
+    /// was granted for.
+    SubscribeToRegister((Result<u64>, OperationId)),
+    //
+    // ===== SafeKey =====
+    //
+    /// Response to [`DataQuery::GetSafeKey`].
+    GetSafeKey((Result<SafeKey>, OperationId)),
+    //
+    // ===== Store cost =====
+    //
+    /// Response to [`DataQuery::GetStoreCost`], with the price quoted by the section.
+    GetStoreCost((Result<Token>, OperationId)),
 }
 
 impl QueryResponse {
@@ -152,11 +238,21 @@ impl QueryResponse {
         use QueryResponse::*;
         match self {
             GetChunk(result) => result.is_ok(),
+            ChunkExists((result, _op_id)) => result.is_ok(),
             GetRegister((result, _op_id)) => result.is_ok(),
             GetRegisterOwner((result, _op_id)) => result.is_ok(),
             ReadRegister((result, _op_id)) => result.is_ok(),
             GetRegisterPolicy((result, _op_id)) => result.is_ok(),
             GetRegisterUserPermissions((result, _op_id)) => result.is_ok(),
+            GetRegisterEntryParents((result, _op_id)) => result.is_ok(),
+            GetRegisterEntryChildren((result, _op_id)) => result.is_ok(),
+            GetRegisterHistory((result, _op_id)) => result.is_ok(),
+            GetRegisterAncestors((result, _op_id)) => result.is_ok(),
+            GetRegisterAt((result, _op_id)) => result.is_ok(),
+            GetRegisterQuotaUsage((result, _op_id)) => result.is_ok(),
+            SubscribeToRegister((result, _op_id)) => result.is_ok(),
+            GetSafeKey((result, _op_id)) => result.is_ok(),
+            GetStoreCost((result, _op_id)) => result.is_ok(),
         }
     }
 
@@ -169,6 +265,10 @@ impl QueryResponse {
                 Ok(_) => false,
                 Err(error) => matches!(*error, ErrorMessage::DataNotFound(_)),
             },
+            ChunkExists((result, _op_id)) => match result {
+                Ok(_) => false,
+                Err(error) => matches!(*error, ErrorMessage::DataNotFound(_)),
+            },
             GetRegister((result, _op_id)) => match result {
                 Ok(_) => false,
                 Err(error) => matches!(*error, ErrorMessage::DataNotFound(_)),
@@ -189,6 +289,42 @@ impl QueryResponse {
                 Ok(_) => false,
                 Err(error) => matches!(*error, ErrorMessage::DataNotFound(_)),
             },
+            GetRegisterEntryParents((result, _op_id)) => match result {
+                Ok(_) => false,
+                Err(error) => matches!(*error, ErrorMessage::DataNotFound(_)),
+            },
+            GetRegisterEntryChildren((result, _op_id)) => match result {
+                Ok(_) => false,
+                Err(error) => matches!(*error, ErrorMessage::DataNotFound(_)),
+            },
+            GetRegisterHistory((result, _op_id)) => match result {
+                Ok(_) => false,
+                Err(error) => matches!(*error, ErrorMessage::DataNotFound(_)),
+            },
+            GetRegisterAncestors((result, _op_id)) => match result {
+                Ok(_) => false,
+                Err(error) => matches!(*error, ErrorMessage::DataNotFound(_)),
+            },
+            GetRegisterAt((result, _op_id)) => match result {
+                Ok(_) => false,
+                Err(error) => matches!(*error, ErrorMessage::DataNotFound(_)),
+            },
+            GetRegisterQuotaUsage((result, _op_id)) => match result {
+                Ok(_) => false,
+                Err(error) => matches!(*error, ErrorMessage::DataNotFound(_)),
+            },
+            SubscribeToRegister((result, _op_id)) => match result {
+                Ok(_) => false,
+                Err(error) => matches!(*error, ErrorMessage::DataNotFound(_)),
+            },
+            GetSafeKey((result, _op_id)) => match result {
+                Ok(_) => false,
+                Err(error) => matches!(*error, ErrorMessage::DataNotFound(_)),
+            },
+            GetStoreCost((result, _op_id)) => match result {
+                Ok(_) => false,
+                Err(error) => matches!(*error, ErrorMessage::DataNotFound(_)),
+            },
         }
     }
 
@@ -220,11 +356,21 @@ impl QueryResponse {
                 },
             },
 
-            GetRegister((_, operation_id))
+            ChunkExists((_, operation_id))
+            | GetRegister((_, operation_id))
             | GetRegisterOwner((_, operation_id))
             | ReadRegister((_, operation_id))
             | GetRegisterPolicy((_, operation_id))
-            | GetRegisterUserPermissions((_, operation_id)) => Ok(operation_id.clone()),
+            | GetRegisterUserPermissions((_, operation_id))
+            | GetRegisterEntryParents((_, operation_id))
+            | GetRegisterEntryChildren((_, operation_id))
+            | GetRegisterHistory((_, operation_id))
+            | GetRegisterAncestors((_, operation_id))
+            | GetRegisterAt((_, operation_id))
+            | GetRegisterQuotaUsage((_, operation_id))
+            | SubscribeToRegister((_, operation_id))
+            | GetSafeKey((_, operation_id))
+            | GetStoreCost((_, operation_id)) => Ok(operation_id.clone()),
         }
     }
 }
@@ -270,11 +416,27 @@ impl TryFrom<QueryResponse> for Chunk {
     }
 }
 
+try_from!(BTreeSet<ChunkAddress>, ChunkExists);
 try_from!(Register, GetRegister);
 try_from!(PublicKey, GetRegisterOwner);
-try_from!(BTreeSet<(EntryHash, Entry)>, ReadRegister);
+try_from!(
+    BTreeSet<(EntryHash, Entry)>,
+    ReadRegister,
+    GetRegisterEntryParents,
+    GetRegisterEntryChildren
+);
+try_from!(
+    Vec<(EntryHash, Entry)>,
+    GetRegisterHistory,
+    GetRegisterAncestors,
+    GetRegisterAt
+);
 try_from!(Policy, GetRegisterPolicy);
+try_from!(u64, SubscribeToRegister);
+try_from!(RegisterQuotaUsage, GetRegisterQuotaUsage);
 try_from!(Permissions, GetRegisterUserPermissions);
+try_from!(SafeKey, GetSafeKey);
+try_from!(Token, GetStoreCost);
 
 #[cfg(test)]
 mod tests {
@@ -339,6 +501,34 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn chunk_existence_response_matches_query() -> Result<()> {
+        let addresses = (0..5)
+            .map(|_| ChunkAddress(XorName::random()))
+            .collect::<Vec<_>>();
+        let query = DataQuery::ChunkExists(addresses.clone());
+
+        let response = QueryResponse::ChunkExists((
+            Ok(addresses[1..3].iter().copied().collect()),
+            query.operation_id()?,
+        ));
+        assert_eq!(response.operation_id()?, query.operation_id()?);
+        assert_eq!(
+            BTreeSet::try_from(response),
+            Ok(addresses[1..3].iter().copied().collect())
+        );
+
+        // the same addresses in another order are another query
+        let mut reversed = addresses;
+        reversed.reverse();
+        assert_ne!(
+            DataQuery::ChunkExists(reversed).operation_id()?,
+            query.operation_id()?
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn wire_msg_payload() -> Result<()> {
         use crate::messaging::data::DataCmd;
@@ -6,8 +6,11 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use super::{operation_id, register::RegisterRead, Error, OperationId, QueryResponse, Result};
-use crate::types::ChunkAddress;
+use super::{
+    chunk_existence_operation_id, operation_id, register::RegisterRead, safe_key_operation_id,
+    store_cost_operation_id, Error, OperationId, QueryResponse, Result,
+};
+use crate::types::{ChunkAddress, DataAddress, SafeKeyAddress};
 use serde::{Deserialize, Serialize};
 use xor_name::XorName;
 
@@ -26,10 +29,35 @@ pub enum DataQuery {
     /// [`Chunk`]: crate::types::Chunk
     /// [`GetChunk`]: QueryResponse::GetChunk
     GetChunk(ChunkAddress),
+    /// Find out which of the [`Chunk`]s at the given addresses are stored on the network,
+    /// without retrieving their contents.
+    ///
+    /// This should eventually lead to a [`ChunkExists`] response.
+    /// [`Chunk`]: crate::types::Chunk
+    /// [`ChunkExists`]: QueryResponse::ChunkExists
+    ChunkExists(Vec<ChunkAddress>),
     /// [`Register`] read operation.
     ///
     /// [`Register`]: crate::types::register::Register
     Register(RegisterRead),
+    /// Retrieve the [`SafeKey`] at the given address.
+    ///
+    /// This should eventually lead to a [`GetSafeKey`] response.
+    /// [`SafeKey`]: crate::types::SafeKey
+    /// [`GetSafeKey`]: QueryResponse::GetSafeKey
+    GetSafeKey(SafeKeyAddress),
+    /// Get the price the section holding the given address asks for storing `bytes` there,
+    /// following the [`store_cost`] curve.
+    ///
+    /// This should eventually lead to a [`GetStoreCost`] response.
+    /// [`store_cost`]: super::store_cost
+    /// [`GetStoreCost`]: QueryResponse::GetStoreCost
+    GetStoreCost {
+        /// Number of bytes to store.
+        bytes: u64,
+        /// Address of the data to store.
+        address: DataAddress,
+    },
 }
 
 impl DataQuery {
@@ -39,7 +67,19 @@ impl DataQuery {
         use DataQuery::*;
         match self {
             GetChunk(_) => Ok(QueryResponse::GetChunk(Err(error))),
+            ChunkExists(addresses) => Ok(QueryResponse::ChunkExists((
+                Err(error),
+                chunk_existence_operation_id(addresses)?,
+            ))),
             Register(q) => q.error(error),
+            GetSafeKey(address) => Ok(QueryResponse::GetSafeKey((
+                Err(error),
+                safe_key_operation_id(address)?,
+            ))),
+            GetStoreCost { bytes, address } => Ok(QueryResponse::GetStoreCost((
+                Err(error),
+                store_cost_operation_id(*bytes, address)?,
+            ))),
         }
     }
 
@@ -48,7 +88,13 @@ impl DataQuery {
         use DataQuery::*;
         match self {
             GetChunk(address) => *address.name(),
+            ChunkExists(addresses) => addresses
+                .first()
+                .map(|address| *address.name())
+                .unwrap_or_default(),
             Register(q) => q.dst_name(),
+            GetSafeKey(address) => *address.name(),
+            GetStoreCost { address, .. } => *address.name(),
         }
     }
 
@@ -59,7 +105,10 @@ impl DataQuery {
     pub fn operation_id(&self) -> Result<OperationId> {
         match self {
             DataQuery::GetChunk(address) => operation_id(address),
+            DataQuery::ChunkExists(addresses) => chunk_existence_operation_id(addresses),
             DataQuery::Register(read) => read.operation_id(),
+            DataQuery::GetSafeKey(address) => safe_key_operation_id(address),
+            DataQuery::GetStoreCost { bytes, address } => store_cost_operation_id(*bytes, address),
         }
     }
 }
@@ -0,0 +1,149 @@
+// Copyright 2021 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::{Error, Result};
+use crate::messaging::{system::KeyedSig, MessageId};
+use crate::types::{ChunkAddress, RegisterAddress, SafeKeyAddress};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use xor_name::XorName;
+
+/// Address of the data a [`StoreReceipt`] is issued for.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum ReceiptAddress {
+    /// A [`Chunk`] stored at Adults.
+    ///
+    /// [`Chunk`]: crate::types::Chunk
+    Chunk(ChunkAddress),
+    /// A [`Register`] write, stored at Elders.
+    ///
+    /// [`Register`]: crate::types::register::Register
+    Register(RegisterAddress),
+    /// A [`SafeKey`], stored at Elders.
+    ///
+    /// [`SafeKey`]: crate::types::SafeKey
+    SafeKey(SafeKeyAddress),
+}
+
+impl ReceiptAddress {
+    /// The xorname of the data.
+    pub fn name(&self) -> &XorName {
+        match self {
+            Self::Chunk(address) => address.name(),
+            Self::Register(address) => address.name(),
+            Self::SafeKey(address) => address.name(),
+        }
+    }
+}
+
+/// Proof that data was stored, signed by the section holding it.
+///
+/// Sent in reply to a [`CmdWithReceipt`] once the data is stored: for data held by Elders,
+/// once they have stored it, and for chunks, once the Adults holding them have confirmed
+/// storing them to the Elders.
+///
+/// Each receipt is signed for a single command, identified by the ID of its message, so that
+/// the signature shares for different commands writing to the same data are never mixed up.
+/// The holders are the ones each Elder knows of: should the Elders disagree about them,
+/// their shares are over different bytes and no receipt is issued.
+///
+/// [`CmdWithReceipt`]: super::ServiceMsg::CmdWithReceipt
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct StoreReceipt {
+    /// Address of the data stored.
+    pub address: ReceiptAddress,
+    /// What was stored at the address: the name of the chunk or SafeKey,
+    /// or the hash of the Register write.
+    pub content: XorName,
+    /// Names of the nodes the data was stored at.
+    pub holders: BTreeSet<XorName>,
+    /// ID of the [`CmdWithReceipt`] message the receipt is for.
+    ///
+    /// [`CmdWithReceipt`]: super::ServiceMsg::CmdWithReceipt
+    pub correlation_id: MessageId,
+    /// Section signature over the address, content, holders and correlation ID.
+    pub sig: KeyedSig,
+}
+
+impl StoreReceipt {
+    /// Returns the bytes the section signs for a receipt of `content` stored at `address`
+    /// by `holders`, for the command message `correlation_id`.
+    pub fn bytes_to_sign(
+        address: &ReceiptAddress,
+        content: &XorName,
+        holders: &BTreeSet<XorName>,
+        correlation_id: &MessageId,
+    ) -> Result<Vec<u8>> {
+        bincode::serialize(&(address, content, holders, correlation_id)).map_err(|err| {
+            Error::InvalidOperation(format!("Could not serialise store receipt: {}", err))
+        })
+    }
+
+    /// Verifies the section signature over the address, content, holders and correlation ID.
+    ///
+    /// This does not check that the signing key is the one of the section holding the data.
+    pub fn verify(&self) -> bool {
+        Self::bytes_to_sign(
+            &self.address,
+            &self.content,
+            &self.holders,
+            &self.correlation_id,
+        )
+        .map(|bytes| self.sig.verify(&bytes))
+        .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ReceiptAddress, StoreReceipt};
+    use crate::messaging::{system::KeyedSig, MessageId};
+    use crate::types::ChunkAddress;
+    use eyre::Result;
+    use xor_name::XorName;
+
+    #[test]
+    fn receipt_signature_covers_content_holders_and_command() -> Result<()> {
+        let secret_key = bls::SecretKey::random();
+        let name = XorName::random();
+        let address = ReceiptAddress::Chunk(ChunkAddress(name));
+        let holders = (0..4).map(|_| XorName::random()).collect();
+        let correlation_id = MessageId::new();
+
+        let bytes = StoreReceipt::bytes_to_sign(&address, &name, &holders, &correlation_id)?;
+        let receipt = StoreReceipt {
+            address,
+            content: name,
+            holders,
+            correlation_id,
+            sig: KeyedSig {
+                public_key: secret_key.public_key(),
+                signature: secret_key.sign(&bytes),
+            },
+        };
+        assert!(receipt.verify());
+
+        let mut other = receipt.clone();
+        other.address = ReceiptAddress::Chunk(ChunkAddress(XorName::random()));
+        assert!(!other.verify());
+
+        let mut other = receipt.clone();
+        other.content = XorName::random();
+        assert!(!other.verify());
+
+        let mut other = receipt.clone();
+        let _ = other.holders.insert(XorName::random());
+        assert!(!other.verify());
+
+        let mut other = receipt;
+        other.correlation_id = MessageId::new();
+        assert!(!other.verify());
+
+        Ok(())
+    }
+}
@@ -9,7 +9,7 @@
 use super::{CmdError, Error, QueryResponse, Result};
 use crate::messaging::data::OperationId;
 use crate::types::{
-    register::{Entry, Register, RegisterOp, User},
+    register::{Entry, EntryHash, PolicyOp, Register, RegisterOp, User},
     PublicKey, RegisterAddress as Address,
 };
 use serde::{Deserialize, Serialize};
@@ -54,6 +54,120 @@ pub enum RegisterRead {
     ///
     /// [`GetRegisterOwner`]: QueryResponse::GetRegisterOwner
     GetOwner(Address),
+    /// Retrieve the entries written on top of the entry with the given hash in the [`Register`].
+    ///
+    /// This should eventually lead to a [`GetRegisterEntryParents`] response.
+    ///
+    /// [`GetRegisterEntryParents`]: QueryResponse::GetRegisterEntryParents
+    GetEntryParents {
+        /// Register address.
+        address: Address,
+        /// Hash of the entry.
+        hash: EntryHash,
+    },
+    /// Retrieve the entries which the entry with the given hash in the [`Register`] was
+    /// written on top of.
+    ///
+    /// This should eventually lead to a [`GetRegisterEntryChildren`] response.
+    ///
+    /// [`GetRegisterEntryChildren`]: QueryResponse::GetRegisterEntryChildren
+    GetEntryChildren {
+        /// Register address.
+        address: Address,
+        /// Hash of the entry.
+        hash: EntryHash,
+    },
+    /// Retrieve a page of the entries of the [`Register`] at the given address in topological
+    /// order, i.e. at most [`MAX_REG_HISTORY_PAGE_LEN`] entries starting at the given index.
+    ///
+    /// This should eventually lead to a [`GetRegisterHistory`] response.
+    ///
+    /// [`GetRegisterHistory`]: QueryResponse::GetRegisterHistory
+    /// [`MAX_REG_HISTORY_PAGE_LEN`]: crate::types::register::MAX_REG_HISTORY_PAGE_LEN
+    GetHistory {
+        /// Register address.
+        address: Address,
+        /// Index in the history of the first entry to retrieve.
+        start: usize,
+    },
+    /// Retrieve the entries preceding the entry with the given hash in the [`Register`],
+    /// up to the given depth, which is capped to [`MAX_REG_ANCESTORS_DEPTH`].
+    ///
+    /// This should eventually lead to a [`GetRegisterAncestors`] response.
+    ///
+    /// [`GetRegisterAncestors`]: QueryResponse::GetRegisterAncestors
+    /// [`MAX_REG_ANCESTORS_DEPTH`]: crate::types::register::MAX_REG_ANCESTORS_DEPTH
+    GetAncestors {
+        /// Register address.
+        address: Address,
+        /// Hash of the entry.
+        hash: EntryHash,
+        /// Number of levels of the entry DAG to go back through.
+        depth: usize,
+    },
+    /// Retrieve the entry with the given hash in the [`Register`] along with all the entries it
+    /// was written on top of, i.e. the [`Register`] as of that version, in topological order.
+    ///
+    /// This should eventually lead to a [`GetRegisterAt`] response.
+    ///
+    /// [`GetRegisterAt`]: QueryResponse::GetRegisterAt
+    GetAt {
+        /// Register address.
+        address: Address,
+        /// Hash of the entry standing for the version.
+        version: EntryHash,
+    },
+    /// Retrieve the usage of the [`Register`] quotas of the requester, as an owner, at the section holding
+    /// the given address, along with the quotas.
+    ///
+    /// This should eventually lead to a [`GetRegisterQuotaUsage`] response.
+    ///
+    /// [`GetRegisterQuotaUsage`]: QueryResponse::GetRegisterQuotaUsage
+    GetQuotaUsage(Address),
+    /// Subscribe to the edits of the [`Register`] at the given address, which are then
+    /// pushed to the client with [`RegisterEdited`] messages until the subscription expires.
+    ///
+    /// Elders may grant a shorter duration than requested, and limit the number of
+    /// subscriptions of a client. A duration of zero cancels the subscription.
+    /// This should eventually lead to a [`SubscribeToRegister`] response.
+    ///
+    /// [`RegisterEdited`]: super::ServiceMsg::RegisterEdited
+    /// [`SubscribeToRegister`]: QueryResponse::SubscribeToRegister
+    Subscribe {
+        /// Register address.
+        address: Address,
+        /// Number of seconds the subscription is requested for.
+        duration_secs: u64,
+    },
+}
+
+/// Limits on the [`Register`]s a key can own at a section, or its usage of them.
+#[derive(
+    Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize,
+)]
+pub struct RegisterQuotas {
+    /// Number of Registers held.
+    pub registers: u64,
+    /// Number of ops written to the Registers, their creation included.
+    pub ops: u64,
+    /// Size in bytes of the ops written to the Registers.
+    pub bytes: u64,
+}
+
+impl RegisterQuotas {
+    /// Whether any of the counts is over the corresponding limit.
+    pub fn exceeds(&self, limits: &RegisterQuotas) -> bool {
+        self.registers > limits.registers || self.ops > limits.ops || self.bytes > limits.bytes
+    }
+}
+
+/// Usage of the [`Register`] quotas of a key at a section.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct RegisterQuotaUsage {
+    /// What the Registers owned by the key used so far.
+    pub used: RegisterQuotas,
+    /// The limits enforced by the section.
+    pub limits: RegisterQuotas,
 }
 
 /// A [`Register`] write operation.
@@ -75,6 +189,11 @@ pub enum RegisterWrite {
     New(Register),
     /// Edit a [`Register`].
     Edit(RegisterOp<Entry>),
+    /// Edit the policy of a [`Register`], e.g. to grant or revoke permissions or to transfer
+    /// its ownership.
+    ///
+    /// This operation will result in an error if not signed by the current owner.
+    EditPolicy(PolicyOp),
     /// Delete a private [`Register`].
     ///
     /// This operation will result in an error if applied to a public register. Only private
@@ -106,6 +225,34 @@ impl RegisterRead {
                 Err(error),
                 self.operation_id()?,
             ))),
+            RegisterRead::GetEntryParents { .. } => Ok(QueryResponse::GetRegisterEntryParents((
+                Err(error),
+                self.operation_id()?,
+            ))),
+            RegisterRead::GetEntryChildren { .. } => Ok(QueryResponse::GetRegisterEntryChildren((
+                Err(error),
+                self.operation_id()?,
+            ))),
+            RegisterRead::GetHistory { .. } => Ok(QueryResponse::GetRegisterHistory((
+                Err(error),
+                self.operation_id()?,
+            ))),
+            RegisterRead::GetAncestors { .. } => Ok(QueryResponse::GetRegisterAncestors((
+                Err(error),
+                self.operation_id()?,
+            ))),
+            RegisterRead::GetAt { .. } => Ok(QueryResponse::GetRegisterAt((
+                Err(error),
+                self.operation_id()?,
+            ))),
+            RegisterRead::GetQuotaUsage(_) => Ok(QueryResponse::GetRegisterQuotaUsage((
+                Err(error),
+                self.operation_id()?,
+            ))),
+            RegisterRead::Subscribe { .. } => Ok(QueryResponse::SubscribeToRegister((
+                Err(error),
+                self.operation_id()?,
+            ))),
         }
     }
 
@@ -116,7 +263,14 @@ impl RegisterRead {
             | RegisterRead::Read(ref address)
             | RegisterRead::GetPolicy(ref address)
             | RegisterRead::GetUserPermissions { ref address, .. }
-            | RegisterRead::GetOwner(ref address) => *address,
+            | RegisterRead::GetOwner(ref address)
+            | RegisterRead::GetEntryParents { ref address, .. }
+            | RegisterRead::GetEntryChildren { ref address, .. }
+            | RegisterRead::GetHistory { ref address, .. }
+            | RegisterRead::GetAncestors { ref address, .. }
+            | RegisterRead::GetAt { ref address, .. }
+            | RegisterRead::GetQuotaUsage(ref address)
+            | RegisterRead::Subscribe { ref address, .. } => *address,
         }
     }
 
@@ -127,7 +281,14 @@ impl RegisterRead {
             | RegisterRead::Read(ref address)
             | RegisterRead::GetPolicy(ref address)
             | RegisterRead::GetUserPermissions { ref address, .. }
-            | RegisterRead::GetOwner(ref address) => *address.name(),
+            | RegisterRead::GetOwner(ref address)
+            | RegisterRead::GetEntryParents { ref address, .. }
+            | RegisterRead::GetEntryChildren { ref address, .. }
+            | RegisterRead::GetHistory { ref address, .. }
+            | RegisterRead::GetAncestors { ref address, .. }
+            | RegisterRead::GetAt { ref address, .. }
+            | RegisterRead::GetQuotaUsage(ref address)
+            | RegisterRead::Subscribe { ref address, .. } => *address.name(),
         }
     }
 
@@ -167,6 +328,71 @@ impl RegisterRead {
                     .encode_to_zbase32()
                     .map_err(|_| Error::NoOperationId)?
             )),
+            RegisterRead::GetEntryParents {
+                ref address,
+                ref hash,
+            } => Ok(format!(
+                "GetEntryParents-{:?}-{}",
+                address
+                    .encode_to_zbase32()
+                    .map_err(|_| Error::NoOperationId)?,
+                hex::encode(hash)
+            )),
+            RegisterRead::GetEntryChildren {
+                ref address,
+                ref hash,
+            } => Ok(format!(
+                "GetEntryChildren-{:?}-{}",
+                address
+                    .encode_to_zbase32()
+                    .map_err(|_| Error::NoOperationId)?,
+                hex::encode(hash)
+            )),
+            RegisterRead::GetHistory { ref address, start } => Ok(format!(
+                "GetHistory-{:?}-{}",
+                address
+                    .encode_to_zbase32()
+                    .map_err(|_| Error::NoOperationId)?,
+                start
+            )),
+            RegisterRead::GetAncestors {
+                ref address,
+                ref hash,
+                depth,
+            } => Ok(format!(
+                "GetAncestors-{:?}-{}-{}",
+                address
+                    .encode_to_zbase32()
+                    .map_err(|_| Error::NoOperationId)?,
+                hex::encode(hash),
+                depth
+            )),
+            RegisterRead::GetQuotaUsage(ref address) => Ok(format!(
+                "GetQuotaUsage-{:?}",
+                address
+                    .encode_to_zbase32()
+                    .map_err(|_| Error::NoOperationId)?
+            )),
+            RegisterRead::GetAt {
+                ref address,
+                ref version,
+            } => Ok(format!(
+                "GetAt-{:?}-{}",
+                address
+                    .encode_to_zbase32()
+                    .map_err(|_| Error::NoOperationId)?,
+                hex::encode(version)
+            )),
+            RegisterRead::Subscribe {
+                ref address,
+                duration_secs,
+            } => Ok(format!(
+                "Subscribe-{:?}-{}",
+                address
+                    .encode_to_zbase32()
+                    .map_err(|_| Error::NoOperationId)?,
+                duration_secs
+            )),
         }
     }
 }
@@ -184,6 +410,7 @@ impl RegisterWrite {
             RegisterWrite::New(ref data) => *data.name(),
             RegisterWrite::Delete(ref address) => *address.name(),
             RegisterWrite::Edit(ref op) => *op.address.name(),
+            RegisterWrite::EditPolicy(ref op) => *op.address.name(),
         }
     }
 
@@ -193,6 +420,7 @@ impl RegisterWrite {
             Self::New(map) => map.address(),
             Self::Delete(address) => address,
             Self::Edit(ref op) => &op.address,
+            Self::EditPolicy(ref op) => &op.address,
         }
     }
 
@@ -203,4 +431,16 @@ impl RegisterWrite {
             _ => None,
         }
     }
+    /// Hash identifying the write: the hash of the entry written for edits,
+    /// and the hash of the whole write otherwise.
+    pub fn hash(&self) -> Result<XorName> {
+        match self {
+            Self::Edit(op) => Ok(XorName(op.crdt_op.hash())),
+            _ => bincode::serialize(self)
+                .map(|bytes| XorName::from_content(&bytes))
+                .map_err(|err| {
+                    Error::InvalidOperation(format!("Could not serialise register write: {}", err))
+                }),
+        }
+    }
 }
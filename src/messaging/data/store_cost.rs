@@ -0,0 +1,49 @@
+// Copyright 2021 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::StorageLevel;
+use crate::types::Token;
+
+/// Price of storing a byte in an empty section, in nano tokens.
+pub const STORE_COST_NANOS_PER_BYTE: u64 = 1;
+
+/// Price Elders quote for storing `bytes` in their section, given the average storage level
+/// of its Adults, a value between 0 and [`StorageLevel::MAX`].
+///
+/// The price per byte doubles with every level, i.e. every 10 percentage points of fullness,
+/// so that storing gets more expensive as the section fills up. Storing is never free,
+/// zero bytes being priced as one.
+pub fn store_cost(bytes: u64, avg_usage: u8) -> Token {
+    let level = avg_usage.min(StorageLevel::MAX) as u32;
+    let nanos = bytes
+        .max(1)
+        .saturating_mul(STORE_COST_NANOS_PER_BYTE)
+        .saturating_mul(1 << level);
+
+    Token::from_nano(nanos)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::store_cost;
+    use crate::messaging::data::StorageLevel;
+    use crate::types::Token;
+
+    #[test]
+    fn store_cost_grows_with_section_fullness() {
+        assert_eq!(store_cost(1024, 0), Token::from_nano(1024));
+        assert_eq!(store_cost(1024, 1), Token::from_nano(2048));
+        assert_eq!(store_cost(0, 0), Token::from_nano(1));
+
+        let full = store_cost(1024, StorageLevel::MAX);
+        assert_eq!(full, Token::from_nano(1024 * 1024));
+        // levels above the maximum are priced as a full section
+        assert_eq!(store_cost(1024, u8::MAX), full);
+        assert_eq!(store_cost(u64::MAX, 5), Token::from_nano(u64::MAX));
+    }
+}
@@ -6,15 +6,19 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
+use super::SigShare;
 use crate::types::{Chunk, PublicKey};
 use crate::{
     messaging::{
-        data::{DataCmd, DataExchange, DataQuery, Result, StorageLevel},
-        EndUser, ServiceAuth,
+        data::{
+            DataCmd, DataExchange, DataQuery, OperationId, ReceiptAddress, Result, StorageLevel,
+        },
+        EndUser, MessageId, ServiceAuth,
     },
     types::ChunkAddress,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 use xor_name::XorName;
 
 /// Command message sent among nodes
@@ -38,6 +42,17 @@ pub enum NodeCmd {
         auth: ServiceAuth,
         /// Message source
         origin: EndUser,
+        /// ID of the user's command message if the user asked for a store receipt,
+        /// in which case the Adult confirms storing the chunk with [`NodeCmd::ChunkStored`]
+        correlation_id: Option<MessageId>,
+    },
+    /// Sent by an Adult to the Elder which asked it to store a chunk for a store receipt,
+    /// once it has stored the chunk
+    ChunkStored {
+        /// Address of the chunk stored
+        address: ChunkAddress,
+        /// ID of the user's command message
+        correlation_id: MessageId,
     },
     /// Notify Elders on nearing max capacity
     RecordStorageLevel {
@@ -58,6 +73,22 @@ pub enum NodeCmd {
         /// Metadata
         metadata: DataExchange,
     },
+    /// An Elder's signature share over a store receipt,
+    /// sent to the Elder aggregating the shares into the receipt for the user
+    StoreReceiptShare {
+        /// Address of the data stored
+        address: ReceiptAddress,
+        /// What was stored at the address
+        content: XorName,
+        /// Names of the nodes the data was stored at
+        holders: BTreeSet<XorName>,
+        /// Signature share over the receipt
+        sig_share: SigShare,
+        /// The user that has asked for the receipt
+        origin: EndUser,
+        /// ID of the user's command message
+        correlation_id: MessageId,
+    },
 }
 
 /// Query originating at a node
@@ -79,6 +110,17 @@ pub enum NodeQuery {
         /// The user that has initiated this query
         origin: EndUser,
     },
+    /// Adults report which of the chunks they hold
+    ChunkExists {
+        /// The chunk addresses
+        addresses: Vec<ChunkAddress>,
+        /// Operation id of the query from the user
+        operation_id: OperationId,
+        /// Id of the query message from the user, which the Adults' responses are correlated to
+        correlation_id: MessageId,
+        /// The user that has initiated this query
+        origin: EndUser,
+    },
 }
 
 ///
@@ -87,4 +129,6 @@ pub enum NodeQuery {
 pub enum NodeQueryResponse {
     /// Elder to Adult Get.
     GetChunk(Result<Chunk>),
+    /// Addresses of the chunks held by the Adult, among those it was asked about.
+    ChunkExists((Result<BTreeSet<ChunkAddress>>, OperationId)),
 }
@@ -6,8 +6,9 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
+use crate::messaging::data::RegisterQuotas;
 use crate::node::{Error, Result};
-use crate::routing::NetworkConfig;
+use crate::routing::{NetworkConfig, DEFAULT_REGISTER_QUOTAS};
 use serde::{Deserialize, Serialize};
 use std::{
     collections::BTreeSet,
@@ -40,6 +41,16 @@ pub struct Config {
     /// Upper limit in bytes for allowed network storage on this node.
     #[structopt(short, long)]
     pub max_capacity: Option<u64>,
+    /// Maximum number of Registers each owner can hold at this node's section.
+    #[structopt(long)]
+    pub max_registers_per_owner: Option<u64>,
+    /// Maximum number of Register ops written to the Registers of each owner at this node's section.
+    #[structopt(long)]
+    pub max_register_ops_per_owner: Option<u64>,
+    /// Maximum size in bytes of the Register ops written to the Registers of each owner
+    /// at this node's section.
+    #[structopt(long)]
+    pub max_register_bytes_per_owner: Option<u64>,
     /// Root directory for dbs and cached state. If not set, it defaults to "root_dir"
     /// within the sn_node project data directory, located at:
     /// Linux: $HOME/.safe/node/root_dir
@@ -212,6 +223,18 @@ impl Config {
             self.max_capacity = Some(*max_capacity);
         }
 
+        if let Some(max_registers) = config.max_registers_per_owner {
+            self.max_registers_per_owner = Some(max_registers);
+        }
+
+        if let Some(max_ops) = config.max_register_ops_per_owner {
+            self.max_register_ops_per_owner = Some(max_ops);
+        }
+
+        if let Some(max_bytes) = config.max_register_bytes_per_owner {
+            self.max_register_bytes_per_owner = Some(max_bytes);
+        }
+
         if let Some(root_dir) = &config.root_dir {
             self.root_dir = Some(root_dir.clone());
         }
@@ -289,6 +312,22 @@ impl Config {
         self.max_capacity.unwrap_or(DEFAULT_MAX_CAPACITY)
     }
 
+    /// Quotas enforced on the Registers of each owner, the defaults being used
+    /// for the limits not set.
+    pub fn register_quotas(&self) -> RegisterQuotas {
+        RegisterQuotas {
+            registers: self
+                .max_registers_per_owner
+                .unwrap_or(DEFAULT_REGISTER_QUOTAS.registers),
+            ops: self
+                .max_register_ops_per_owner
+                .unwrap_or(DEFAULT_REGISTER_QUOTAS.ops),
+            bytes: self
+                .max_register_bytes_per_owner
+                .unwrap_or(DEFAULT_REGISTER_QUOTAS.bytes),
+        }
+    }
+
     /// Root directory for dbs and cached state. If not set, it defaults to
     /// `DEFAULT_ROOT_DIR_NAME` within the project's data directory (see `Config::root_dir` for the
     /// directories on each platform).
@@ -483,7 +522,7 @@ fn smoke() {
     // NOTE: IF this value is being changed due to a change in the config,
     // the change in config also be handled in Config::merge()
     // and in examples/config_handling.rs
-    let expected_size = 440;
+    let expected_size = 456;
 
     assert_eq!(std::mem::size_of::<Config>(), expected_size);
 }
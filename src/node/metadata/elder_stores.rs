@@ -30,9 +30,13 @@ impl ElderStores {
         let register_storage = self.network.get_register_storage().await;
         let reg_data = register_storage.get_data_of(prefix).await?;
 
+        let safe_key_storage = self.network.get_safe_key_storage().await;
+        let safe_key_data = safe_key_storage.get_data_of(prefix)?;
+
         Ok(DataExchange {
             chunk_data,
             reg_data,
+            safe_key_data,
         })
     }
 
@@ -42,6 +46,8 @@ impl ElderStores {
         let register_storage = self.network.get_register_storage().await;
 
         register_storage.update(data.reg_data)?;
+        let safe_key_storage = self.network.get_safe_key_storage().await;
+        safe_key_storage.update(data.safe_key_data).await?;
         let _chunks = self.network.update_chunks(data.chunk_data).await;
         Ok(())
     }
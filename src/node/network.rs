@@ -15,7 +15,7 @@ use crate::messaging::{
 use crate::node::{state_db::store_network_keypair, Config as NodeConfig, Error, Result};
 use crate::routing::{
     ChunkStore, Config as RoutingConfig, Error as RoutingError, EventStream, PeerUtils,
-    RegisterStorage, Routing as RoutingNode, SectionAuthorityProviderUtils,
+    RegisterStorage, Routing as RoutingNode, SafeKeyStorage, SectionAuthorityProviderUtils,
 };
 use crate::types::PublicKey;
 use bls::{PublicKey as BlsPublicKey, PublicKeySet};
@@ -41,6 +41,7 @@ impl Network {
             bootstrap_nodes: config.hard_coded_contacts.clone(),
             genesis_key: config.genesis_key.clone(),
             network_config: config.network_config().clone(),
+            register_quotas: config.register_quotas(),
             ..Default::default()
         };
         if let Some(local_addr) = config.local_addr {
@@ -69,6 +70,10 @@ impl Network {
         self.routing.get_chunk_storage().await
     }
 
+    pub(crate) async fn get_safe_key_storage(&self) -> SafeKeyStorage {
+        self.routing.get_safe_key_storage().await
+    }
+
     pub(crate) async fn get_chunk_data_of(&self, prefix: &Prefix) -> ChunkDataExchange {
         self.routing.get_chunk_data_of(prefix).await
     }
@@ -83,11 +83,15 @@ impl Core {
             joins_allowed: true,
             resource_proof: ResourceProof::new(RESOURCE_PROOF_DATA_SIZE, RESOURCE_PROOF_DIFFICULTY),
             register_storage: self.register_storage.clone(),
+            register_subscriptions: self.register_subscriptions.clone(),
             root_storage_dir: self.root_storage_dir.clone(),
             used_space: self.used_space.clone(),
             capacity: self.capacity.clone(),
             chunk_storage: self.chunk_storage.clone(),
+            safe_key_storage: self.safe_key_storage.clone(),
             liveness: self.liveness.clone(),
+            chunk_existence_queries: self.chunk_existence_queries.clone(),
+            pending_chunk_receipts: self.pending_chunk_receipts.clone(),
         })
     }
 
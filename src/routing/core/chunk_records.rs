@@ -12,16 +12,63 @@ use crate::routing::{error::convert_to_error_message, Error};
 use crate::types::{Chunk, PublicKey};
 use crate::{
     messaging::{
-        data::{operation_id, ChunkDataExchange, CmdError, Error as ErrorMessage, StorageLevel},
+        data::{
+            chunk_existence_operation_id, operation_id, ChunkDataExchange, CmdError,
+            Error as ErrorMessage, OperationId, ReceiptAddress, Result as ResultMessage,
+            StorageLevel,
+        },
         system::{NodeCmd, NodeQuery, SystemMsg},
-        AuthorityProof, EndUser, MessageId, ServiceAuth,
+        AuthorityProof, DstLocation, EndUser, MessageId, ServiceAuth,
     },
     types::ChunkAddress,
 };
-use std::collections::BTreeSet;
+use dashmap::DashMap;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    iter,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use tracing::info;
 use xor_name::XorName;
 
+/// Maximum number of chunk existence queries awaiting the answers of Adults at a time.
+const MAX_CHUNK_EXISTENCE_QUERIES: usize = 1024;
+
+/// Time after which a chunk existence query Adults have not all answered is given up on.
+const CHUNK_EXISTENCE_QUERY_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// Maximum number of chunk stores awaiting the confirmations of Adults before a receipt is signed.
+const MAX_PENDING_CHUNK_RECEIPTS: usize = 1024;
+
+/// Time after which a chunk store Adults have not all confirmed is no longer signed a receipt for.
+const PENDING_CHUNK_RECEIPT_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// Chunk existence queries awaiting the answers of Adults, by id of the query message from the user.
+pub(super) type ChunkExistenceQueries = Arc<DashMap<MessageId, ChunkExistenceQuery>>;
+
+#[derive(Debug)]
+pub(super) struct ChunkExistenceQuery {
+    origin: EndUser,
+    operation_id: OperationId,
+    awaiting: BTreeSet<XorName>,
+    existing: BTreeSet<ChunkAddress>,
+    started: Instant,
+}
+
+/// Chunk stores awaiting the confirmations of Adults before a receipt is signed,
+/// by id of the command message from the user.
+pub(super) type PendingChunkReceipts = Arc<DashMap<MessageId, PendingChunkReceipt>>;
+
+#[derive(Debug)]
+pub(super) struct PendingChunkReceipt {
+    address: ChunkAddress,
+    origin: EndUser,
+    holders: BTreeSet<XorName>,
+    awaiting: BTreeSet<XorName>,
+    started: Instant,
+}
+
 impl Core {
     pub(crate) fn get_copy_count(&self) -> usize {
         CHUNK_COPY_COUNT
@@ -77,28 +124,119 @@ impl Core {
         msg_id: MessageId,
         auth: AuthorityProof<ServiceAuth>,
         origin: EndUser,
+        with_receipt: bool,
     ) -> Result<Vec<Command>> {
         trace!("{:?}: {:?}", LogMarker::ChunkStoreReceivedAtElder, chunk);
 
-        let target = *chunk.name();
+        let address = *chunk.address();
+        let targets = self.get_chunk_holder_adults(address.name()).await;
+
+        let aggregation = false;
+
+        if self.get_copy_count() > targets.len() {
+            let error = CmdError::Data(ErrorMessage::InsufficientAdults(*self.section().prefix()));
+            return self.send_cmd_error_response(error, origin, msg_id);
+        }
+
+        // Our share of the receipt is only signed once all the Adults have confirmed storing the chunk
+        let correlation_id = if with_receipt {
+            self.pending_chunk_receipts
+                .retain(|_, pending| pending.started.elapsed() < PENDING_CHUNK_RECEIPT_TIMEOUT);
+            if self.pending_chunk_receipts.len() >= MAX_PENDING_CHUNK_RECEIPTS {
+                warn!(
+                    "Too many chunk stores awaiting receipts, not signing one for {:?}",
+                    address
+                );
+                None
+            } else {
+                let _ = self.pending_chunk_receipts.insert(
+                    msg_id,
+                    PendingChunkReceipt {
+                        address,
+                        origin,
+                        holders: targets.clone(),
+                        awaiting: targets.clone(),
+                        started: Instant::now(),
+                    },
+                );
+                Some(msg_id)
+            }
+        } else {
+            None
+        };
 
         let msg = SystemMsg::NodeCmd(NodeCmd::StoreChunk {
             chunk,
             auth: auth.into_inner(),
             origin,
+            correlation_id,
         });
 
-        let targets = self.get_chunk_holder_adults(&target).await;
+        self.send_node_msg_to_targets(msg, targets, aggregation)
+            .await
+    }
 
-        let aggregation = false;
+    /// Confirms to the Elder which asked for it that we have stored the chunk at `address`.
+    pub(super) fn confirm_chunk_stored(
+        &self,
+        address: ChunkAddress,
+        correlation_id: MessageId,
+        requesting_elder: XorName,
+    ) -> Command {
+        let msg = SystemMsg::NodeCmd(NodeCmd::ChunkStored {
+            address,
+            correlation_id,
+        });
+        let dst = DstLocation::Node {
+            name: requesting_elder,
+            section_pk: *self.section().chain().last_key(),
+        };
 
-        if self.get_copy_count() > targets.len() {
-            let error = CmdError::Data(ErrorMessage::InsufficientAdults(*self.section().prefix()));
-            return self.send_cmd_error_response(error, origin, msg_id);
+        Command::PrepareNodeMsgToSend { msg, dst }
+    }
+
+    /// Records an Adult's confirmation of having stored a chunk the user asked a receipt for.
+    /// Once all Adults asked to store it have confirmed, our share of the receipt is signed.
+    pub(super) async fn record_chunk_stored(
+        &self,
+        holder: XorName,
+        address: ChunkAddress,
+        correlation_id: MessageId,
+    ) -> Result<Vec<Command>> {
+        let all_confirmed = match self.pending_chunk_receipts.get_mut(&correlation_id) {
+            Some(mut pending) if pending.address == address => {
+                if !pending.awaiting.remove(&holder) {
+                    trace!(
+                        "Ignoring unexpected chunk store confirmation from {}",
+                        holder
+                    );
+                    return Ok(vec![]);
+                }
+                pending.awaiting.is_empty()
+            }
+            _ => {
+                trace!("Ignoring chunk store confirmation for unknown chunk store");
+                return Ok(vec![]);
+            }
+        };
+
+        if !all_confirmed {
+            return Ok(vec![]);
         }
 
-        self.send_node_msg_to_targets(msg, targets, aggregation)
-            .await
+        match self.pending_chunk_receipts.remove(&correlation_id) {
+            Some((correlation_id, pending)) => {
+                self.send_store_receipt_share(
+                    ReceiptAddress::Chunk(pending.address),
+                    *pending.address.name(),
+                    pending.holders,
+                    pending.origin,
+                    correlation_id,
+                )
+                .await
+            }
+            None => Ok(vec![]),
+        }
     }
 
     pub(crate) async fn send_error(
@@ -148,4 +286,124 @@ impl Core {
         self.send_node_msg_to_targets(msg, fresh_targets, aggregation)
             .await
     }
+
+    /// Asks the holders of each chunk whether they have it, without moving the chunk contents.
+    /// Chunks which do not belong to our section are not looked up, and thus reported as not found.
+    pub(super) async fn check_chunks_at_adults(
+        &self,
+        addresses: Vec<ChunkAddress>,
+        msg_id: MessageId,
+        origin: EndUser,
+    ) -> Result<Vec<Command>> {
+        trace!(
+            "{:?} preparing to query adults for {} chunks",
+            LogMarker::ChunkExistenceQueryReceivedAtElder,
+            addresses.len()
+        );
+
+        let operation_id = chunk_existence_operation_id(&addresses)?;
+        let prefix = *self.section().prefix();
+
+        let mut addresses_by_holder: BTreeMap<XorName, Vec<ChunkAddress>> = BTreeMap::new();
+        for address in addresses
+            .into_iter()
+            .filter(|address| prefix.matches(address.name()))
+        {
+            for holder in self.get_chunk_holder_adults(address.name()).await {
+                addresses_by_holder.entry(holder).or_default().push(address);
+            }
+        }
+
+        // Adults which never answer would otherwise leave their queries behind forever
+        self.chunk_existence_queries
+            .retain(|_, query| query.started.elapsed() < CHUNK_EXISTENCE_QUERY_TIMEOUT);
+
+        // Reporting no chunks as found is always safe, at worst they are stored again.
+        if addresses_by_holder.is_empty()
+            || self.chunk_existence_queries.len() >= MAX_CHUNK_EXISTENCE_QUERIES
+        {
+            return self.send_chunk_existence_response(
+                Ok(BTreeSet::new()),
+                operation_id,
+                msg_id,
+                origin,
+            );
+        }
+
+        let _ = self.chunk_existence_queries.insert(
+            msg_id,
+            ChunkExistenceQuery {
+                origin,
+                operation_id: operation_id.clone(),
+                awaiting: addresses_by_holder.keys().copied().collect(),
+                existing: BTreeSet::new(),
+                started: Instant::now(),
+            },
+        );
+
+        let mut commands = vec![];
+        for (holder, addresses) in addresses_by_holder {
+            self.liveness
+                .add_a_pending_request_operation(holder, operation_id.clone())
+                .await;
+
+            let msg = SystemMsg::NodeQuery(NodeQuery::ChunkExists {
+                addresses,
+                operation_id: operation_id.clone(),
+                correlation_id: msg_id,
+                origin,
+            });
+            let aggregation = false;
+
+            commands.extend(
+                self.send_node_msg_to_targets(msg, iter::once(holder).collect(), aggregation)
+                    .await?,
+            );
+        }
+
+        Ok(commands)
+    }
+
+    /// Records the answer of an Adult to the chunk existence query from the user message `correlation_id`.
+    /// Once all Adults asked have answered, the chunks found are sent to the user.
+    pub(super) fn record_chunk_existence(
+        &self,
+        holder: XorName,
+        response: ResultMessage<BTreeSet<ChunkAddress>>,
+        operation_id: OperationId,
+        correlation_id: MessageId,
+    ) -> Result<Vec<Command>> {
+        let all_answered = match self.chunk_existence_queries.get_mut(&correlation_id) {
+            Some(mut query)
+                if query.operation_id == operation_id && query.awaiting.contains(&holder) =>
+            {
+                match response {
+                    Ok(existing) => query.existing.extend(existing),
+                    // the chunks it holds are then reported as not found, which at worst
+                    // has them stored again
+                    Err(error) => warn!("Adult {} failed to check its chunks: {:?}", holder, error),
+                }
+                let _ = query.awaiting.remove(&holder);
+                query.awaiting.is_empty()
+            }
+            _ => {
+                trace!("Ignoring chunk existence response for unknown query");
+                return Ok(vec![]);
+            }
+        };
+
+        if !all_answered {
+            return Ok(vec![]);
+        }
+
+        match self.chunk_existence_queries.remove(&correlation_id) {
+            Some((correlation_id, query)) => self.send_chunk_existence_response(
+                Ok(query.existing),
+                query.operation_id,
+                correlation_id,
+                query.origin,
+            ),
+            None => Ok(vec![]),
+        }
+    }
 }
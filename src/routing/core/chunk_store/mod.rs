@@ -7,10 +7,14 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use crate::dbs::{convert_to_error_message, Error, KvStore, Result, Subdir, UsedSpace};
-use crate::messaging::{data::StorageLevel, system::NodeQueryResponse};
+use crate::messaging::{
+    data::{OperationId, StorageLevel},
+    system::NodeQueryResponse,
+};
 use crate::routing::log_markers::LogMarker;
 use crate::types::{Chunk, ChunkAddress};
 use std::{
+    collections::BTreeSet,
     fmt::{self, Display, Formatter},
     path::Path,
     sync::Arc,
@@ -68,6 +72,31 @@ impl ChunkStore {
         NodeQueryResponse::GetChunk(self.get_chunk(address).map_err(convert_to_error_message))
     }
 
+    // Check which of the chunks are in the local store and return NodeQueryResponse
+    pub(crate) fn existing(
+        &self,
+        addresses: &[ChunkAddress],
+        operation_id: OperationId,
+    ) -> NodeQueryResponse {
+        trace!("{:?}", LogMarker::ChunkExistenceQueryReceivedAtAdult);
+        let mut existing = BTreeSet::new();
+        for address in addresses {
+            match self.db.has(address) {
+                Ok(true) => {
+                    let _ = existing.insert(*address);
+                }
+                Ok(false) => {}
+                Err(error) => {
+                    return NodeQueryResponse::ChunkExists((
+                        Err(convert_to_error_message(error)),
+                        operation_id,
+                    ))
+                }
+            }
+        }
+        NodeQueryResponse::ChunkExists((Ok(existing), operation_id))
+    }
+
     pub(super) async fn store(&self, data: &Chunk) -> Result<Option<StorageLevel>> {
         trace!("{:?}", LogMarker::StoringChunk);
         if self.db.has(data.address())? {
@@ -20,6 +20,8 @@ mod messaging;
 mod msg_count;
 mod msg_handling;
 mod register_storage;
+mod register_subscriptions;
+mod safe_key_storage;
 mod split_barrier;
 
 pub(crate) use back_pressure::BackPressure;
@@ -27,7 +29,8 @@ pub(crate) use bootstrap::{join_network, JoiningAsRelocated};
 pub(crate) use capacity::{CHUNK_COPY_COUNT, MIN_LEVEL_WHEN_FULL};
 pub(crate) use chunk_store::ChunkStore;
 pub(crate) use comm::{Comm, ConnectionEvent, SendStatus};
-pub(crate) use register_storage::RegisterStorage;
+pub(crate) use register_storage::{RegisterStorage, DEFAULT_REGISTER_QUOTAS};
+pub(crate) use safe_key_storage::SafeKeyStorage;
 
 use self::split_barrier::SplitBarrier;
 use crate::dbs::UsedSpace;
@@ -47,8 +50,10 @@ use crate::routing::{
     Elders, Event, NodeElderChange, SectionAuthorityProviderUtils,
 };
 use capacity::Capacity;
+use chunk_records::{ChunkExistenceQueries, PendingChunkReceipts};
 use itertools::Itertools;
 use liveness_tracking::Liveness;
+use register_subscriptions::RegisterSubscriptions;
 use resource_proof::ResourceProof;
 use std::{
     collections::{BTreeMap, BTreeSet},
@@ -81,9 +86,13 @@ pub(crate) struct Core {
     used_space: UsedSpace,
     pub(super) register_storage: RegisterStorage,
     pub(super) chunk_storage: ChunkStore,
+    pub(super) safe_key_storage: SafeKeyStorage,
+    register_subscriptions: RegisterSubscriptions,
     root_storage_dir: PathBuf,
     capacity: Capacity,
     liveness: Liveness,
+    chunk_existence_queries: ChunkExistenceQueries,
+    pending_chunk_receipts: PendingChunkReceipts,
 }
 
 impl Core {
@@ -105,6 +114,7 @@ impl Core {
 
         let register_storage = RegisterStorage::new(&root_storage_dir, used_space.clone())?;
         let chunk_storage = ChunkStore::new(&root_storage_dir, used_space.clone())?;
+        let safe_key_storage = SafeKeyStorage::new(&root_storage_dir, used_space.clone())?;
 
         let capacity = Capacity::new(BTreeMap::new());
         let adult_liveness = Liveness::new();
@@ -126,8 +136,12 @@ impl Core {
             resource_proof: ResourceProof::new(RESOURCE_PROOF_DATA_SIZE, RESOURCE_PROOF_DIFFICULTY),
             register_storage,
             chunk_storage,
+            safe_key_storage,
+            register_subscriptions: RegisterSubscriptions::default(),
             capacity,
             liveness: adult_liveness,
+            chunk_existence_queries: ChunkExistenceQueries::default(),
+            pending_chunk_receipts: PendingChunkReceipts::default(),
             root_storage_dir,
             used_space,
         })
@@ -14,6 +14,7 @@ mod proposals;
 mod relocation;
 mod resource_proof;
 mod service_msgs;
+mod store_receipts;
 mod update_section;
 
 use super::Core;
@@ -36,6 +37,7 @@ use bls::PublicKey as BlsPublicKey;
 use bytes::Bytes;
 use rand::rngs::OsRng;
 use std::{collections::BTreeSet, net::SocketAddr};
+use store_receipts::StoreReceiptShare;
 use xor_name::XorName;
 
 // Message handling
@@ -532,11 +534,38 @@ impl Core {
             // plugging in msg handlers.
             SystemMsg::NodeCmd(node_cmd) => {
                 match node_cmd {
-                    NodeCmd::StoreChunk { chunk, .. } => {
+                    NodeCmd::StoreChunk {
+                        chunk,
+                        correlation_id,
+                        ..
+                    } => {
                         info!("Processing chunk write with MessageId: {:?}", msg_id);
                         // There is no point in verifying a sig from a sender A or B here.
                         let level_report = self.chunk_storage.store(&chunk).await?;
-                        return Ok(self.record_if_any(level_report).await);
+                        let mut commands = self.record_if_any(level_report).await;
+                        if let Some(correlation_id) = correlation_id {
+                            let requesting_elder = msg_authority.get_auth_xorname();
+                            commands.push(self.confirm_chunk_stored(
+                                *chunk.address(),
+                                correlation_id,
+                                requesting_elder,
+                            ));
+                        }
+                        return Ok(commands);
+                    }
+                    NodeCmd::ChunkStored {
+                        address,
+                        correlation_id,
+                    } => {
+                        if self.is_not_elder() {
+                            trace!("Dropping ChunkStored msg from {}: {:?}", sender, msg_id);
+                            return Ok(vec![]);
+                        }
+
+                        let holder = msg_authority.get_auth_xorname();
+                        return self
+                            .record_chunk_stored(holder, address, correlation_id)
+                            .await;
                     }
                     NodeCmd::ReplicateChunk(chunk) => {
                         info!(
@@ -555,6 +584,33 @@ impl Core {
                             Ok(self.record_if_any(level_report).await)
                         };
                     }
+                    NodeCmd::StoreReceiptShare {
+                        address,
+                        content,
+                        holders,
+                        sig_share,
+                        origin,
+                        correlation_id,
+                    } => {
+                        if self.is_not_elder() {
+                            trace!(
+                                "Dropping StoreReceiptShare msg from {}: {:?}",
+                                sender,
+                                msg_id
+                            );
+                            return Ok(vec![]);
+                        }
+
+                        let share = StoreReceiptShare {
+                            address,
+                            content,
+                            holders,
+                            sig_share,
+                        };
+                        return self
+                            .aggregate_store_receipt(share, origin, correlation_id)
+                            .await;
+                    }
                     NodeCmd::RepublishChunk(chunk) => {
                         info!(
                             "Republishing chunk {:?} with MessageId {:?}",
@@ -588,6 +644,23 @@ impl Core {
                         self.handle_get_chunk_at_adult(msg_id, &address, origin, sender_xorname)
                             .await
                     }
+                    // A request from EndUser - via elders - to know which chunks are stored
+                    NodeQuery::ChunkExists {
+                        addresses,
+                        operation_id,
+                        correlation_id,
+                        origin,
+                    } => {
+                        let sender_xorname = msg_authority.get_auth_xorname();
+                        self.handle_chunk_existence_at_adult(
+                            correlation_id,
+                            &addresses,
+                            operation_id,
+                            origin,
+                            sender_xorname,
+                        )
+                        .await
+                    }
                     _ => {
                         self.send_event(Event::MessageReceived {
                             msg_id,
@@ -9,15 +9,22 @@
 use super::Core;
 use crate::dbs::convert_to_error_message as convert_db_error_to_error_message;
 use crate::messaging::{
-    data::{CmdError, DataCmd, DataQuery, QueryResponse, RegisterRead, RegisterWrite, ServiceMsg},
+    data::{
+        store_cost, store_cost_operation_id, CmdError, DataCmd, DataQuery, OperationId,
+        QueryResponse, ReceiptAddress, RegisterRead, RegisterWrite, Result as ResultMessage,
+        ServiceMsg,
+    },
     system::{NodeQueryResponse, SystemMsg},
     AuthorityProof, DstLocation, EndUser, MessageId, MsgKind, NodeAuth, ServiceAuth, WireMsg,
 };
 use crate::routing::{
     core::capacity::CHUNK_COPY_COUNT, error::Result, log_markers::LogMarker, peer::PeerUtils,
-    routing_api::command::Command,
+    routing_api::command::Command, SectionAuthorityProviderUtils,
+};
+use crate::types::{
+    register::{Entry, RegisterOp},
+    ChunkAddress, DataAddress, PublicKey, SafeKey, SafeKeyAddress,
 };
-use crate::types::{ChunkAddress, PublicKey};
 use itertools::Itertools;
 use std::{cmp::Ordering, collections::BTreeSet};
 use xor_name::XorName;
@@ -55,17 +62,49 @@ impl Core {
         register_write: RegisterWrite,
         user: EndUser,
         auth: AuthorityProof<ServiceAuth>,
+        with_receipt: bool,
     ) -> Result<Vec<Command>> {
+        let address = *register_write.address();
         trace!(
             "{:?} preparing to write register {:?}",
             LogMarker::RegisterWrite,
-            register_write.address(),
+            address,
         );
 
+        let edit = match &register_write {
+            RegisterWrite::Edit(op) => Some(op.clone()),
+            _ => None,
+        };
+        let content = if with_receipt {
+            Some(register_write.hash()?)
+        } else {
+            None
+        };
+
         match self.register_storage.write(register_write, auth).await {
             Ok(_) => {
                 info!("Successfully wrote Register from Message: {:?}", msg_id);
-                Ok(vec![])
+                let mut commands = match edit {
+                    Some(op) => self.send_register_edit_to_subscribers(op)?,
+                    None => vec![],
+                };
+                let content = match content {
+                    Some(content) => content,
+                    None => return Ok(commands),
+                };
+
+                // Registers are stored at Elders, so the write is stored now
+                commands.extend(
+                    self.send_store_receipt_share(
+                        ReceiptAddress::Register(address),
+                        content,
+                        self.section().authority_provider().names(),
+                        user,
+                        msg_id,
+                    )
+                    .await?,
+                );
+                Ok(commands)
             }
             Err(error) => {
                 trace!("Problem on writing Register! {:?}", error);
@@ -77,6 +116,36 @@ impl Core {
         }
     }
 
+    /// Pushes an edit applied to a Register to the users subscribed to it.
+    fn send_register_edit_to_subscribers(&self, op: RegisterOp<Entry>) -> Result<Vec<Command>> {
+        let subscribers = self.register_subscriptions.subscribers(&op.address);
+        if subscribers.is_empty() {
+            return Ok(vec![]);
+        }
+
+        trace!(
+            "Sending edit of Register {:?} to {} subscribers",
+            op.address,
+            subscribers.len()
+        );
+        let msg = ServiceMsg::RegisterEdited(op);
+
+        // FIXME: define which signature/authority this message should really carry,
+        // perhaps it needs to carry Node signature on a NodeMsg::QueryResponse msg type.
+        // Giving a random sig temporarily
+        let (msg_kind, payload) = Self::random_client_signature(&msg)?;
+
+        subscribers
+            .into_iter()
+            .map(|user| {
+                let dst = DstLocation::EndUser(user);
+                let wire_msg =
+                    WireMsg::new_msg(MessageId::new(), payload.clone(), msg_kind.clone(), dst)?;
+                Ok(Command::ParseAndSendWireMsg(wire_msg))
+            })
+            .collect()
+    }
+
     /// Handle register reads
     pub(crate) fn handle_register_read(
         &self,
@@ -98,6 +167,26 @@ impl Core {
                     return Ok(vec![]);
                 }
 
+                // The storage only checks the user can read the Register it subscribes to
+                let response = match (query, response) {
+                    (
+                        RegisterRead::Subscribe {
+                            address,
+                            duration_secs,
+                        },
+                        QueryResponse::SubscribeToRegister((Ok(_), operation_id)),
+                    ) => {
+                        let result = self.register_subscriptions.subscribe(
+                            address,
+                            user,
+                            auth.public_key,
+                            duration_secs,
+                        );
+                        QueryResponse::SubscribeToRegister((result, operation_id))
+                    }
+                    (_, response) => response,
+                };
+
                 trace!(
                     "Responding to regsiter read, msg_id {:?} with {:?}",
                     msg_id,
@@ -130,6 +219,121 @@ impl Core {
         }
     }
 
+    /// Handle SafeKey commands
+    pub(crate) async fn handle_safe_key_write(
+        &self,
+        msg_id: MessageId,
+        safe_key: SafeKey,
+        user: EndUser,
+        with_receipt: bool,
+    ) -> Result<Vec<Command>> {
+        let address = *safe_key.address();
+        trace!("Preparing to store SafeKey {:?}", address);
+
+        match self.safe_key_storage.store(&safe_key).await {
+            Ok(()) => {
+                info!("Successfully stored SafeKey from Message: {:?}", msg_id);
+                if !with_receipt {
+                    return Ok(vec![]);
+                }
+
+                // SafeKeys are stored at Elders, so the key is stored now
+                self.send_store_receipt_share(
+                    ReceiptAddress::SafeKey(address),
+                    *address.name(),
+                    self.section().authority_provider().names(),
+                    user,
+                    msg_id,
+                )
+                .await
+            }
+            Err(error) => {
+                trace!("Problem on storing SafeKey! {:?}", error);
+                let error = CmdError::Data(convert_db_error_to_error_message(error));
+                self.send_cmd_error_response(error, user, msg_id)
+            }
+        }
+    }
+
+    /// Handle SafeKey reads
+    pub(crate) fn handle_safe_key_read(
+        &self,
+        msg_id: MessageId,
+        address: SafeKeyAddress,
+        user: EndUser,
+        auth: AuthorityProof<ServiceAuth>,
+    ) -> Result<Vec<Command>> {
+        trace!("Preparing to read SafeKey {:?}", address);
+
+        match self.safe_key_storage.read(&address, auth.public_key) {
+            Ok(response) => {
+                let msg = ServiceMsg::QueryResponse {
+                    response,
+                    correlation_id: msg_id,
+                };
+
+                // FIXME: define which signature/authority this message should really carry,
+                // perhaps it needs to carry Node signature on a NodeMsg::QueryResponse msg type.
+                // Giving a random sig temporarily
+                let (msg_kind, payload) = Self::random_client_signature(&msg)?;
+
+                let dst = DstLocation::EndUser(user);
+                let wire_msg = WireMsg::new_msg(msg_id, payload, msg_kind, dst)?;
+
+                Ok(vec![Command::ParseAndSendWireMsg(wire_msg)])
+            }
+            Err(error) => {
+                trace!("Problem on reading SafeKey! {:?}", error);
+                let error = CmdError::Data(convert_db_error_to_error_message(error));
+                self.send_cmd_error_response(error, user, msg_id)
+            }
+        }
+    }
+
+    /// Handle store cost queries, quoting a price based on how full our Adults are
+    pub(crate) async fn handle_store_cost_query(
+        &self,
+        msg_id: MessageId,
+        bytes: u64,
+        address: DataAddress,
+        user: EndUser,
+    ) -> Result<Vec<Command>> {
+        let avg_usage = self.capacity.avg_usage().await;
+        let cost = store_cost(bytes, avg_usage);
+        trace!(
+            "Quoting {:?} for storing {} bytes at {:?}, section usage level is {}",
+            cost,
+            bytes,
+            address,
+            avg_usage
+        );
+
+        let response = match store_cost_operation_id(bytes, &address) {
+            Ok(operation_id) => QueryResponse::GetStoreCost((Ok(cost), operation_id)),
+            Err(error) => {
+                warn!(
+                    "Could not form operation id of store cost query: {:?}",
+                    error
+                );
+                return Ok(vec![]);
+            }
+        };
+        let msg = ServiceMsg::QueryResponse {
+            response,
+            correlation_id: msg_id,
+        };
+
+        // FIXME: define which signature/authority this message should really carry,
+        // perhaps it needs to carry Node signature on a NodeMsg::QueryResponse msg type.
+        // Giving a random sig temporarily
+        let (msg_kind, payload) = Self::random_client_signature(&msg)?;
+
+        let dst = DstLocation::EndUser(user);
+        let wire_msg = WireMsg::new_msg(msg_id, payload, msg_kind, dst)?;
+
+        Ok(vec![Command::ParseAndSendWireMsg(wire_msg)])
+    }
+
     /// Sign and serialize node message to be sent
     pub(crate) fn prepare_node_msg(
         &self,
@@ -181,6 +385,33 @@ impl Core {
         Ok(commands)
     }
 
+    /// Handle chunk existence query, responding with the `correlation_id` of the query from the user
+    pub(crate) async fn handle_chunk_existence_at_adult(
+        &self,
+        correlation_id: MessageId,
+        addresses: &[ChunkAddress],
+        operation_id: OperationId,
+        user: EndUser,
+        requesting_elder: XorName,
+    ) -> Result<Vec<Command>> {
+        trace!("Handling chunk existence query at adult");
+
+        let msg = SystemMsg::NodeQueryResponse {
+            response: self.chunk_storage.existing(addresses, operation_id),
+            correlation_id,
+            user,
+        };
+
+        // Setup node authority on this response and send this back to our elders
+        let section_pk = *self.section().chain().last_key();
+        let dst = DstLocation::Node {
+            name: requesting_elder,
+            section_pk,
+        };
+
+        Ok(vec![Command::PrepareNodeMsgToSend { msg, dst }])
+    }
+
     /// Handle chunk read
     /// Records response in liveness tracking
     /// Forms a response to send to the requester
@@ -199,9 +430,19 @@ impl Core {
             sending_nodes_pk
         );
 
-        let NodeQueryResponse::GetChunk(response) = response;
-
-        let query_response = QueryResponse::GetChunk(response);
+        let query_response = match response {
+            NodeQueryResponse::GetChunk(response) => QueryResponse::GetChunk(response),
+            NodeQueryResponse::ChunkExists((response, operation_id)) => {
+                return self
+                    .handle_chunk_existence_response_at_elder(
+                        response,
+                        operation_id,
+                        correlation_id,
+                        sending_nodes_pk,
+                    )
+                    .await
+            }
+        };
 
         let pending_removed = match query_response.operation_id() {
             Ok(op_id) => {
@@ -262,6 +503,71 @@ impl Core {
         Ok(commands)
     }
 
+    /// Handle chunk existence response
+    /// Records response in liveness tracking
+    /// Forms a response to send to the requester once all queried adults have answered
+    async fn handle_chunk_existence_response_at_elder(
+        &self,
+        response: ResultMessage<BTreeSet<ChunkAddress>>,
+        operation_id: OperationId,
+        correlation_id: MessageId,
+        sending_nodes_pk: PublicKey,
+    ) -> Result<Vec<Command>> {
+        let mut commands = vec![];
+        let node_id = XorName::from(sending_nodes_pk);
+
+        let pending_removed = self
+            .liveness
+            .request_operation_fulfilled(&node_id, operation_id.clone())
+            .await;
+
+        // Check for unresponsive adults here.
+        for (name, count) in self.liveness.find_unresponsive_nodes().await {
+            warn!(
+                "Node {} has {} pending ops. It might be unresponsive",
+                name, count
+            );
+            commands.push(Command::ProposeOffline(name));
+        }
+
+        if !pending_removed {
+            trace!("Ignoring un-expected response");
+            return Ok(commands);
+        }
+
+        commands.extend(self.record_chunk_existence(
+            node_id,
+            response,
+            operation_id,
+            correlation_id,
+        )?);
+        Ok(commands)
+    }
+
+    /// Forms a response to send the chunks found to the requester
+    pub(crate) fn send_chunk_existence_response(
+        &self,
+        response: ResultMessage<BTreeSet<ChunkAddress>>,
+        operation_id: OperationId,
+        correlation_id: MessageId,
+        origin: EndUser,
+    ) -> Result<Vec<Command>> {
+        let msg = ServiceMsg::QueryResponse {
+            response: QueryResponse::ChunkExists((response, operation_id)),
+            correlation_id,
+        };
+
+        // FIXME: define which signature/authority this message should really carry,
+        // perhaps it needs to carry Node signature on a NodeMsg::QueryResponse msg type.
+        // Giving a random sig temporarily
+        let (msg_kind, payload) = Self::random_client_signature(&msg)?;
+
+        let dst = DstLocation::EndUser(origin);
+        let wire_msg = WireMsg::new_msg(MessageId::new(), payload, msg_kind, dst)?;
+
+        Ok(vec![Command::ParseAndSendWireMsg(wire_msg)])
+    }
+
     /// Handle ServiceMsgs received from EndUser
     pub(crate) async fn handle_service_msg_received(
         &self,
@@ -274,20 +580,48 @@ impl Core {
             // Register
             // Commands to be handled at elder.
             ServiceMsg::Cmd(DataCmd::Register(register_write)) => {
-                self.handle_register_write(msg_id, register_write, user, auth)
+                self.handle_register_write(msg_id, register_write, user, auth, false)
+                    .await
+            }
+            ServiceMsg::CmdWithReceipt(DataCmd::Register(register_write)) => {
+                self.handle_register_write(msg_id, register_write, user, auth, true)
                     .await
             }
             ServiceMsg::Query(DataQuery::Register(read)) => {
                 self.handle_register_read(msg_id, read, user, auth)
             }
+            // SafeKeys are stored at elders as well.
+            ServiceMsg::Cmd(DataCmd::StoreSafeKey(safe_key)) => {
+                self.handle_safe_key_write(msg_id, safe_key, user, false)
+                    .await
+            }
+            ServiceMsg::CmdWithReceipt(DataCmd::StoreSafeKey(safe_key)) => {
+                self.handle_safe_key_write(msg_id, safe_key, user, true)
+                    .await
+            }
+            ServiceMsg::Query(DataQuery::GetSafeKey(address)) => {
+                self.handle_safe_key_read(msg_id, address, user, auth)
+            }
+            ServiceMsg::Query(DataQuery::GetStoreCost { bytes, address }) => {
+                self.handle_store_cost_query(msg_id, bytes, address, user)
+                    .await
+            }
             // These will only be received at elders.
             // These reads/writes are for adult nodes...
             ServiceMsg::Cmd(DataCmd::StoreChunk(chunk)) => {
-                self.send_chunk_to_adults(chunk, msg_id, auth, user).await
+                self.send_chunk_to_adults(chunk, msg_id, auth, user, false)
+                    .await
+            }
+            ServiceMsg::CmdWithReceipt(DataCmd::StoreChunk(chunk)) => {
+                self.send_chunk_to_adults(chunk, msg_id, auth, user, true)
+                    .await
             }
             ServiceMsg::Query(DataQuery::GetChunk(address)) => {
                 self.read_chunk_from_adults(address, msg_id, user).await
             }
+            ServiceMsg::Query(DataQuery::ChunkExists(addresses)) => {
+                self.check_chunks_at_adults(addresses, msg_id, user).await
+            }
             _ => {
                 warn!("!!!! Unexpected ServiceMsg received in routing. Was not sent to node layer: {:?}", msg);
                 Ok(vec![])
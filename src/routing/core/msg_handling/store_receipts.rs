@@ -0,0 +1,146 @@
+// Copyright 2021 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::Core;
+use crate::messaging::{
+    data::{ReceiptAddress, ServiceMsg, StoreReceipt},
+    signature_aggregator::Error as AggregatorError,
+    system::{NodeCmd, SigShare, SystemMsg},
+    DstLocation, EndUser, MessageId, WireMsg,
+};
+use crate::routing::{error::Result, routing_api::command::Command, SectionAuthorityProviderUtils};
+use std::{collections::BTreeSet, iter};
+use xor_name::XorName;
+
+impl Core {
+    /// Signs our share of the receipt for `content` stored at `address` by `holders`,
+    /// and sends it to the Elder aggregating the shares for the data.
+    ///
+    /// This is only to be called once the data is stored: at Elders, or at the Adults
+    /// holding it once they have all confirmed storing it.
+    pub(crate) async fn send_store_receipt_share(
+        &self,
+        address: ReceiptAddress,
+        content: XorName,
+        holders: BTreeSet<XorName>,
+        origin: EndUser,
+        correlation_id: MessageId,
+    ) -> Result<Vec<Command>> {
+        let key_share = self.key_share().await?;
+        let bytes = StoreReceipt::bytes_to_sign(&address, &content, &holders, &correlation_id)?;
+        let sig_share = SigShare::new(
+            key_share.public_key_set.clone(),
+            key_share.index,
+            &key_share.secret_key_share,
+            &bytes,
+        );
+
+        // All Elders pick the same one to aggregate the shares: the closest to the data
+        let aggregator = match self
+            .section()
+            .authority_provider()
+            .names()
+            .into_iter()
+            .min_by(|lhs, rhs| address.name().cmp_distance(lhs, rhs))
+        {
+            Some(name) => name,
+            None => return Ok(vec![]),
+        };
+
+        if aggregator == self.node().name() {
+            return self
+                .aggregate_store_receipt(
+                    StoreReceiptShare {
+                        address,
+                        content,
+                        holders,
+                        sig_share,
+                    },
+                    origin,
+                    correlation_id,
+                )
+                .await;
+        }
+
+        let msg = SystemMsg::NodeCmd(NodeCmd::StoreReceiptShare {
+            address,
+            content,
+            holders,
+            sig_share,
+            origin,
+            correlation_id,
+        });
+        let aggregation = false;
+
+        self.send_node_msg_to_targets(msg, iter::once(aggregator).collect(), aggregation)
+            .await
+    }
+
+    /// Adds an Elder's share to the receipt for the data,
+    /// sending the receipt to the user once enough shares are collected.
+    ///
+    /// Shares are aggregated by the bytes they sign, which include the ID of the user's
+    /// command message, so each receipt only gathers the shares for that command.
+    pub(crate) async fn aggregate_store_receipt(
+        &self,
+        share: StoreReceiptShare,
+        origin: EndUser,
+        correlation_id: MessageId,
+    ) -> Result<Vec<Command>> {
+        let StoreReceiptShare {
+            address,
+            content,
+            holders,
+            sig_share,
+        } = share;
+        let bytes = StoreReceipt::bytes_to_sign(&address, &content, &holders, &correlation_id)?;
+
+        let sig = match self.message_aggregator.add(&bytes, sig_share).await {
+            Ok(sig) => sig,
+            Err(AggregatorError::NotEnoughShares) => return Ok(vec![]),
+            Err(error) => {
+                warn!(
+                    "Failed to aggregate store receipt for {:?}: {:?}",
+                    address, error
+                );
+                return Ok(vec![]);
+            }
+        };
+
+        trace!("Store receipt for {:?} aggregated", address);
+
+        let msg = ServiceMsg::CmdReceipt {
+            receipt: StoreReceipt {
+                address,
+                content,
+                holders,
+                correlation_id,
+                sig,
+            },
+            correlation_id,
+        };
+
+        // FIXME: define which signature/authority this message should really carry,
+        // perhaps it needs to carry Node signature on a NodeMsg::QueryResponse msg type.
+        // Giving a random sig temporarily
+        let (msg_kind, payload) = Self::random_client_signature(&msg)?;
+
+        let dst = DstLocation::EndUser(origin);
+        let wire_msg = WireMsg::new_msg(MessageId::new(), payload, msg_kind, dst)?;
+
+        Ok(vec![Command::ParseAndSendWireMsg(wire_msg)])
+    }
+}
+
+/// An Elder's signature share over a store receipt, along with what it signs.
+pub(crate) struct StoreReceiptShare {
+    pub(crate) address: ReceiptAddress,
+    pub(crate) content: XorName,
+    pub(crate) holders: BTreeSet<XorName>,
+    pub(crate) sig_share: SigShare,
+}
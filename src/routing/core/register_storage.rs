@@ -7,25 +7,30 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use crate::dbs::SLED_FLUSH_TIME_MS;
-use crate::dbs::{convert_to_error_message, Error, EventStore, Result, UsedSpace};
+use crate::dbs::{
+    convert_to_error_message, deserialise, serialise, Error, EventStore, Result, ToDbKey, UsedSpace,
+};
 use crate::types::{
-    register::{Action, Register, User},
-    PublicKey, RegisterAddress as Address,
+    register::{Action, EntryHash, LegacyRegister, LegacyRegisterOp, Register, User},
+    Chunk, PublicKey, RegisterAddress as Address,
 };
 use crate::{
     messaging::{
         data::{
-            DataCmd, OperationId, QueryResponse, RegisterCmd, RegisterDataExchange, RegisterRead,
-            RegisterWrite, ServiceMsg,
+            DataCmd, OperationId, QueryResponse, RegisterCmd, RegisterDataExchange, RegisterLog,
+            RegisterQuotaUsage, RegisterQuotas, RegisterRead, RegisterSnapshot, RegisterWrite,
+            ServiceMsg,
         },
         AuthorityProof, ServiceAuth, WireMsg,
     },
     types::DataAddress,
 };
-use dashmap::DashMap;
-use sled::Db;
+use dashmap::{mapref::one::RefMut, DashMap};
+use serde::{Deserialize, Serialize};
+use sled::{Db, Tree};
 use std::{
     collections::BTreeMap,
+    convert::TryFrom,
     fmt::{self, Display, Formatter},
     path::{Path, PathBuf},
     sync::Arc,
@@ -34,9 +39,56 @@ use tracing::info;
 use xor_name::{Prefix, XorName};
 
 const DATABASE_NAME: &str = "register";
+const SNAPSHOTS_TREE_NAME: &str = "register-snapshots";
+const USAGE_TREE_NAME: &str = "register-usage";
+const OP_LOG_VERSION_KEY: &str = "register-op-log-version";
+
+/// Version of the encoding of the recorded ops, to be bumped along with a migration
+/// whenever that of [`RegisterCmd`] changes.
+const OP_LOG_VERSION: u8 = 1;
+
+/// Quotas enforced on the Registers of each owner, unless configured otherwise.
+pub(crate) const DEFAULT_REGISTER_QUOTAS: RegisterQuotas = RegisterQuotas {
+    registers: 10_000,
+    ops: 1_000_000,
+    bytes: 1024 * 1024 * 1024,
+};
+
+/// Number of ops recorded for a Register after which a snapshot of it is taken,
+/// and the ops it stands for are pruned.
+const SNAPSHOT_INTERVAL: usize = 128;
 
 type RegisterOpStore = EventStore<RegisterCmd>;
 
+/// A [`RegisterCmd`] as encoded before Register entries could be bytes.
+#[derive(Serialize, Deserialize)]
+struct LegacyRegisterCmd {
+    write: LegacyRegisterWrite,
+    auth: ServiceAuth,
+}
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Serialize, Deserialize)]
+enum LegacyRegisterWrite {
+    New(LegacyRegister),
+    Edit(LegacyRegisterOp),
+    Delete(Address),
+}
+
+// The variants of `ServiceMsg` and `DataCmd` up to the Register commands,
+// to encode these as clients signed them.
+#[derive(Serialize)]
+enum LegacyServiceMsg {
+    Cmd(LegacyDataCmd),
+}
+
+#[allow(dead_code, clippy::large_enum_variant)]
+#[derive(Serialize)]
+enum LegacyDataCmd {
+    StoreChunk(Chunk),
+    Register(LegacyRegisterWrite),
+}
+
 /// Operations over the data type Register.
 // TODO: dont expose this
 #[derive(Clone, Debug)]
@@ -45,6 +97,9 @@ pub(crate) struct RegisterStorage {
     used_space: UsedSpace,
     registers: Arc<DashMap<XorName, Option<StateEntry>>>,
     db: Db,
+    quotas: RegisterQuotas,
+    // What the Registers of each owner used of its quotas, as recorded in the db
+    usage: Arc<DashMap<PublicKey, RegisterQuotas>>,
 }
 
 #[derive(Clone, Debug)]
@@ -64,15 +119,23 @@ impl RegisterStorage {
             .flush_every_ms(SLED_FLUSH_TIME_MS)
             .open()
             .map_err(Error::from)?;
+        migrate_op_logs(&db)?;
 
         Ok(Self {
             path: path.to_path_buf(),
             used_space,
             registers: Arc::new(DashMap::new()),
             db,
+            quotas: DEFAULT_REGISTER_QUOTAS,
+            usage: Arc::new(DashMap::new()),
         })
     }
 
+    /// Sets the quotas enforced on the Registers of each owner.
+    pub(crate) fn set_quotas(&mut self, quotas: RegisterQuotas) {
+        self.quotas = quotas;
+    }
+
     /// --- Synching ---
 
     /// Used for replication of data to new Elders.
@@ -83,12 +146,12 @@ impl RegisterStorage {
             let (key, cache) = entry.pair();
             if let Some(entry) = cache {
                 if prefix.matches(entry.state.name()) {
-                    let _ = the_data.insert(*key, entry.store.get_all()?);
+                    let _ = the_data.insert(*key, self.get_log(*key, entry)?);
                 }
             } else {
                 let entry = self.load_state(*key)?;
                 if prefix.matches(entry.state.name()) {
-                    let _ = the_data.insert(*key, entry.store.get_all()?);
+                    let _ = the_data.insert(*key, self.get_log(*key, &entry)?);
                 }
             }
         }
@@ -103,17 +166,90 @@ impl RegisterStorage {
         let RegisterDataExchange(data) = reg_data;
 
         // todo: make outer loop parallel
-        for (_, history) in data {
-            for op in history {
-                let auth = WireMsg::verify_sig(
-                    op.auth.clone(),
-                    ServiceMsg::Cmd(DataCmd::Register(op.write.clone())),
-                )
-                .map_err(|_| Error::InvalidSignature(op.auth.public_key))?;
-                let _ = self.apply(op, auth)?;
+        for (_, RegisterLog { snapshot, ops }) in data {
+            if let Some(snapshot) = snapshot {
+                self.merge_snapshot(snapshot)?;
+            }
+
+            for op in ops {
+                let auth = verify_cmd(&op)?;
+                let size = serialise(&op)?.len() as u64;
+                let owner = self.owner_of(&op.write)?;
+                let write = op.write.clone();
+                self.apply(op, auth)?;
+                if let Some(owner) = owner {
+                    self.record_usage(owner, &write, size)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // Merges a snapshot received from another Elder into our replica of the Register,
+    // creating it if we don't hold it yet.
+    // The edits it is made of are applied like any other, so those which don't verify
+    // against the Register are left out instead of being taken on the word of the Elder.
+    fn merge_snapshot(&self, snapshot: RegisterSnapshot) -> Result<()> {
+        let RegisterSnapshot { new, edits } = snapshot;
+        let _ = verify_cmd(&new)?;
+        let register = match &new.write {
+            RegisterWrite::New(register) => register.clone(),
+            _ => return Err(Error::InvalidStore),
+        };
+
+        let key = to_reg_key(register.address())?;
+        let mut cache = self.registers.entry(key).or_insert(None);
+        if cache.is_none() {
+            if let Ok(entry) = self.load_state(key) {
+                let _ = cache.replace(entry);
             }
         }
 
+        // The writes merged are charged to the owner as those sent to us would have been,
+        // leaving out the edits we already had, which were charged when we got them.
+        if let Some(entry) = cache.as_mut() {
+            let applied = apply_edits(&mut entry.state, edits);
+            let added = self.compact(key, entry, applied)?;
+            let owner = entry.state.owner();
+            drop(cache);
+
+            self.record_edits_usage(owner, &added)
+        } else {
+            let mut entry = StateEntry {
+                state: register,
+                store: self.load_store(key)?,
+            };
+            let owner = entry.state.owner();
+            let size = serialise(&new)?.len() as u64;
+            let write = new.write.clone();
+            // the snapshot we take is started from the command which created the Register
+            entry.store.append(new)?;
+            let applied = apply_edits(&mut entry.state, edits);
+            let added = self.compact(key, &mut entry, applied)?;
+            let editor = entry.state.owner();
+            let _ = cache.replace(entry);
+            drop(cache);
+
+            self.record_usage(owner, &write, size)?;
+            self.record_edits_usage(editor, &added)
+        }
+    }
+
+    // Charges the owner for a write which was already accepted by the section,
+    // e.g. by another Elder, so the usage is recorded but the quotas are not enforced.
+    fn record_usage(&self, owner: PublicKey, write: &RegisterWrite, size: u64) -> Result<()> {
+        let mut usage = self.usage_of(owner)?;
+        *usage = charge(*usage, write, size);
+        self.store_usage(owner, *usage)
+    }
+
+    // Charges the owner for edits merged from a snapshot, which are not carried by
+    // the commands they were sent with, so are charged their own size.
+    fn record_edits_usage(&self, owner: PublicKey, edits: &[RegisterWrite]) -> Result<()> {
+        for edit in edits {
+            self.record_usage(owner, edit, serialise(edit)?.len() as u64)?;
+        }
         Ok(())
     }
 
@@ -132,7 +268,26 @@ impl RegisterStorage {
             write,
             auth: auth.clone().into_inner(),
         };
-        self.apply(op, auth)
+
+        let limits = self.quotas;
+        let size = serialise(&op)?.len() as u64;
+        // Ops are charged to the owner of the Register, whoever sends them
+        let owner = match self.owner_of(&op.write)? {
+            Some(owner) => owner,
+            // without a Register to charge, the op fails or, if a Delete, does nothing
+            None => return self.apply(op, auth),
+        };
+        // The usage stays locked while the op is applied, so concurrent ops to the Registers
+        // of the same owner can't go over the quotas together.
+        let mut usage = self.usage_of(owner)?;
+        let charged = charge(*usage, &op.write, size);
+        if charged.exceeds(&limits) && !matches!(op.write, RegisterWrite::Delete(_)) {
+            return Err(Error::RegisterQuotaExceeded(limits));
+        }
+
+        self.apply(op, auth)?;
+        *usage = charged;
+        self.store_usage(owner, charged)
     }
 
     fn apply(&self, op: RegisterCmd, auth: AuthorityProof<ServiceAuth>) -> Result<()> {
@@ -160,7 +315,7 @@ impl RegisterStorage {
                 let result = match self.registers.get_mut(&key) {
                     None => {
                         trace!("Attempting to delete register if it exists");
-                        let _ = self.db.drop_tree(key)?;
+                        self.drop_register(key)?;
                         Ok(())
                     }
                     Some(mut entry) => {
@@ -177,12 +332,12 @@ impl RegisterStorage {
                                 Err(Error::InvalidOwner(auth.public_key))
                             } else {
                                 info!("Deleting Register");
-                                let _ = self.db.drop_tree(key)?;
+                                self.drop_register(key)?;
                                 Ok(())
                             }
                         } else if self.load_store(key).is_ok() {
                             info!("Deleting Register");
-                            let _ = self.db.drop_tree(key)?;
+                            self.drop_register(key)?;
                             Ok(())
                         } else {
                             Ok(())
@@ -196,39 +351,64 @@ impl RegisterStorage {
 
                 result
             }
-            Edit(reg_op) => {
-                let mut cache = self
-                    .registers
-                    .get_mut(&key)
-                    .ok_or(Error::NoSuchData(DataAddress::Register(address)))?;
-                let entry = if let Some(cached_entry) = cache.as_mut() {
-                    cached_entry
-                } else {
-                    let fresh_entry = self.load_state(key)?;
-                    let _ = cache.replace(fresh_entry);
-                    if let Some(entry) = cache.as_mut() {
-                        entry
-                    } else {
-                        return Err(Error::NoSuchData(DataAddress::Register(address)));
-                    }
-                };
-
+            // Ops carry the signature of their source, which is checked against the policy
+            // when applied, so they can be sent on behalf of their source, e.g. a group of owners.
+            Edit(reg_op) => self.edit(key, address, op, |register| {
+                check_submitter(register, auth.public_key, reg_op.source)?;
                 info!("Editing Register");
+                register.apply_op(reg_op).map_err(Error::NetworkData)
+            }),
+            EditPolicy(policy_op) => self.edit(key, address, op, |register| {
+                check_submitter(register, auth.public_key, policy_op.source)?;
+                info!("Editing Register policy");
+                register
+                    .apply_policy_op(policy_op)
+                    .map_err(Error::NetworkData)
+            }),
+        }
+    }
+
+    // Applies an edit to the cached Register, loading it from disk if needed,
+    // and records the op in the store if the edit succeeded, compacting the store
+    // once it holds enough ops.
+    fn edit(
+        &self,
+        key: XorName,
+        address: Address,
+        op: RegisterCmd,
+        apply: impl FnOnce(&mut Register) -> Result<()>,
+    ) -> Result<()> {
+        let mut cache = self
+            .registers
+            .get_mut(&key)
+            .ok_or(Error::NoSuchData(DataAddress::Register(address)))?;
+        let entry = if let Some(cached_entry) = cache.as_mut() {
+            cached_entry
+        } else {
+            let fresh_entry = self.load_state(key)?;
+            let _ = cache.replace(fresh_entry);
+            if let Some(entry) = cache.as_mut() {
                 entry
-                    .state
-                    .check_permissions(Action::Write, Some(auth.public_key))?;
-                let result = entry.state.apply_op(reg_op).map_err(Error::NetworkData);
+            } else {
+                return Err(Error::NoSuchData(DataAddress::Register(address)));
+            }
+        };
 
-                if result.is_ok() {
-                    entry.store.append(op)?;
-                    trace!("Editing Register success!");
-                } else {
-                    trace!("Editing Register failed!");
-                }
+        let result = apply(&mut entry.state);
 
-                result
+        if result.is_ok() {
+            entry.store.append(op)?;
+            trace!("Editing Register success!");
+            if entry.store.len() >= SNAPSHOT_INTERVAL {
+                if let Err(error) = self.compact(key, entry, vec![]) {
+                    warn!("Failed to compact Register {:?}: {:?}", address, error);
+                }
             }
+        } else {
+            trace!("Editing Register failed!");
         }
+
+        result
     }
 
     /// --- Reading ---
@@ -250,6 +430,28 @@ impl RegisterStorage {
                 self.get_user_permissions(*address, *user, requester_pk, operation_id)
             }
             GetPolicy(address) => self.get_policy(*address, requester_pk, operation_id),
+            GetEntryParents { address, hash } => {
+                self.get_entry_parents(*address, *hash, requester_pk, operation_id)
+            }
+            GetEntryChildren { address, hash } => {
+                self.get_entry_children(*address, *hash, requester_pk, operation_id)
+            }
+            GetHistory { address, start } => {
+                self.get_history(*address, *start, requester_pk, operation_id)
+            }
+            GetAncestors {
+                address,
+                hash,
+                depth,
+            } => self.get_ancestors(*address, *hash, *depth, requester_pk, operation_id),
+            GetAt { address, version } => {
+                self.get_at(*address, *version, requester_pk, operation_id)
+            }
+            GetQuotaUsage(_) => self.get_quota_usage(requester_pk, operation_id),
+            Subscribe {
+                address,
+                duration_secs,
+            } => self.subscribe(*address, *duration_secs, requester_pk, operation_id),
         }
     }
 
@@ -372,6 +574,160 @@ impl RegisterStorage {
         Ok(QueryResponse::GetRegisterPolicy((result, operation_id)))
     }
 
+    fn get_entry_parents(
+        &self,
+        address: Address,
+        hash: EntryHash,
+        requester_pk: PublicKey,
+        operation_id: OperationId,
+    ) -> Result<QueryResponse> {
+        let result = match self
+            .get_register(&address, Action::Read, requester_pk)
+            .and_then(|register| {
+                register
+                    .parents(hash, Some(requester_pk))
+                    .map_err(Error::from)
+            }) {
+            Ok(res) => Ok(res),
+            Err(Error::NoSuchData(addr)) => return Err(Error::NoSuchData(addr)),
+            Err(error) => Err(convert_to_error_message(error)),
+        };
+
+        Ok(QueryResponse::GetRegisterEntryParents((
+            result,
+            operation_id,
+        )))
+    }
+
+    fn get_entry_children(
+        &self,
+        address: Address,
+        hash: EntryHash,
+        requester_pk: PublicKey,
+        operation_id: OperationId,
+    ) -> Result<QueryResponse> {
+        let result = match self
+            .get_register(&address, Action::Read, requester_pk)
+            .and_then(|register| {
+                register
+                    .children(hash, Some(requester_pk))
+                    .map_err(Error::from)
+            }) {
+            Ok(res) => Ok(res),
+            Err(Error::NoSuchData(addr)) => return Err(Error::NoSuchData(addr)),
+            Err(error) => Err(convert_to_error_message(error)),
+        };
+
+        Ok(QueryResponse::GetRegisterEntryChildren((
+            result,
+            operation_id,
+        )))
+    }
+
+    fn get_history(
+        &self,
+        address: Address,
+        start: usize,
+        requester_pk: PublicKey,
+        operation_id: OperationId,
+    ) -> Result<QueryResponse> {
+        let result = match self
+            .get_register(&address, Action::Read, requester_pk)
+            .and_then(|register| {
+                register
+                    .history_page(start, Some(requester_pk))
+                    .map_err(Error::from)
+            }) {
+            Ok(res) => Ok(res),
+            Err(Error::NoSuchData(addr)) => return Err(Error::NoSuchData(addr)),
+            Err(error) => Err(convert_to_error_message(error)),
+        };
+
+        Ok(QueryResponse::GetRegisterHistory((result, operation_id)))
+    }
+
+    /// Only checks the requester can read the Register, the subscription itself
+    /// being recorded by the caller.
+    fn subscribe(
+        &self,
+        address: Address,
+        duration_secs: u64,
+        requester_pk: PublicKey,
+        operation_id: OperationId,
+    ) -> Result<QueryResponse> {
+        let result = match self.get_register(&address, Action::Read, requester_pk) {
+            Ok(_) => Ok(duration_secs),
+            Err(Error::NoSuchData(addr)) => return Err(Error::NoSuchData(addr)),
+            Err(error) => Err(convert_to_error_message(error)),
+        };
+
+        Ok(QueryResponse::SubscribeToRegister((result, operation_id)))
+    }
+
+    fn get_ancestors(
+        &self,
+        address: Address,
+        hash: EntryHash,
+        depth: usize,
+        requester_pk: PublicKey,
+        operation_id: OperationId,
+    ) -> Result<QueryResponse> {
+        let result = match self
+            .get_register(&address, Action::Read, requester_pk)
+            .and_then(|register| {
+                register
+                    .ancestors(hash, depth, Some(requester_pk))
+                    .map_err(Error::from)
+            }) {
+            Ok(res) => Ok(res),
+            Err(Error::NoSuchData(addr)) => return Err(Error::NoSuchData(addr)),
+            Err(error) => Err(convert_to_error_message(error)),
+        };
+
+        Ok(QueryResponse::GetRegisterAncestors((result, operation_id)))
+    }
+
+    fn get_at(
+        &self,
+        address: Address,
+        version: EntryHash,
+        requester_pk: PublicKey,
+        operation_id: OperationId,
+    ) -> Result<QueryResponse> {
+        let result = match self
+            .get_register(&address, Action::Read, requester_pk)
+            .and_then(|register| {
+                register
+                    .history_at(version, Some(requester_pk))
+                    .map_err(Error::from)
+            }) {
+            Ok(res) => Ok(res),
+            Err(Error::NoSuchData(addr)) => return Err(Error::NoSuchData(addr)),
+            Err(error) => Err(convert_to_error_message(error)),
+        };
+
+        Ok(QueryResponse::GetRegisterAt((result, operation_id)))
+    }
+
+    fn get_quota_usage(
+        &self,
+        requester_pk: PublicKey,
+        operation_id: OperationId,
+    ) -> Result<QueryResponse> {
+        let used = match self.usage.get(&requester_pk) {
+            Some(usage) => *usage,
+            None => self.load_usage(requester_pk)?,
+        };
+
+        Ok(QueryResponse::GetRegisterQuotaUsage((
+            Ok(RegisterQuotaUsage {
+                used,
+                limits: self.quotas,
+            }),
+            operation_id,
+        )))
+    }
+
     /// Load a register op store
     fn load_store(&self, id: XorName) -> Result<RegisterOpStore> {
         RegisterOpStore::new(id, self.db.clone()).map_err(Error::from)
@@ -380,16 +736,26 @@ impl RegisterStorage {
     fn load_state(&self, key: XorName) -> Result<StateEntry> {
         // read from disk
         let store = self.load_store(key)?;
-        let mut reg = None;
-        // apply all ops
+        // start from the latest snapshot, if any
+        let mut reg = match self.load_snapshot(key)? {
+            Some(snapshot) => Some(replay(snapshot)?),
+            None => None,
+        };
+        // apply all ops recorded since
         use RegisterWrite::*;
         for op in store.get_all()? {
-            // first op shall be New
+            // first op shall be New, unless the ops were compacted into a snapshot
             if let New(register) = op.write {
-                reg = Some(register);
+                if reg.is_none() {
+                    reg = Some(register);
+                }
             } else if let Some(register) = &mut reg {
-                if let Edit(reg_op) = op.write {
-                    register.apply_op(reg_op).map_err(Error::NetworkData)?;
+                match op.write {
+                    Edit(reg_op) => register.apply_op(reg_op).map_err(Error::NetworkData)?,
+                    EditPolicy(policy_op) => register
+                        .apply_policy_op(policy_op)
+                        .map_err(Error::NetworkData)?,
+                    _ => {}
                 }
             }
         }
@@ -398,6 +764,263 @@ impl RegisterStorage {
             .ok_or(Error::InvalidStore)
             .map(|state| StateEntry { state, store })
     }
+
+    // --- Quotas ---
+
+    // The owner of the Register written to, to charge the write to,
+    // or `None` if we don't hold the Register.
+    fn owner_of(&self, write: &RegisterWrite) -> Result<Option<PublicKey>> {
+        if let RegisterWrite::New(register) = write {
+            return Ok(Some(register.owner()));
+        }
+
+        let key = to_reg_key(write.address())?;
+        if let Some(Some(entry)) = self.registers.get(&key).as_deref() {
+            return Ok(Some(entry.state.owner()));
+        }
+        match self.load_state(key) {
+            Ok(entry) => {
+                let owner = entry.state.owner();
+                let mut cache = self.registers.entry(key).or_insert(None);
+                if cache.is_none() {
+                    let _ = cache.replace(entry);
+                }
+                Ok(Some(owner))
+            }
+            Err(_) => Ok(None),
+        }
+    }
+
+    // The usage of the quotas of the owner, locked until the returned reference is dropped.
+    fn usage_of(&self, owner: PublicKey) -> Result<RefMut<'_, PublicKey, RegisterQuotas>> {
+        if !self.usage.contains_key(&owner) {
+            let stored = self.load_usage(owner)?;
+            let _ = self.usage.entry(owner).or_insert(stored);
+        }
+        Ok(self.usage.entry(owner).or_default())
+    }
+
+    fn usage_tree(&self) -> Result<Tree> {
+        self.db.open_tree(USAGE_TREE_NAME).map_err(Error::from)
+    }
+
+    fn load_usage(&self, owner: PublicKey) -> Result<RegisterQuotas> {
+        match self.usage_tree()?.get(serialise(&owner)?)? {
+            Some(bytes) => deserialise(&bytes),
+            None => Ok(RegisterQuotas::default()),
+        }
+    }
+
+    fn store_usage(&self, owner: PublicKey, usage: RegisterQuotas) -> Result<()> {
+        let _ = self
+            .usage_tree()?
+            .insert(serialise(&owner)?, serialise(&usage)?)?;
+        Ok(())
+    }
+
+    // --- Snapshots ---
+
+    fn snapshots(&self) -> Result<Tree> {
+        self.db.open_tree(SNAPSHOTS_TREE_NAME).map_err(Error::from)
+    }
+
+    fn load_snapshot(&self, key: XorName) -> Result<Option<RegisterSnapshot>> {
+        match self.snapshots()?.get(key.to_db_key()?)? {
+            Some(bytes) => Ok(Some(deserialise(&bytes)?)),
+            None => Ok(None),
+        }
+    }
+
+    // Moves the ops recorded for the Register into its snapshot, followed by the `edits`
+    // applied to it since, e.g. those merged from the snapshot of another Elder.
+    // The snapshot is stored before the ops are pruned, and re-applying ops on top of
+    // a snapshot that already includes them is a no-op, so an interrupted compaction
+    // leaves the Register in a consistent state.
+    // Returns those of the `edits` which were neither in the snapshot nor recorded.
+    fn compact(
+        &self,
+        key: XorName,
+        entry: &mut StateEntry,
+        edits: Vec<RegisterWrite>,
+    ) -> Result<Vec<RegisterWrite>> {
+        trace!("Compacting Register {:?}", entry.state.address());
+        let ops = entry.store.get_all()?;
+        let mut snapshot = match self.load_snapshot(key)? {
+            Some(snapshot) => snapshot,
+            None => {
+                let new = ops
+                    .iter()
+                    .find(|op| matches!(op.write, RegisterWrite::New(_)))
+                    .cloned()
+                    .ok_or(Error::InvalidStore)?;
+                RegisterSnapshot::new(new).map_err(Error::NetworkData)?
+            }
+        };
+        let recorded = ops.into_iter().filter_map(|op| match op.write {
+            RegisterWrite::Edit(_) | RegisterWrite::EditPolicy(_) => Some(op.write),
+            _ => None,
+        });
+        snapshot.extend(recorded).map_err(Error::NetworkData)?;
+        let known = snapshot.edits.len();
+        snapshot.extend(edits).map_err(Error::NetworkData)?;
+        let added = snapshot.edits[known..].to_vec();
+
+        let _ = self
+            .snapshots()?
+            .insert(key.to_db_key()?, serialise(&snapshot)?)?;
+        entry.store.clear()?;
+        Ok(added)
+    }
+
+    // The snapshot and the ops recorded since, to replicate the Register.
+    fn get_log(&self, key: XorName, entry: &StateEntry) -> Result<RegisterLog> {
+        Ok(RegisterLog {
+            snapshot: self.load_snapshot(key)?,
+            ops: entry.store.get_all()?,
+        })
+    }
+
+    fn drop_register(&self, key: XorName) -> Result<()> {
+        let _ = self.db.drop_tree(key)?;
+        let _ = self.snapshots()?.remove(key.to_db_key()?)?;
+        Ok(())
+    }
+}
+
+// An op is only accepted from its source or from a client allowed to write to the Register,
+// which is accountable for it, e.g. the member of a group of owners sending their op.
+fn check_submitter(register: &Register, submitter: PublicKey, source: PublicKey) -> Result<()> {
+    if submitter == source {
+        return Ok(());
+    }
+    register
+        .check_permissions(Action::Write, Some(submitter))
+        .map_err(Error::NetworkData)
+}
+
+// Rebuilds a Register from a snapshot, verifying the command which created it
+// and each of the edits applied since.
+fn replay(snapshot: RegisterSnapshot) -> Result<Register> {
+    let RegisterSnapshot { new, edits } = snapshot;
+    let _ = verify_cmd(&new)?;
+    let mut register = match new.write {
+        RegisterWrite::New(register) => register,
+        _ => return Err(Error::InvalidStore),
+    };
+    let _ = apply_edits(&mut register, edits);
+    Ok(register)
+}
+
+// Applies edits to the Register in order, leaving out those which don't verify against it,
+// and returns those which were applied.
+fn apply_edits(register: &mut Register, edits: Vec<RegisterWrite>) -> Vec<RegisterWrite> {
+    edits
+        .into_iter()
+        .filter(|edit| {
+            let result = match edit.clone() {
+                RegisterWrite::Edit(reg_op) => register.apply_op(reg_op),
+                RegisterWrite::EditPolicy(policy_op) => register.apply_policy_op(policy_op),
+                _ => return false,
+            };
+            if let Err(error) = &result {
+                warn!("Leaving out Register edit from snapshot: {:?}", error);
+            }
+            result.is_ok()
+        })
+        .collect()
+}
+
+// Verifies the signature of the client which sent a command.
+fn verify_cmd(op: &RegisterCmd) -> Result<AuthorityProof<ServiceAuth>> {
+    // the write may have been sent asking for a store receipt or not
+    WireMsg::verify_sig(
+        op.auth.clone(),
+        ServiceMsg::Cmd(DataCmd::Register(op.write.clone())),
+    )
+    .or_else(|_| {
+        WireMsg::verify_sig(
+            op.auth.clone(),
+            ServiceMsg::CmdWithReceipt(DataCmd::Register(op.write.clone())),
+        )
+    })
+    .or_else(|_| verify_legacy_sig(op))
+    .map_err(|_| Error::InvalidSignature(op.auth.public_key))
+}
+
+// Rewrites the ops recorded with an earlier encoding, the first time the db is opened.
+// Before the encoding was versioned, Register entries could only be Urls.
+fn migrate_op_logs(db: &Db) -> Result<()> {
+    if db.get(OP_LOG_VERSION_KEY)?.is_some() {
+        return Ok(());
+    }
+
+    for name in db.tree_names() {
+        if name == db.name()
+            || name == SNAPSHOTS_TREE_NAME.as_bytes()
+            || name == USAGE_TREE_NAME.as_bytes()
+        {
+            continue;
+        }
+        let tree = db.open_tree(&name)?;
+        let mut batch = sled::Batch::default();
+        for entry in tree.iter() {
+            let (key, value) = entry?;
+            let LegacyRegisterCmd { write, auth } = deserialise(&value)?;
+            let write = match write {
+                LegacyRegisterWrite::New(register) => {
+                    RegisterWrite::New(Register::try_from(register).map_err(Error::NetworkData)?)
+                }
+                LegacyRegisterWrite::Edit(op) => RegisterWrite::Edit(op.into()),
+                LegacyRegisterWrite::Delete(address) => RegisterWrite::Delete(address),
+            };
+            batch.insert(key, serialise(&RegisterCmd { write, auth })?);
+        }
+        tree.apply_batch(batch)?;
+    }
+
+    let _ = db.insert(OP_LOG_VERSION_KEY, &[OP_LOG_VERSION])?;
+    let _ = db.flush()?;
+    Ok(())
+}
+
+// Verifies the signature of a command as encoded before Register entries could be bytes,
+// for the migrated ops of other Elders.
+fn verify_legacy_sig(op: &RegisterCmd) -> crate::messaging::Result<AuthorityProof<ServiceAuth>> {
+    use crate::messaging::Error as MsgError;
+    let write = match &op.write {
+        RegisterWrite::New(register) => LegacyRegister::try_from(register)
+            .map(LegacyRegisterWrite::New)
+            .map_err(|_| MsgError::InvalidSignature)?,
+        RegisterWrite::Edit(reg_op) => LegacyRegisterOp::try_from(reg_op)
+            .map(LegacyRegisterWrite::Edit)
+            .map_err(|_| MsgError::InvalidSignature)?,
+        RegisterWrite::Delete(address) => LegacyRegisterWrite::Delete(*address),
+        RegisterWrite::EditPolicy(_) => return Err(MsgError::InvalidSignature),
+    };
+    let msg = LegacyServiceMsg::Cmd(LegacyDataCmd::Register(write));
+    let payload = WireMsg::serialize_msg_payload(&msg)?;
+    AuthorityProof::verify(op.auth.clone(), &payload)
+}
+
+// Adds a command of `size` bytes to the usage of the quotas.
+// Deleting a Register frees its slot, its ops are still accounted for.
+fn charge(usage: RegisterQuotas, write: &RegisterWrite, size: u64) -> RegisterQuotas {
+    match write {
+        RegisterWrite::New(_) => RegisterQuotas {
+            registers: usage.registers.saturating_add(1),
+            ops: usage.ops.saturating_add(1),
+            bytes: usage.bytes.saturating_add(size),
+        },
+        RegisterWrite::Edit(_) | RegisterWrite::EditPolicy(_) => RegisterQuotas {
+            ops: usage.ops.saturating_add(1),
+            bytes: usage.bytes.saturating_add(size),
+            ..usage
+        },
+        RegisterWrite::Delete(_) => RegisterQuotas {
+            registers: usage.registers.saturating_sub(1),
+            ..usage
+        },
+    }
 }
 
 /// This also encodes the Public | Private scope,
@@ -418,21 +1041,33 @@ impl Display for RegisterStorage {
 
 #[cfg(test)]
 mod test {
-    use super::RegisterOpStore;
-    use crate::messaging::data::{RegisterCmd, RegisterWrite};
-    use crate::messaging::ServiceAuth;
+    use super::{
+        to_reg_key, LegacyDataCmd, LegacyRegisterCmd, LegacyRegisterWrite, LegacyServiceMsg,
+        RegisterOpStore, RegisterStorage, DATABASE_NAME, SNAPSHOT_INTERVAL,
+    };
+    use crate::dbs::{serialise, Error as DbError, ToDbKey, UsedSpace};
+    use crate::messaging::data::{
+        DataCmd, QueryResponse, RegisterCmd, RegisterDataExchange, RegisterLog, RegisterQuotaUsage,
+        RegisterQuotas, RegisterRead, RegisterSnapshot, RegisterWrite, ServiceMsg,
+    };
+    use crate::messaging::{AuthorityProof, ServiceAuth, WireMsg};
     use crate::node::Result;
 
     use crate::node::Error;
     use crate::types::{
-        register::{PublicPermissions, PublicPolicy, Register, User},
-        Keypair,
+        register::{
+            Entry, LegacyRegister, LegacyRegisterOp, PublicPermissions, PublicPolicy, Register,
+            User,
+        },
+        BytesAddress, Error as TypesError, Keypair,
     };
+    use crate::url::{ContentType, Url, XorUrlBase};
     use rand::rngs::OsRng;
-    use std::collections::BTreeMap;
+    use std::collections::{BTreeMap, BTreeSet};
+    use std::convert::TryFrom;
     use std::path::Path;
     use tempfile::tempdir;
-    use xor_name::XorName;
+    use xor_name::{Prefix, XorName};
 
     #[tokio::test(flavor = "multi_thread")]
     async fn appends_and_reads_from_store() -> Result<()> {
@@ -460,7 +1095,7 @@ mod test {
             register_name,
             register_tag,
             Some(PublicPolicy {
-                owner: pk,
+                owner: pk.into(),
                 permissions,
             }),
         );
@@ -486,4 +1121,384 @@ mod test {
 
         Ok(())
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn compacts_ops_into_snapshots() -> Result<()> {
+        let tmp_dir = tempdir()?;
+        let storage =
+            RegisterStorage::new(&tmp_dir.path().join("elder1"), UsedSpace::new(u64::MAX))?;
+
+        let keypair = Keypair::new_ed25519(&mut OsRng);
+        let pk = keypair.public_key();
+        let mut register = Register::new_public(pk, XorName::random(), 43_000, None);
+        let key = to_reg_key(register.address())?;
+
+        let write = RegisterWrite::New(register.clone());
+        storage.write(write.clone(), sign(&keypair, write)?).await?;
+
+        // the New op and the edits fill the store once, the remaining edits are kept as ops
+        let edits = SNAPSHOT_INTERVAL + 2;
+        for i in 0..edits {
+            let entry = Entry::from(i.to_be_bytes().to_vec());
+            let (_, mut op) = register.write(entry, BTreeSet::new())?;
+            op.signature = Some(keypair.sign(&bincode::serialize(&op.crdt_op)?));
+            let write = RegisterWrite::Edit(op);
+            storage.write(write.clone(), sign(&keypair, write)?).await?;
+        }
+
+        let state = match storage.registers.get(&key).as_deref() {
+            Some(Some(entry)) => {
+                assert_eq!(entry.store.len(), 3);
+                entry.state.clone()
+            }
+            _ => unreachable!(),
+        };
+        assert_eq!(state.size(None)?, edits as u64);
+
+        let snapshot = match storage.load_snapshot(key)? {
+            Some(snapshot) => snapshot,
+            None => unreachable!(),
+        };
+        assert!(matches!(snapshot.new.write, RegisterWrite::New(_)));
+        assert_eq!(snapshot.edits.len(), SNAPSHOT_INTERVAL - 1);
+
+        // the state is rebuilt from the snapshot and the remaining ops
+        assert_eq!(storage.load_state(key)?.state, state);
+
+        // a new Elder bootstraps from the snapshot and the remaining ops
+        let new_elder =
+            RegisterStorage::new(&tmp_dir.path().join("elder2"), UsedSpace::new(u64::MAX))?;
+        let data = storage.get_data_of(Prefix::default()).await?;
+        assert_eq!(data.0[&key].ops.len(), 3);
+        new_elder.update(data)?;
+
+        match new_elder.registers.get(&key).as_deref() {
+            Some(Some(entry)) => assert_eq!(entry.state.read(None)?, state.read(None)?),
+            _ => unreachable!(),
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn leaves_out_forged_snapshot_ops() -> Result<()> {
+        let tmp_dir = tempdir()?;
+        let storage = RegisterStorage::new(tmp_dir.path(), UsedSpace::new(u64::MAX))?;
+
+        let keypair = Keypair::new_ed25519(&mut OsRng);
+        let mut register =
+            Register::new_public(keypair.public_key(), XorName::random(), 43_000, None);
+        let key = to_reg_key(register.address())?;
+        let write = RegisterWrite::New(register.clone());
+        let new = RegisterCmd {
+            write: write.clone(),
+            auth: sign(&keypair, write)?.into_inner(),
+        };
+
+        let (_, mut op) = register.write(Entry::from(b"signed".to_vec()), BTreeSet::new())?;
+        op.signature = Some(keypair.sign(&bincode::serialize(&op.crdt_op)?));
+        let signed = RegisterWrite::Edit(op);
+
+        // an Elder can't sign ops on behalf of the owner...
+        let elder_keypair = Keypair::new_ed25519(&mut OsRng);
+        let (_, mut op) = register.write(Entry::from(b"forged".to_vec()), BTreeSet::new())?;
+        op.signature = Some(elder_keypair.sign(&bincode::serialize(&op.crdt_op)?));
+        let forged = RegisterWrite::Edit(op);
+
+        let mut snapshot = RegisterSnapshot::new(new.clone())?;
+        snapshot.extend(vec![signed, forged])?;
+        let log = |snapshot| {
+            RegisterDataExchange(
+                vec![(
+                    key,
+                    RegisterLog {
+                        snapshot: Some(snapshot),
+                        ops: vec![],
+                    },
+                )]
+                .into_iter()
+                .collect(),
+            )
+        };
+
+        // ...nor replace the Register it was created with
+        let mut replaced = snapshot.clone();
+        let write = RegisterWrite::New(Register::new_public(
+            elder_keypair.public_key(),
+            XorName::random(),
+            43_000,
+            None,
+        ));
+        replaced.new = RegisterCmd {
+            write,
+            auth: new.auth,
+        };
+        assert!(matches!(
+            storage.update(log(replaced)),
+            Err(DbError::InvalidSignature(_))
+        ));
+
+        storage.update(log(snapshot))?;
+        let entries = storage.load_state(key)?.state.read(None)?;
+        assert_eq!(entries.len(), 1);
+        assert!(entries
+            .iter()
+            .all(|(_, entry)| *entry == Entry::from(b"signed".to_vec())));
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn enforces_quotas_per_owner() -> Result<()> {
+        let tmp_dir = tempdir()?;
+        let mut storage = RegisterStorage::new(tmp_dir.path(), UsedSpace::new(u64::MAX))?;
+        let limits = RegisterQuotas {
+            registers: 1,
+            ops: 2,
+            bytes: u64::MAX,
+        };
+        storage.set_quotas(limits);
+
+        let keypair = Keypair::new_ed25519(&mut OsRng);
+        let pk = keypair.public_key();
+        let mut register = Register::new_private(pk, XorName::random(), 43_000, None);
+        let write = RegisterWrite::New(register.clone());
+        storage.write(write.clone(), sign(&keypair, write)?).await?;
+
+        // a second Register is refused
+        let other = Register::new_public(pk, XorName::random(), 43_000, None);
+        let write = RegisterWrite::New(other);
+        let result = storage.write(write.clone(), sign(&keypair, write)?).await;
+        assert!(matches!(result, Err(DbError::RegisterQuotaExceeded(quotas)) if quotas == limits));
+
+        // as are the ops over the limit
+        let mut edit = || -> Result<RegisterWrite> {
+            let (_, mut op) = register.write(Entry::from(b"entry".to_vec()), BTreeSet::new())?;
+            op.signature = Some(keypair.sign(&bincode::serialize(&op.crdt_op)?));
+            Ok(RegisterWrite::Edit(op))
+        };
+        let write = edit()?;
+        storage.write(write.clone(), sign(&keypair, write)?).await?;
+        let write = edit()?;
+        let result = storage.write(write.clone(), sign(&keypair, write)?).await;
+        assert!(matches!(result, Err(DbError::RegisterQuotaExceeded(_))));
+
+        // other keys have their own quotas
+        let other_keypair = Keypair::new_ed25519(&mut OsRng);
+        let other =
+            Register::new_public(other_keypair.public_key(), XorName::random(), 43_000, None);
+        let write = RegisterWrite::New(other);
+        storage
+            .write(write.clone(), sign(&other_keypair, write)?)
+            .await?;
+
+        let read = RegisterRead::GetQuotaUsage(*register.address());
+        match storage.read(&read, pk)? {
+            QueryResponse::GetRegisterQuotaUsage((Ok(usage), _)) => {
+                assert_eq!(usage.limits, limits);
+                assert_eq!(usage.used.registers, 1);
+                assert_eq!(usage.used.ops, 2);
+            }
+            _ => unreachable!(),
+        }
+
+        // deleting a Register we don't hold frees nothing
+        let unknown = Register::new_private(pk, XorName::random(), 43_000, None);
+        let write = RegisterWrite::Delete(*unknown.address());
+        storage.write(write.clone(), sign(&keypair, write)?).await?;
+        match storage.read(&read, pk)? {
+            QueryResponse::GetRegisterQuotaUsage((Ok(RegisterQuotaUsage { used, .. }), _)) => {
+                assert_eq!(used.registers, 1)
+            }
+            _ => unreachable!(),
+        }
+
+        // deleting the Register frees its slot
+        let write = RegisterWrite::Delete(*register.address());
+        storage.write(write.clone(), sign(&keypair, write)?).await?;
+
+        // the usage is kept across restarts
+        drop(storage);
+        let storage = RegisterStorage::new(tmp_dir.path(), UsedSpace::new(u64::MAX))?;
+        match storage.read(&read, pk)? {
+            QueryResponse::GetRegisterQuotaUsage((Ok(RegisterQuotaUsage { used, .. }), _)) => {
+                assert_eq!(used.registers, 0);
+                assert_eq!(used.ops, 2);
+            }
+            _ => unreachable!(),
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn charges_the_owner_for_merged_snapshots() -> Result<()> {
+        let tmp_dir = tempdir()?;
+        let storage =
+            RegisterStorage::new(&tmp_dir.path().join("elder1"), UsedSpace::new(u64::MAX))?;
+
+        let keypair = Keypair::new_ed25519(&mut OsRng);
+        let pk = keypair.public_key();
+        let mut register = Register::new_public(pk, XorName::random(), 43_000, None);
+        let write = RegisterWrite::New(register.clone());
+        storage.write(write.clone(), sign(&keypair, write)?).await?;
+
+        // most of the ops end up in the snapshot, including the New one
+        let edits = SNAPSHOT_INTERVAL + 2;
+        for i in 0..edits {
+            let entry = Entry::from(i.to_be_bytes().to_vec());
+            let (_, mut op) = register.write(entry, BTreeSet::new())?;
+            op.signature = Some(keypair.sign(&bincode::serialize(&op.crdt_op)?));
+            let write = RegisterWrite::Edit(op);
+            storage.write(write.clone(), sign(&keypair, write)?).await?;
+        }
+
+        let new_elder =
+            RegisterStorage::new(&tmp_dir.path().join("elder2"), UsedSpace::new(u64::MAX))?;
+        new_elder.update(storage.get_data_of(Prefix::default()).await?)?;
+
+        let read = RegisterRead::GetQuotaUsage(*register.address());
+        match new_elder.read(&read, pk)? {
+            QueryResponse::GetRegisterQuotaUsage((Ok(RegisterQuotaUsage { used, .. }), _)) => {
+                assert_eq!(used.registers, 1);
+                assert_eq!(used.ops, edits as u64 + 1);
+                assert!(used.bytes > 0);
+            }
+            _ => unreachable!(),
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn accepts_ops_from_their_source_or_writers() -> Result<()> {
+        let tmp_dir = tempdir()?;
+        let storage = RegisterStorage::new(tmp_dir.path(), UsedSpace::new(u64::MAX))?;
+
+        let keypair = Keypair::new_ed25519(&mut OsRng);
+        let writer_keypair = Keypair::new_ed25519(&mut OsRng);
+        let stranger_keypair = Keypair::new_ed25519(&mut OsRng);
+        let permissions = vec![(
+            User::Key(writer_keypair.public_key()),
+            PublicPermissions::new(true),
+        )]
+        .into_iter()
+        .collect();
+        let policy = PublicPolicy {
+            owner: keypair.public_key().into(),
+            permissions,
+        };
+        let mut register = Register::new_public(
+            keypair.public_key(),
+            XorName::random(),
+            43_000,
+            Some(policy),
+        );
+        let write = RegisterWrite::New(register.clone());
+        storage.write(write.clone(), sign(&keypair, write)?).await?;
+
+        let mut edit = || -> Result<RegisterWrite> {
+            let (_, mut op) = register.write(Entry::from(b"entry".to_vec()), BTreeSet::new())?;
+            op.signature = Some(keypair.sign(&bincode::serialize(&op.crdt_op)?));
+            Ok(RegisterWrite::Edit(op))
+        };
+
+        let write = edit()?;
+        let result = storage
+            .write(write.clone(), sign(&stranger_keypair, write)?)
+            .await;
+        assert!(matches!(
+            result,
+            Err(DbError::NetworkData(TypesError::AccessDenied(_)))
+        ));
+
+        let write = edit()?;
+        storage
+            .write(write.clone(), sign(&writer_keypair, write)?)
+            .await?;
+        let write = edit()?;
+        storage.write(write.clone(), sign(&keypair, write)?).await?;
+
+        // the ops are all charged to the owner
+        let read = RegisterRead::GetQuotaUsage(*register.address());
+        let used_by = |pk| match storage.read(&read, pk) {
+            Ok(QueryResponse::GetRegisterQuotaUsage((Ok(usage), _))) => Some(usage.used.ops),
+            _ => None,
+        };
+        assert_eq!(used_by(keypair.public_key()), Some(3));
+        assert_eq!(used_by(writer_keypair.public_key()), Some(0));
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn migrates_legacy_op_logs() -> Result<()> {
+        let tmp_dir = tempdir()?;
+        let path = tmp_dir.path().join("elder1");
+        let keypair = Keypair::new_ed25519(&mut OsRng);
+        let mut register =
+            Register::new_public(keypair.public_key(), XorName::random(), 43_000, None);
+        let key = to_reg_key(register.address())?;
+
+        // an op log recorded when entries could only be Urls
+        let legacy_new = LegacyRegisterWrite::New(LegacyRegister::try_from(&register)?);
+        let url = Url::encode_bytes(
+            BytesAddress::Public(XorName::random()),
+            ContentType::Raw,
+            XorUrlBase::Base32z,
+        )
+        .and_then(|url| Url::from_url(&url))
+        .map_err(|error| Error::Logic(error.to_string()))?;
+        let (hash, op) = register.write(Entry::Url(url), BTreeSet::new())?;
+        let mut legacy_op = LegacyRegisterOp::try_from(&op)?;
+        legacy_op.signature = Some(keypair.sign(&bincode::serialize(&legacy_op.crdt_op)?));
+        let legacy_edit = LegacyRegisterWrite::Edit(legacy_op);
+        {
+            let db = sled::open(path.join("db").join(DATABASE_NAME))?;
+            let tree = db.open_tree(key.to_db_key()?)?;
+            for (i, write) in vec![legacy_new, legacy_edit].into_iter().enumerate() {
+                let msg = LegacyServiceMsg::Cmd(LegacyDataCmd::Register(write));
+                let auth = ServiceAuth {
+                    public_key: keypair.public_key(),
+                    signature: keypair.sign(&WireMsg::serialize_msg_payload(&msg)?),
+                };
+                let write = match msg {
+                    LegacyServiceMsg::Cmd(LegacyDataCmd::Register(write)) => write,
+                    _ => unreachable!(),
+                };
+                let _ = tree.insert(
+                    i.to_string(),
+                    serialise(&LegacyRegisterCmd { write, auth })?,
+                )?;
+            }
+            let _ = db.flush()?;
+        }
+
+        let storage = RegisterStorage::new(&path, UsedSpace::new(u64::MAX))?;
+        let entry = storage.load_state(key)?;
+        assert_eq!(entry.state.read(None)?, register.read(None)?);
+        assert!(matches!(entry.state.get(hash, None), Ok(Some(_))));
+
+        // the migrated ops are still accepted by other Elders
+        let new_elder =
+            RegisterStorage::new(&tmp_dir.path().join("elder2"), UsedSpace::new(u64::MAX))?;
+        let log = storage.get_log(key, &entry)?;
+        new_elder.update(RegisterDataExchange(vec![(key, log)].into_iter().collect()))?;
+        match new_elder.registers.get(&key).as_deref() {
+            Some(Some(entry)) => assert_eq!(entry.state.read(None)?, register.read(None)?),
+            _ => unreachable!(),
+        }
+
+        Ok(())
+    }
+
+    fn sign(keypair: &Keypair, write: RegisterWrite) -> Result<AuthorityProof<ServiceAuth>> {
+        let msg = ServiceMsg::Cmd(DataCmd::Register(write));
+        let payload = WireMsg::serialize_msg_payload(&msg)?;
+        let auth = ServiceAuth {
+            public_key: keypair.public_key(),
+            signature: keypair.sign(&payload),
+        };
+        Ok(WireMsg::verify_sig(auth, msg)?)
+    }
 }
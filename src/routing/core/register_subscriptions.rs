@@ -0,0 +1,309 @@
+// Copyright 2021 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::messaging::{
+    data::{Error as ErrorMessage, Result as ResultMessage},
+    EndUser,
+};
+use crate::types::{PublicKey, RegisterAddress};
+use dashmap::DashMap;
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+/// Maximum number of Registers a client can be subscribed to at once.
+pub(crate) const MAX_SUBSCRIPTIONS_PER_CLIENT: usize = 32;
+/// Maximum number of subscriptions held by an Elder, of all clients together.
+pub(crate) const MAX_SUBSCRIPTIONS: usize = 100_000;
+/// Maximum duration of a subscription, clients need to renew it to keep receiving the edits.
+pub(crate) const MAX_SUBSCRIPTION_DURATION: Duration = Duration::from_secs(10 * 60);
+
+// When the subscriptions of a client expire, by Register and user subscribed.
+type ClientSubscriptions = HashMap<(RegisterAddress, EndUser), Instant>;
+
+#[derive(Clone, Copy, Debug)]
+struct Subscription {
+    client: PublicKey,
+    expiry: Instant,
+}
+
+/// Users subscribed to the edits of the Registers held by this Elder.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct RegisterSubscriptions {
+    subscribers: Arc<DashMap<RegisterAddress, HashMap<EndUser, Subscription>>>,
+    // The subscriptions of each client, so that they are counted without going through all.
+    by_client: Arc<DashMap<PublicKey, ClientSubscriptions>>,
+    count: Arc<AtomicUsize>,
+}
+
+impl RegisterSubscriptions {
+    /// Subscribes `user` to the edits of the Register at `address`, or renews its subscription.
+    /// A duration of zero cancels the subscription.
+    /// Returns the number of seconds the subscription was granted for.
+    pub(crate) fn subscribe(
+        &self,
+        address: RegisterAddress,
+        user: EndUser,
+        client: PublicKey,
+        duration_secs: u64,
+    ) -> ResultMessage<u64> {
+        let now = Instant::now();
+        self.remove_expired_of(client, now);
+
+        if duration_secs == 0 {
+            self.remove(address, user);
+            return Ok(0);
+        }
+
+        let (renewal, held) = self
+            .by_client
+            .get(&client)
+            .map(|subscriptions| {
+                (
+                    subscriptions.contains_key(&(address, user)),
+                    subscriptions.len(),
+                )
+            })
+            .unwrap_or((false, 0));
+        if !renewal {
+            if held >= MAX_SUBSCRIPTIONS_PER_CLIENT {
+                return Err(ErrorMessage::TooManySubscriptions(
+                    MAX_SUBSCRIPTIONS_PER_CLIENT,
+                ));
+            }
+            // The subscriptions of the clients which did not come back are only dropped
+            // once the Elder is full.
+            if self.count.load(Ordering::SeqCst) >= MAX_SUBSCRIPTIONS {
+                self.remove_expired(now);
+                if self.count.load(Ordering::SeqCst) >= MAX_SUBSCRIPTIONS {
+                    return Err(ErrorMessage::TooManySubscriptions(MAX_SUBSCRIPTIONS));
+                }
+            }
+        }
+
+        let duration = Duration::from_secs(duration_secs).min(MAX_SUBSCRIPTION_DURATION);
+        let subscription = Subscription {
+            client,
+            expiry: now + duration,
+        };
+        let previous = self
+            .subscribers
+            .entry(address)
+            .or_default()
+            .insert(user, subscription);
+        match previous {
+            None => {
+                let _ = self.count.fetch_add(1, Ordering::SeqCst);
+            }
+            Some(previous) if previous.client != client => {
+                if let Some(mut subscriptions) = self.by_client.get_mut(&previous.client) {
+                    let _ = subscriptions.remove(&(address, user));
+                }
+            }
+            Some(_) => {}
+        }
+        let _ = self
+            .by_client
+            .entry(client)
+            .or_default()
+            .insert((address, user), subscription.expiry);
+
+        Ok(duration.as_secs())
+    }
+
+    /// Returns the users currently subscribed to the edits of the Register at `address`.
+    pub(crate) fn subscribers(&self, address: &RegisterAddress) -> Vec<EndUser> {
+        let now = Instant::now();
+        self.subscribers
+            .get(address)
+            .map(|subscribers| {
+                subscribers
+                    .iter()
+                    .filter(|(_, subscription)| subscription.expiry > now)
+                    .map(|(user, _)| *user)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn remove(&self, address: RegisterAddress, user: EndUser) {
+        let removed = self
+            .subscribers
+            .get_mut(&address)
+            .and_then(|mut subscribers| subscribers.remove(&user));
+        if let Some(subscription) = removed {
+            let _ = self.count.fetch_sub(1, Ordering::SeqCst);
+            if let Some(mut subscriptions) = self.by_client.get_mut(&subscription.client) {
+                let _ = subscriptions.remove(&(address, user));
+            }
+        }
+        let _ = self
+            .subscribers
+            .remove_if(&address, |_, subscribers| subscribers.is_empty());
+    }
+
+    fn remove_expired_of(&self, client: PublicKey, now: Instant) {
+        let expired: Vec<_> = self
+            .by_client
+            .get(&client)
+            .map(|subscriptions| {
+                subscriptions
+                    .iter()
+                    .filter(|(_, expiry)| **expiry <= now)
+                    .map(|(key, _)| *key)
+                    .collect()
+            })
+            .unwrap_or_default();
+        for (address, user) in expired {
+            self.remove(address, user);
+        }
+        let _ = self
+            .by_client
+            .remove_if(&client, |_, subscriptions| subscriptions.is_empty());
+    }
+
+    fn remove_expired(&self, now: Instant) {
+        let mut removed = 0;
+        self.subscribers.retain(|_, subscribers| {
+            let held = subscribers.len();
+            subscribers.retain(|_, subscription| subscription.expiry > now);
+            removed += held - subscribers.len();
+            !subscribers.is_empty()
+        });
+        let _ = self.count.fetch_sub(removed, Ordering::SeqCst);
+        self.by_client.retain(|_, subscriptions| {
+            subscriptions.retain(|_, expiry| *expiry > now);
+            !subscriptions.is_empty()
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        RegisterSubscriptions, MAX_SUBSCRIPTIONS, MAX_SUBSCRIPTIONS_PER_CLIENT,
+        MAX_SUBSCRIPTION_DURATION,
+    };
+    use crate::messaging::{data::Error as ErrorMessage, EndUser};
+    use crate::types::{Keypair, RegisterAddress};
+    use eyre::Result;
+    use rand::rngs::OsRng;
+    use std::time::{Duration, Instant};
+    use xor_name::XorName;
+
+    #[test]
+    fn subscriptions_are_limited_per_client() -> Result<()> {
+        let subscriptions = RegisterSubscriptions::default();
+        let client = Keypair::new_ed25519(&mut OsRng).public_key();
+        let user = EndUser(XorName::random());
+
+        for _ in 0..MAX_SUBSCRIPTIONS_PER_CLIENT {
+            let address = RegisterAddress::Public {
+                name: XorName::random(),
+                tag: 15_000,
+            };
+            let _ = subscriptions.subscribe(address, user, client, 60)?;
+            assert_eq!(subscriptions.subscribers(&address), vec![user]);
+        }
+
+        let address = RegisterAddress::Public {
+            name: XorName::random(),
+            tag: 15_000,
+        };
+        assert_eq!(
+            subscriptions.subscribe(address, user, client, 60),
+            Err(ErrorMessage::TooManySubscriptions(
+                MAX_SUBSCRIPTIONS_PER_CLIENT
+            ))
+        );
+
+        // other clients are not affected
+        let other_client = Keypair::new_ed25519(&mut OsRng).public_key();
+        let other_user = EndUser(XorName::random());
+        assert_eq!(
+            subscriptions.subscribe(address, other_user, other_client, 60)?,
+            60
+        );
+
+        // durations are capped, and a duration of zero cancels the subscription
+        assert_eq!(
+            subscriptions.subscribe(address, other_user, other_client, u64::MAX)?,
+            MAX_SUBSCRIPTION_DURATION.as_secs()
+        );
+        assert_eq!(
+            subscriptions.subscribe(address, other_user, other_client, 0)?,
+            0
+        );
+        assert!(subscriptions.subscribers(&address).is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn subscriptions_are_limited_for_all_clients() -> Result<()> {
+        let subscriptions = RegisterSubscriptions::default();
+        let address = RegisterAddress::Public {
+            name: XorName::random(),
+            tag: 15_000,
+        };
+
+        let mut last = None;
+        for _ in 0..MAX_SUBSCRIPTIONS / MAX_SUBSCRIPTIONS_PER_CLIENT {
+            let client = Keypair::new_ed25519(&mut OsRng).public_key();
+            for _ in 0..MAX_SUBSCRIPTIONS_PER_CLIENT {
+                let user = EndUser(XorName::random());
+                let _ = subscriptions.subscribe(address, user, client, 60)?;
+                last = Some((user, client));
+            }
+        }
+
+        // fresh keys don't get around the limit
+        let client = Keypair::new_ed25519(&mut OsRng).public_key();
+        let user = EndUser(XorName::random());
+        assert_eq!(
+            subscriptions.subscribe(address, user, client, 60),
+            Err(ErrorMessage::TooManySubscriptions(MAX_SUBSCRIPTIONS))
+        );
+
+        // a cancelled subscription frees its slot
+        if let Some((last_user, last_client)) = last {
+            let _ = subscriptions.subscribe(address, last_user, last_client, 0)?;
+        }
+        assert_eq!(subscriptions.subscribe(address, user, client, 60)?, 60);
+
+        Ok(())
+    }
+
+    #[test]
+    fn subscriptions_expire() -> Result<()> {
+        let subscriptions = RegisterSubscriptions::default();
+        let client = Keypair::new_ed25519(&mut OsRng).public_key();
+        let user = EndUser(XorName::random());
+        let address = RegisterAddress::Public {
+            name: XorName::random(),
+            tag: 15_000,
+        };
+
+        let _ = subscriptions.subscribe(address, user, client, 10)?;
+        assert_eq!(subscriptions.subscribers(&address), vec![user]);
+
+        if let Some(mut subscribers) = subscriptions.subscribers.get_mut(&address) {
+            for subscription in subscribers.values_mut() {
+                subscription.expiry = Instant::now() - Duration::from_secs(1);
+            }
+        }
+        assert!(subscriptions.subscribers(&address).is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,174 @@
+// Copyright 2021 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::dbs::{convert_to_error_message, Error, KvStore, Result, Subdir, UsedSpace};
+use crate::messaging::data::{safe_key_operation_id, QueryResponse, SafeKeyDataExchange};
+use crate::types::{DataAddress, PublicKey, SafeKey, SafeKeyAddress};
+use std::{
+    fmt::{self, Display, Formatter},
+    path::Path,
+};
+use xor_name::Prefix;
+
+type Db = KvStore<SafeKeyAddress, SafeKey>;
+
+impl Subdir for Db {
+    fn subdir() -> &'static Path {
+        Path::new("safe_keys")
+    }
+}
+
+/// Operations over the data type SafeKey, which is stored at Elders.
+#[derive(Clone, Debug)]
+pub(crate) struct SafeKeyStorage {
+    db: Db,
+}
+
+impl SafeKeyStorage {
+    /// Create new SafeKeyStorage
+    pub(crate) fn new(path: &Path, used_space: UsedSpace) -> Result<Self> {
+        Ok(Self {
+            db: Db::new(path, used_space)?,
+        })
+    }
+
+    // --- Synching ---
+
+    /// Used for replication of data to new Elders.
+    pub(crate) fn get_data_of(&self, prefix: Prefix) -> Result<SafeKeyDataExchange> {
+        let mut the_data = vec![];
+        for address in self.db.keys()? {
+            if prefix.matches(address.name()) {
+                the_data.push(self.db.get(&address)?);
+            }
+        }
+
+        Ok(SafeKeyDataExchange(the_data))
+    }
+
+    /// On receiving data from Elders when promoted.
+    pub(crate) async fn update(&self, safe_key_data: SafeKeyDataExchange) -> Result<()> {
+        debug!("Updating SafeKey store");
+
+        let SafeKeyDataExchange(data) = safe_key_data;
+        for safe_key in data {
+            self.store(&safe_key).await?;
+        }
+
+        Ok(())
+    }
+
+    // --- Writing ---
+
+    /// Stores a SafeKey signed by its secret key. Storing it again does nothing.
+    pub(crate) async fn store(&self, safe_key: &SafeKey) -> Result<()> {
+        if !safe_key.verify() {
+            return Err(Error::InvalidSignature(safe_key.public_key()));
+        }
+        if self.db.has(safe_key.address())? {
+            info!(
+                "{}: SafeKey already exists, not storing: {:?}",
+                self,
+                safe_key.address()
+            );
+            return Ok(());
+        }
+
+        self.db.store(safe_key).await
+    }
+
+    // --- Reading ---
+
+    /// Reads the SafeKey at the address, private ones being readable only by their own key.
+    pub(crate) fn read(
+        &self,
+        address: &SafeKeyAddress,
+        requester_pk: PublicKey,
+    ) -> Result<QueryResponse> {
+        let operation_id = safe_key_operation_id(address).map_err(|_| Error::NoOperationId)?;
+
+        let safe_key = match self.db.get(address) {
+            Ok(safe_key) => safe_key,
+            Err(Error::KeyNotFound(_)) => {
+                return Err(Error::NoSuchData(DataAddress::SafeKey(*address)))
+            }
+            Err(error) => return Err(error),
+        };
+
+        let result = if address.is_private() && safe_key.public_key() != requester_pk {
+            Err(convert_to_error_message(Error::NetworkData(
+                crate::types::Error::AccessDenied(requester_pk),
+            )))
+        } else {
+            Ok(safe_key)
+        };
+
+        Ok(QueryResponse::GetSafeKey((result, operation_id)))
+    }
+}
+
+impl Display for SafeKeyStorage {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(formatter, "SafeKeyStorage")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::SafeKeyStorage;
+    use crate::dbs::{Error, UsedSpace};
+    use crate::messaging::data::{Error as ErrorMessage, QueryResponse};
+    use crate::types::{Keypair, SafeKey};
+    use crate::url::Scope;
+    use eyre::{eyre, Result};
+    use rand::rngs::OsRng;
+    use tempfile::tempdir;
+    use xor_name::Prefix;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn stores_and_replicates_safe_keys() -> Result<()> {
+        let tmp_dir = tempdir()?;
+        let storage =
+            SafeKeyStorage::new(&tmp_dir.path().join("elder1"), UsedSpace::new(u64::MAX))?;
+
+        let keypair = Keypair::new_ed25519(&mut OsRng);
+        let safe_key = SafeKey::new(&keypair, Scope::Private)?;
+        storage.store(&safe_key).await?;
+        // storing it again is a no-op
+        storage.store(&safe_key).await?;
+
+        // private SafeKeys can only be read by their own key
+        match storage.read(safe_key.address(), keypair.public_key())? {
+            QueryResponse::GetSafeKey((Ok(stored), _)) => assert_eq!(stored, safe_key),
+            response => return Err(eyre!("Unexpected response {:?}", response)),
+        }
+        let other = Keypair::new_ed25519(&mut OsRng).public_key();
+        match storage.read(safe_key.address(), other)? {
+            QueryResponse::GetSafeKey((Err(ErrorMessage::AccessDenied(_)), _)) => {}
+            response => return Err(eyre!("Unexpected response {:?}", response)),
+        }
+
+        let missing = SafeKey::new(&Keypair::new_ed25519(&mut OsRng), Scope::Public)?;
+        assert!(matches!(
+            storage.read(missing.address(), other),
+            Err(Error::NoSuchData(_))
+        ));
+
+        // the SafeKeys are handed over to other Elders
+        let data = storage.get_data_of(Prefix::default())?;
+        let replica =
+            SafeKeyStorage::new(&tmp_dir.path().join("elder2"), UsedSpace::new(u64::MAX))?;
+        replica.update(data).await?;
+        assert!(matches!(
+            replica.read(safe_key.address(), keypair.public_key())?,
+            QueryResponse::GetSafeKey((Ok(_), _))
+        ));
+
+        Ok(())
+    }
+}
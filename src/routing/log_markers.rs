@@ -26,6 +26,8 @@ pub(crate) enum LogMarker {
     ChunkQueryResponseReceviedFromAdult,
     ChunkQueryReceviedAtElder,
     ChunkQueryReceviedAtAdult,
+    ChunkExistenceQueryReceivedAtElder,
+    ChunkExistenceQueryReceivedAtAdult,
     RegisterWrite,
     RegisterQueryReceived,
 }
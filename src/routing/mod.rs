@@ -27,7 +27,8 @@ pub use self::error::ProposalError;
 pub(crate) use self::{
     core::ChunkStore,
     core::RegisterStorage,
-    core::{CHUNK_COPY_COUNT, MIN_LEVEL_WHEN_FULL},
+    core::SafeKeyStorage,
+    core::{CHUNK_COPY_COUNT, DEFAULT_REGISTER_QUOTAS, MIN_LEVEL_WHEN_FULL},
     section::section_keys::SectionKeyShare,
 };
 pub use self::{
@@ -6,7 +6,8 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use crate::routing::NetworkConfig;
+use crate::messaging::data::RegisterQuotas;
+use crate::routing::{NetworkConfig, DEFAULT_REGISTER_QUOTAS};
 use ed25519_dalek::Keypair;
 use std::{
     collections::BTreeSet,
@@ -29,6 +30,8 @@ pub struct Config {
     pub genesis_key: Option<String>,
     /// Configuration for the underlying network transport.
     pub network_config: NetworkConfig,
+    /// Quotas enforced on the Registers of each owner.
+    pub register_quotas: RegisterQuotas,
 }
 
 impl Default for Config {
@@ -40,6 +43,7 @@ impl Default for Config {
             bootstrap_nodes: BTreeSet::new(),
             genesis_key: None,
             network_config: NetworkConfig::default(),
+            register_quotas: DEFAULT_REGISTER_QUOTAS,
         }
     }
 }
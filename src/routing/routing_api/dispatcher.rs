@@ -13,7 +13,7 @@ use crate::messaging::{
     DstLocation, EndUser, MsgKind, WireMsg,
 };
 use crate::routing::{
-    core::{ChunkStore, RegisterStorage},
+    core::{ChunkStore, RegisterStorage, SafeKeyStorage},
     core::{Core, SendStatus},
     error::Result,
     messages::WireMsgUtils,
@@ -68,6 +68,10 @@ impl Dispatcher {
         self.core.read().await.chunk_storage.clone()
     }
 
+    pub(super) async fn get_safe_key_storage(&self) -> SafeKeyStorage {
+        self.core.read().await.safe_key_storage.clone()
+    }
+
     pub(super) async fn get_chunk_data_of(&self, prefix: &Prefix) -> ChunkDataExchange {
         self.core.read().await.get_data_of(prefix).await
     }
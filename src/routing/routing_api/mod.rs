@@ -29,7 +29,9 @@ use crate::messaging::{
     DstLocation, SectionAuthorityProvider, WireMsg,
 };
 use crate::routing::{
-    core::{join_network, ChunkStore, Comm, ConnectionEvent, Core, RegisterStorage},
+    core::{
+        join_network, ChunkStore, Comm, ConnectionEvent, Core, RegisterStorage, SafeKeyStorage,
+    },
     ed25519,
     error::{Error, Result},
     messages::WireMsgUtils,
@@ -80,7 +82,7 @@ impl Routing {
         let (event_tx, event_rx) = mpsc::channel(EVENT_CHANNEL_SIZE);
         let (connection_event_tx, mut connection_event_rx) = mpsc::channel(1);
 
-        let core = if config.first {
+        let mut core = if config.first {
             // Genesis node having a fix age of 255.
             let keypair = ed25519::gen_keypair(&Prefix::default().range_inclusive(), 255);
             let node_name = ed25519::name(&keypair.public);
@@ -183,6 +185,7 @@ impl Routing {
 
             core
         };
+        core.register_storage.set_quotas(config.register_quotas);
 
         let dispatcher = Arc::new(Dispatcher::new(core));
         let event_stream = EventStream::new(event_rx);
@@ -207,6 +210,10 @@ impl Routing {
     pub(crate) async fn get_chunk_storage(&self) -> ChunkStore {
         self.dispatcher.get_chunk_storage().await
     }
+
+    pub(crate) async fn get_safe_key_storage(&self) -> SafeKeyStorage {
+        self.dispatcher.get_safe_key_storage().await
+    }
     pub(crate) async fn get_chunk_data_of(&self, prefix: &Prefix) -> ChunkDataExchange {
         self.dispatcher.get_chunk_data_of(prefix).await
     }
@@ -79,6 +79,9 @@ pub enum Error {
     /// Failed signature validation.
     #[error("Invalid signature")]
     InvalidSignature,
+    /// Failed to decrypt data, e.g. for lack of the key it was encrypted with.
+    #[error("Failed to decrypt: {0}")]
+    FailedToDecrypt(String),
     /// While parsing, precision would be lost.
     #[error("Lost precision on the number of coins during parsing")]
     LossOfPrecision,
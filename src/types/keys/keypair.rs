@@ -23,7 +23,7 @@ use rand::{CryptoRng, Rng};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 /// Entity that owns the data or tokens.
 pub enum OwnerType {
     /// Single owner
@@ -32,6 +32,18 @@ pub enum OwnerType {
     Multi(PublicKeySet),
 }
 
+impl From<PublicKey> for OwnerType {
+    fn from(public_key: PublicKey) -> Self {
+        Self::Single(public_key)
+    }
+}
+
+impl From<PublicKeySet> for OwnerType {
+    fn from(public_key_set: PublicKeySet) -> Self {
+        Self::Multi(public_key_set)
+    }
+}
+
 impl OwnerType {
     /// Returns the owner public key
     pub fn public_key(&self) -> PublicKey {
@@ -19,6 +19,7 @@ mod cache;
 mod chunk;
 mod errors;
 mod keys;
+mod safe_key;
 mod token;
 
 pub use address::{BytesAddress, ChunkAddress, DataAddress, RegisterAddress, SafeKeyAddress};
@@ -32,6 +33,7 @@ pub use keys::{
     secret_key::SecretKey,
     signature::{Signature, SignatureShare},
 };
+pub use safe_key::SafeKey;
 pub use token::Token;
 
 use xor_name::XorName;
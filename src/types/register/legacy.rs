@@ -0,0 +1,202 @@
+// Copyright 2021 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under the MIT license <LICENSE-MIT
+// https://opensource.org/licenses/MIT> or the Modified BSD license <LICENSE-BSD
+// https://opensource.org/licenses/BSD-3-Clause>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms. Please review the Licences for the
+// specific language governing permissions and limitations relating to use of the SAFE Network
+// Software.
+
+//! Registers as they were encoded before their entries could be arbitrary bytes and their
+//! policies edited, so that those stored or signed back then can still be read.
+
+use super::super::{utils, Error, OwnerType, PublicKey, RegisterAddress as Address, Result};
+use super::{
+    reg_crdt::{CrdtOperation, RegisterCrdt},
+    Entry, EntryHash, Policy, PrivatePermissions, PrivatePolicy, PublicPermissions, PublicPolicy,
+    Register, RegisterOp, User,
+};
+use crate::url::Url;
+use crdts::merkle_reg::{MerkleReg, Node};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    convert::TryFrom,
+};
+
+/// A Register operation whose entry is a Url, as encoded before entries could be bytes.
+pub(crate) type LegacyRegisterOp = CrdtOperation<Url>;
+
+/// A Register as encoded before entries could be bytes and owners multi-sig.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct LegacyRegister {
+    authority: PublicKey,
+    crdt: LegacyRegisterCrdt,
+    policy: LegacyPolicy,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct LegacyRegisterCrdt {
+    address: Address,
+    data: MerkleReg<Url>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+enum LegacyPolicy {
+    Public(LegacyPublicPolicy),
+    Private(LegacyPrivatePolicy),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct LegacyPublicPolicy {
+    owner: PublicKey,
+    permissions: BTreeMap<User, PublicPermissions>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct LegacyPrivatePolicy {
+    owner: PublicKey,
+    permissions: BTreeMap<PublicKey, PrivatePermissions>,
+}
+
+// Has the same encoding as a `MerkleReg`, whose fields are private.
+#[derive(Serialize, Deserialize)]
+struct MerkleRegFields<T> {
+    roots: BTreeSet<EntryHash>,
+    dag: BTreeMap<EntryHash, Node<T>>,
+    orphans: BTreeMap<EntryHash, Node<T>>,
+}
+
+impl TryFrom<LegacyRegister> for Register {
+    type Error = Error;
+
+    fn try_from(legacy: LegacyRegister) -> Result<Self> {
+        let policy = match legacy.policy {
+            LegacyPolicy::Public(policy) => Policy::Public(PublicPolicy {
+                owner: policy.owner.into(),
+                permissions: policy.permissions,
+            }),
+            LegacyPolicy::Private(policy) => Policy::Private(PrivatePolicy {
+                owner: policy.owner.into(),
+                permissions: policy.permissions,
+            }),
+        };
+        // Url entries are hashed as they were, so the entries keep their hashes.
+        let data = map_entries(&legacy.crdt.data, |url| Some(Entry::Url(url)))?;
+
+        Ok(Self {
+            authority: legacy.authority,
+            crdt: RegisterCrdt::from_data(legacy.crdt.address, data),
+            policy: policy.clone(),
+            policy_version: 0,
+            base_policy: policy,
+            policy_edits: BTreeMap::new(),
+        })
+    }
+}
+
+impl TryFrom<&Register> for LegacyRegister {
+    type Error = Error;
+
+    /// Fails with `InvalidOperation` if the Register has bytes entries, a multi-sig owner
+    /// or an edited policy, none of which could be encoded before.
+    fn try_from(register: &Register) -> Result<Self> {
+        if register.policy_version > 0 {
+            return Err(Error::InvalidOperation);
+        }
+        let policy = match &register.policy {
+            Policy::Public(PublicPolicy {
+                owner: OwnerType::Single(owner),
+                permissions,
+            }) => LegacyPolicy::Public(LegacyPublicPolicy {
+                owner: *owner,
+                permissions: permissions.clone(),
+            }),
+            Policy::Private(PrivatePolicy {
+                owner: OwnerType::Single(owner),
+                permissions,
+            }) => LegacyPolicy::Private(LegacyPrivatePolicy {
+                owner: *owner,
+                permissions: permissions.clone(),
+            }),
+            _ => return Err(Error::InvalidOperation),
+        };
+        let data = map_entries(register.crdt.data(), |entry| match entry {
+            Entry::Url(url) => Some(url),
+            Entry::Bytes(_) => None,
+        })?;
+
+        Ok(Self {
+            authority: register.authority,
+            crdt: LegacyRegisterCrdt {
+                address: *register.address(),
+                data,
+            },
+            policy,
+        })
+    }
+}
+
+impl From<LegacyRegisterOp> for RegisterOp<Entry> {
+    fn from(op: LegacyRegisterOp) -> Self {
+        Self {
+            address: op.address,
+            crdt_op: Node {
+                children: op.crdt_op.children,
+                value: Entry::Url(op.crdt_op.value),
+            },
+            source: op.source,
+            signature: op.signature,
+        }
+    }
+}
+
+impl TryFrom<&RegisterOp<Entry>> for LegacyRegisterOp {
+    type Error = Error;
+
+    /// Fails with `InvalidOperation` if the entry is not a Url.
+    fn try_from(op: &RegisterOp<Entry>) -> Result<Self> {
+        let url = op.crdt_op.value.as_url().ok_or(Error::InvalidOperation)?;
+        Ok(Self {
+            address: op.address,
+            crdt_op: Node {
+                children: op.crdt_op.children.clone(),
+                value: url.clone(),
+            },
+            source: op.source,
+            signature: op.signature.clone(),
+        })
+    }
+}
+
+// Maps the entries of a Merkle register, keeping their hashes and the links between them.
+// Fails with `InvalidOperation` if an entry can't be mapped.
+fn map_entries<A, B>(data: &MerkleReg<A>, map: impl Fn(A) -> Option<B>) -> Result<MerkleReg<B>>
+where
+    A: Serialize + DeserializeOwned,
+    B: Serialize + DeserializeOwned,
+{
+    let fields: MerkleRegFields<A> = utils::deserialise(&utils::serialise(data)?)?;
+    let map_nodes = |nodes: BTreeMap<EntryHash, Node<A>>| {
+        nodes
+            .into_iter()
+            .map(|(hash, node)| {
+                let value = map(node.value).ok_or(Error::InvalidOperation)?;
+                Ok((
+                    hash,
+                    Node {
+                        children: node.children,
+                        value,
+                    },
+                ))
+            })
+            .collect::<Result<BTreeMap<_, _>>>()
+    };
+    let fields = MerkleRegFields {
+        roots: fields.roots,
+        dag: map_nodes(fields.dag)?,
+        orphans: map_nodes(fields.orphans)?,
+    };
+
+    utils::deserialise(&utils::serialise(&fields)?)
+}
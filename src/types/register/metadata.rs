@@ -9,8 +9,12 @@
 
 use crate::url::Url;
 use crdts::merkle_reg::Sha3Hash;
+use serde::{Deserialize, Serialize};
 use tiny_keccak::{Hasher, Sha3};
 
+/// Maximum size of a [`Entry::Bytes`] entry, in bytes.
+pub const MAX_REG_ENTRY_SIZE: usize = 1024;
+
 /// An action on Register data type.
 #[derive(Clone, Debug, Copy, Eq, PartialEq)]
 pub enum Action {
@@ -21,12 +25,57 @@ pub enum Action {
 }
 
 /// An entry in a Register.
-pub type Entry = Url;
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum Entry {
+    /// A SAFE Url, e.g. pointing to some data stored on the network.
+    Url(Url),
+    /// Arbitrary bytes, e.g. a small JSON document, a counter or a hash,
+    /// of at most [`MAX_REG_ENTRY_SIZE`] bytes.
+    Bytes(#[serde(with = "serde_bytes")] Vec<u8>),
+}
+
+impl Entry {
+    /// Returns the Url, if this is a Url entry.
+    pub fn as_url(&self) -> Option<&Url> {
+        match self {
+            Self::Url(url) => Some(url),
+            Self::Bytes(_) => None,
+        }
+    }
+
+    /// Returns the bytes, if this is a bytes entry.
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Self::Url(_) => None,
+            Self::Bytes(bytes) => Some(bytes),
+        }
+    }
+}
+
+impl From<Url> for Entry {
+    fn from(url: Url) -> Self {
+        Self::Url(url)
+    }
+}
 
-impl Eq for Entry {}
+impl From<Vec<u8>> for Entry {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self::Bytes(bytes)
+    }
+}
 
 impl Sha3Hash for Entry {
     fn hash(&self, hasher: &mut Sha3) {
-        hasher.update(self.to_string().as_bytes());
+        match self {
+            // Hashed as before there were other kinds of entries, so that their hashes are unchanged.
+            Self::Url(url) => hasher.update(url.to_string().as_bytes()),
+            // A Url string always starts with its scheme, so the leading zero byte
+            // keeps bytes entries from having the same hash as any Url entry.
+            Self::Bytes(bytes) => {
+                hasher.update(&[0]);
+                hasher.update(bytes);
+            }
+        }
     }
 }
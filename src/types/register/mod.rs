@@ -7,17 +7,20 @@
 // specific language governing permissions and limitations relating to use of the SAFE Network
 // Software.
 
+mod legacy;
 mod metadata;
 mod policy;
 mod reg_crdt;
 
-pub use metadata::{Action, Entry};
+pub(crate) use legacy::{LegacyRegister, LegacyRegisterOp};
+pub use metadata::{Action, Entry, MAX_REG_ENTRY_SIZE};
 pub use policy::{
-    Permissions, Policy, PrivatePermissions, PrivatePolicy, PublicPermissions, PublicPolicy, User,
+    Permissions, Policy, PolicyOp, PrivatePermissions, PrivatePolicy, PublicPermissions,
+    PublicPolicy, User,
 };
-pub use reg_crdt::EntryHash;
+pub use reg_crdt::{EntryHash, MAX_REG_ANCESTORS_DEPTH, MAX_REG_HISTORY_PAGE_LEN};
 
-use super::{Error, PublicKey, Result};
+use super::{Error, OwnerType, PublicKey, Result};
 use crate::{types::RegisterAddress as Address, url::Scope};
 use reg_crdt::{CrdtOperation, RegisterCrdt};
 use serde::{Deserialize, Serialize};
@@ -30,12 +33,21 @@ use xor_name::XorName;
 /// Register mutation operation to apply to Register.
 pub type RegisterOp<T> = CrdtOperation<T>;
 
+/// Maximum number of policy operations a Register replica holds on to while
+/// the operations they are based on have not been applied.
+pub const MAX_REG_PENDING_POLICY_OPS: usize = 32;
+
 /// Object storing the Register
 #[derive(Clone, Eq, PartialEq, PartialOrd, Hash, Serialize, Deserialize, Debug)]
 pub struct Register {
     authority: PublicKey,
     crdt: RegisterCrdt,
     policy: Policy,
+    policy_version: u64,
+    // The policy the Register was created with, which all policy edits build upon.
+    base_policy: Policy,
+    // Every policy edit accepted, by version, with the key which signed it.
+    policy_edits: BTreeMap<u64, BTreeSet<(Policy, PublicKey)>>,
 }
 
 impl Register {
@@ -51,14 +63,18 @@ impl Register {
         policy: Option<PublicPolicy>,
     ) -> Self {
         let policy = policy.unwrap_or(PublicPolicy {
-            owner: authority,
+            owner: authority.into(),
             permissions: BTreeMap::new(),
         });
 
+        let policy: Policy = policy.into();
         Self {
             authority,
             crdt: RegisterCrdt::new(Address::Public { name, tag }),
-            policy: policy.into(),
+            policy: policy.clone(),
+            policy_version: 0,
+            base_policy: policy,
+            policy_edits: BTreeMap::new(),
         }
     }
 
@@ -74,14 +90,18 @@ impl Register {
         policy: Option<PrivatePolicy>,
     ) -> Self {
         let policy = policy.unwrap_or(PrivatePolicy {
-            owner: authority,
+            owner: authority.into(),
             permissions: BTreeMap::new(),
         });
 
+        let policy: Policy = policy.into();
         Self {
             authority,
             crdt: RegisterCrdt::new(Address::Private { name, tag }),
-            policy: policy.into(),
+            policy: policy.clone(),
+            policy_version: 0,
+            base_policy: policy,
+            policy_edits: BTreeMap::new(),
         }
     }
 
@@ -142,6 +162,81 @@ impl Register {
         Ok(self.crdt.read())
     }
 
+    /// Return the entries written on top of the entry with the provided `hash`, i.e. the
+    /// entries which have it as a child. Fails with `NoSuchEntry` if the entry is not present.
+    pub fn parents(
+        &self,
+        hash: EntryHash,
+        requester: Option<PublicKey>,
+    ) -> Result<BTreeSet<(EntryHash, Entry)>> {
+        self.check_permissions(Action::Read, requester)?;
+
+        self.crdt.parents(hash)
+    }
+
+    /// Return the children of the entry with the provided `hash`, i.e. the entries it was
+    /// written on top of. Fails with `NoSuchEntry` if the entry is not present.
+    pub fn children(
+        &self,
+        hash: EntryHash,
+        requester: Option<PublicKey>,
+    ) -> Result<BTreeSet<(EntryHash, Entry)>> {
+        self.check_permissions(Action::Read, requester)?;
+
+        self.crdt.children(hash)
+    }
+
+    /// Return all the entries of the register in topological order: every entry comes after
+    /// the entries it was written on top of, so the first entries written come first and the
+    /// current entries last.
+    pub fn history(&self, requester: Option<PublicKey>) -> Result<Vec<(EntryHash, Entry)>> {
+        self.check_permissions(Action::Read, requester)?;
+
+        Ok(self.crdt.history())
+    }
+
+    /// Return a page of the history of the register, i.e. at most [`MAX_REG_HISTORY_PAGE_LEN`]
+    /// entries starting at the `start` index of the full [`Register::history`].
+    /// A page shorter than [`MAX_REG_HISTORY_PAGE_LEN`] is the last one.
+    pub fn history_page(
+        &self,
+        start: usize,
+        requester: Option<PublicKey>,
+    ) -> Result<Vec<(EntryHash, Entry)>> {
+        self.check_permissions(Action::Read, requester)?;
+
+        Ok(self.crdt.history_page(start))
+    }
+
+    /// Return the entry with the provided `hash` along with all the entries it was written on
+    /// top of, i.e. the register as of that entry, in topological order with the entry last.
+    /// Fails with `NoSuchEntry` if the entry is not present.
+    pub fn history_at(
+        &self,
+        hash: EntryHash,
+        requester: Option<PublicKey>,
+    ) -> Result<Vec<(EntryHash, Entry)>> {
+        self.check_permissions(Action::Read, requester)?;
+
+        self.crdt.history_at(hash)
+    }
+
+    /// Return the entries preceding the entry with the provided `hash`, going back through
+    /// its children up to `depth` levels, the nearest ones first.
+    /// At most [`MAX_REG_ANCESTORS_DEPTH`] levels and [`MAX_REG_HISTORY_PAGE_LEN`] entries are
+    /// returned, the farther ones can be read by asking for the ancestors of the last entries.
+    /// Fails with `NoSuchEntry` if the entry is not present.
+    pub fn ancestors(
+        &self,
+        hash: EntryHash,
+        depth: usize,
+        requester: Option<PublicKey>,
+    ) -> Result<Vec<(EntryHash, Entry)>> {
+        self.check_permissions(Action::Read, requester)?;
+
+        self.crdt.ancestors(hash, depth)
+    }
+
     /// Write an entry to the Register, returning the generated unsigned
     /// CRDT operation so the caller can sign and broadcast it to other replicas,
     /// along with the hash of the entry just written.
@@ -150,9 +245,21 @@ impl Register {
         entry: Entry,
         children: BTreeSet<EntryHash>,
     ) -> Result<(EntryHash, RegisterOp<Entry>)> {
-        self.check_permissions(Action::Write, None)?;
+        self.write_as(entry, children, self.authority)
+    }
 
-        self.crdt.write(entry, children, self.authority)
+    /// Write an entry to the Register on behalf of `source`, the key the returned
+    /// CRDT operation is expected to be signed with. This is the key of the set
+    /// for operations signed by a group of owners.
+    pub fn write_as(
+        &mut self,
+        entry: Entry,
+        children: BTreeSet<EntryHash>,
+        source: PublicKey,
+    ) -> Result<(EntryHash, RegisterOp<Entry>)> {
+        self.check_permissions(Action::Write, Some(source))?;
+
+        self.crdt.write(entry, children, source)
     }
 
     /// Apply a signed data CRDT operation.
@@ -162,6 +269,25 @@ impl Register {
         self.crdt.apply_op(op)
     }
 
+    /// Merge another replica of this Register into this one, e.g. one held by another client.
+    ///
+    /// Entries and policy edits are merged, and the policy settled again from the edits of both,
+    /// so that replicas merging each other in any order end up with the same state.
+    /// The other replica is trusted, since the signatures of the ops it is made of are not kept.
+    pub fn merge(&mut self, other: Register) -> Result<()> {
+        if self.address() != other.address() {
+            return Err(Error::CrdtWrongAddress(*other.address()));
+        }
+
+        for (version, edits) in other.policy_edits {
+            self.policy_edits.entry(version).or_default().extend(edits);
+        }
+        self.settle_policy();
+        self.crdt.merge(other.crdt);
+
+        Ok(())
+    }
+
     /// Return user permissions, if applicable.
     pub fn permissions(&self, user: User, requester: Option<PublicKey>) -> Result<Permissions> {
         self.check_permissions(Action::Read, requester)?;
@@ -176,6 +302,137 @@ impl Register {
         Ok(&self.policy)
     }
 
+    /// Return the version of the policy, the number of times it was edited.
+    pub fn policy_version(&self) -> u64 {
+        self.policy_version
+    }
+
+    /// Replace the policy, e.g. to grant or revoke permissions or to transfer ownership,
+    /// returning the generated unsigned operation so the caller can sign and broadcast
+    /// it to other replicas. Only the current owner can edit the policy.
+    pub fn edit_policy(
+        &mut self,
+        policy: Policy,
+        requester: Option<PublicKey>,
+    ) -> Result<PolicyOp> {
+        let requester = requester.unwrap_or(self.authority);
+        if requester != self.owner() {
+            return Err(Error::AccessDenied(requester));
+        }
+        self.check_policy_scope(&policy)?;
+
+        let version = self.policy_version + 1;
+        let _ = self
+            .policy_edits
+            .entry(version)
+            .or_default()
+            .insert((policy.clone(), requester));
+        self.settle_policy();
+
+        Ok(PolicyOp {
+            address: *self.address(),
+            policy,
+            version,
+            source: requester,
+            signature: None,
+        })
+    }
+
+    /// Apply a signed policy operation.
+    ///
+    /// The operation is checked against the policy it replaces, i.e. its source must be the
+    /// owner as of the version before it, whatever the current owner. An operation whose
+    /// previous version is not yet known is held on to until it is, up to
+    /// [`MAX_REG_PENDING_POLICY_OPS`] of them, provided its source is the owner of a policy
+    /// already known, be it applied or held on to. Otherwise it fails with `OpNotCausallyReady`,
+    /// and is to be applied again once the operations it is based on are.
+    ///
+    /// Concurrent operations of the same version are not merged: the greatest policy wins as a
+    /// whole, so permissions granted by the others are lost and must be granted again.
+    /// This way replicas applying the same operations in any order end up with the same policy.
+    pub fn apply_policy_op(&mut self, op: PolicyOp) -> Result<()> {
+        let sig = op.signature.as_ref().ok_or(Error::CrdtMissingOpSignature)?;
+        op.source.verify(sig, &op.bytes_to_sign()?)?;
+
+        if *self.address() != op.address {
+            return Err(Error::CrdtWrongAddress(op.address));
+        }
+        if op.version == 0 {
+            return Err(Error::InvalidOperation);
+        }
+        self.check_policy_scope(&op.policy)?;
+
+        if op.version <= self.policy_version + 1 {
+            let replaced = self.policy_chain()[op.version as usize - 1];
+            if op.source != replaced.owner() {
+                return Err(Error::AccessDenied(op.source));
+            }
+        } else if self.pending_policy_ops() >= MAX_REG_PENDING_POLICY_OPS
+            || !self.is_known_owner(op.source)
+        {
+            return Err(Error::OpNotCausallyReady);
+        }
+
+        let _ = self
+            .policy_edits
+            .entry(op.version)
+            .or_default()
+            .insert((op.policy, op.source));
+        self.settle_policy();
+
+        Ok(())
+    }
+
+    // The policy as of each version, from the base policy to the current one.
+    // At each version, the greatest of the policies signed by the previous owner is kept.
+    fn policy_chain(&self) -> Vec<&Policy> {
+        let mut chain = vec![&self.base_policy];
+        while let Some(edits) = self.policy_edits.get(&(chain.len() as u64)) {
+            let owner = chain[chain.len() - 1].owner();
+            match edits.iter().rev().find(|(_, source)| *source == owner) {
+                Some((policy, _)) => chain.push(policy),
+                None => break,
+            }
+        }
+        chain
+    }
+
+    fn settle_policy(&mut self) {
+        let chain = self.policy_chain();
+        let policy = chain[chain.len() - 1].clone();
+        self.policy_version = chain.len() as u64 - 1;
+        self.policy = policy;
+    }
+
+    // Number of policy edits whose previous version is not known.
+    fn pending_policy_ops(&self) -> usize {
+        self.policy_edits
+            .range(self.policy_version + 2..)
+            .map(|(_, edits)| edits.len())
+            .sum()
+    }
+
+    // Whether the key owns any of the policies known, so that ops whose previous version is not
+    // yet known are only held on to from keys which are, or are about to become, owners.
+    fn is_known_owner(&self, key: PublicKey) -> bool {
+        self.policy_chain()
+            .into_iter()
+            .chain(
+                self.policy_edits
+                    .range(self.policy_version + 1..)
+                    .flat_map(|(_, edits)| edits.iter().map(|(policy, _)| policy)),
+            )
+            .any(|policy| policy.owner() == key)
+    }
+
+    // A public Register can only have a public policy, and a private one a private policy.
+    fn check_policy_scope(&self, policy: &Policy) -> Result<()> {
+        match (self.scope(), policy) {
+            (Scope::Public, Policy::Public(_)) | (Scope::Private, Policy::Private(_)) => Ok(()),
+            _ => Err(Error::InvalidOperation),
+        }
+    }
+
     /// Helper to check permissions for given `action`
     /// for the given requester's public key.
     ///
@@ -189,7 +446,12 @@ impl Register {
 
     /// Return the owner of the data.
     pub fn owner(&self) -> PublicKey {
-        *self.policy.owner()
+        self.policy.owner()
+    }
+
+    /// Return the owner of the data, either a single key or a set of keys.
+    pub fn owner_type(&self) -> &OwnerType {
+        self.policy.owner_type()
     }
 
     /// Return the PK which the messages are expected to be signed with by this replica.
@@ -202,12 +464,12 @@ impl Register {
 mod tests {
     use super::super::{
         register::{
-            Entry, EntryHash, Permissions, PrivatePermissions, PrivatePolicy, PublicPermissions,
-            PublicPolicy, Register, RegisterOp, User,
+            Entry, EntryHash, Permissions, PolicyOp, PrivatePermissions, PrivatePolicy,
+            PublicPermissions, PublicPolicy, Register, RegisterOp, User, MAX_REG_ANCESTORS_DEPTH,
+            MAX_REG_ENTRY_SIZE, MAX_REG_HISTORY_PAGE_LEN, MAX_REG_PENDING_POLICY_OPS,
         },
-        utils, Error, Keypair, Result,
+        utils, Error, Keypair, PublicKey, Result, Signature, SignatureShare,
     };
-    use crate::url::Url;
     use crate::{
         types::{BytesAddress, RegisterAddress as Address},
         url::Scope,
@@ -217,6 +479,7 @@ mod tests {
     use rand::{rngs::OsRng, seq::SliceRandom, thread_rng};
     use std::{
         collections::{BTreeMap, BTreeSet},
+        iter,
         sync::Arc,
     };
     use xor_name::XorName;
@@ -285,7 +548,7 @@ mod tests {
             register_name,
             register_tag,
             Some(PublicPolicy {
-                owner: authority1,
+                owner: authority1.into(),
                 permissions: perms.clone(),
             }),
         );
@@ -294,13 +557,13 @@ mod tests {
             register_name,
             register_tag,
             Some(PublicPolicy {
-                owner: authority1,
+                owner: authority1.into(),
                 permissions: perms,
             }),
         );
 
         // And let's write an item to replica1 with autority1
-        let item1 = random_url()?;
+        let item1 = random_entry()?;
         let (_, op1) = replica1.write(item1, BTreeSet::new())?;
         let signed_write_op1 = sign_register_op(op1, &authority_keypair1)?;
 
@@ -309,7 +572,7 @@ mod tests {
         assert_eq!(replica2.size(None)?, 0);
 
         // Concurrently write another item with authority2 on replica2
-        let item2 = random_url()?;
+        let item2 = random_entry()?;
         let (_, op2) = replica2.write(item2, BTreeSet::new())?;
         let signed_write_op2 = sign_register_op(op2, &authority_keypair2)?;
 
@@ -330,9 +593,9 @@ mod tests {
     fn register_get_by_hash() -> eyre::Result<()> {
         let (_, register) = &mut create_public_reg_replicas(1)[0];
 
-        let entry1 = random_url()?;
-        let entry2 = random_url()?;
-        let entry3 = random_url()?;
+        let entry1 = random_entry()?;
+        let entry2 = random_entry()?;
+        let entry3 = random_entry()?;
 
         let (entry1_hash, _) = register.write(entry1.clone(), BTreeSet::new())?;
 
@@ -363,6 +626,404 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn register_bytes_entries() -> eyre::Result<()> {
+        let (authority_keypair, mut replica1) = create_public_reg_replicas(1)[0].clone();
+        let mut replica2 = replica1.clone();
+
+        let entry = Entry::Bytes(br#"{"counter":1}"#.to_vec());
+        let (hash, op) = replica1.write(entry.clone(), BTreeSet::new())?;
+        replica2.apply_op(sign_register_op(op, &authority_keypair)?)?;
+        assert_eq!(replica2.get(hash, None)?, Some(&entry));
+        assert_eq!(
+            replica2.get(hash, None)?.and_then(Entry::as_bytes),
+            Some(&br#"{"counter":1}"#[..])
+        );
+
+        // entries are limited in size, both when written and when applied
+        let too_big = Entry::Bytes(vec![0; MAX_REG_ENTRY_SIZE + 1]);
+        assert!(matches!(
+            replica1.write(too_big.clone(), BTreeSet::new()),
+            Err(Error::ExceededSize)
+        ));
+
+        let (_, mut op) = replica1.write(Entry::Bytes(vec![]), BTreeSet::new())?;
+        op.crdt_op.value = too_big;
+        assert!(matches!(
+            replica2.apply_op(sign_register_op(op, &authority_keypair)?),
+            Err(Error::ExceededSize)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn register_traverse_entry_dag() -> eyre::Result<()> {
+        let (_, register) = &mut create_public_reg_replicas(1)[0];
+
+        // entry1 <- entry2, entry1 <- entry3 (a fork), then entry4 merging the fork
+        let entry1 = random_entry()?;
+        let (entry1_hash, _) = register.write(entry1.clone(), BTreeSet::new())?;
+        let entry2 = random_entry()?;
+        let (entry2_hash, _) =
+            register.write(entry2.clone(), vec![entry1_hash].into_iter().collect())?;
+        let entry3 = random_entry()?;
+        let (entry3_hash, _) =
+            register.write(entry3.clone(), vec![entry1_hash].into_iter().collect())?;
+        let entry4 = random_entry()?;
+        let (entry4_hash, _) = register.write(
+            entry4.clone(),
+            vec![entry2_hash, entry3_hash].into_iter().collect(),
+        )?;
+
+        let fork: BTreeSet<_> = vec![(entry2_hash, entry2), (entry3_hash, entry3)]
+            .into_iter()
+            .collect();
+        assert_eq!(register.parents(entry1_hash, None)?, fork);
+        assert_eq!(register.children(entry4_hash, None)?, fork);
+        assert!(register.parents(entry4_hash, None)?.is_empty());
+        assert!(register.children(entry1_hash, None)?.is_empty());
+
+        // the history is topologically ordered, concurrent entries sorted by hash
+        let history = register.history(None)?;
+        assert_eq!(history.len(), 4);
+        assert_eq!(history[0], (entry1_hash, entry1.clone()));
+        assert_eq!(history[1..3].iter().cloned().collect::<BTreeSet<_>>(), fork);
+        assert!(history[1].0 < history[2].0);
+        assert_eq!(history[3], (entry4_hash, entry4));
+
+        assert_eq!(
+            register.ancestors(entry4_hash, 1, None)?,
+            fork.iter().cloned().collect::<Vec<_>>()
+        );
+        let ancestors = register.ancestors(entry4_hash, 5, None)?;
+        assert_eq!(ancestors.len(), 3);
+        assert_eq!(ancestors[2], (entry1_hash, entry1));
+        assert!(register.ancestors(entry4_hash, 0, None)?.is_empty());
+
+        assert!(matches!(
+            register.parents(EntryHash::default(), None),
+            Err(Error::NoSuchEntry)
+        ));
+        assert!(matches!(
+            register.ancestors(EntryHash::default(), 1, None),
+            Err(Error::NoSuchEntry)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn register_history_and_ancestors_are_bounded() -> eyre::Result<()> {
+        let (_, register) = &mut create_public_reg_replicas(1)[0];
+
+        let mut children = BTreeSet::new();
+        for i in 0..MAX_REG_HISTORY_PAGE_LEN + 2 {
+            let entry = Entry::Bytes(i.to_be_bytes().to_vec());
+            let (hash, _) = register.write(entry, children)?;
+            children = vec![hash].into_iter().collect();
+        }
+
+        let history = register.history(None)?;
+        let first_page = register.history_page(0, None)?;
+        let last_page = register.history_page(MAX_REG_HISTORY_PAGE_LEN, None)?;
+        assert_eq!(first_page.len(), MAX_REG_HISTORY_PAGE_LEN);
+        assert_eq!(last_page.len(), 2);
+        assert_eq!([first_page, last_page].concat(), history);
+        assert!(register.history_page(history.len(), None)?.is_empty());
+
+        let last_hash = history[history.len() - 1].0;
+        let ancestors = register.ancestors(last_hash, usize::MAX, None)?;
+        assert_eq!(ancestors.len(), MAX_REG_ANCESTORS_DEPTH);
+
+        Ok(())
+    }
+
+    #[test]
+    fn register_concurrent_policy_edits_converge() -> eyre::Result<()> {
+        let owner_keypair = Keypair::new_ed25519(&mut OsRng);
+        let owner = owner_keypair.public_key();
+        let replicas =
+            gen_pub_reg_replicas(Some(owner_keypair), XorName::random(), 43_000, None, 2);
+        let (owner_keypair, mut replica1) = replicas[0].clone();
+        let (_, mut replica2) = replicas[1].clone();
+
+        // the owner concurrently grants write permissions to a different user on each replica
+        let mut policy1 = replica1.policy(None)?.clone();
+        policy1.set_permissions(
+            User::Key(Keypair::new_ed25519(&mut OsRng).public_key()),
+            Permissions::Public(PublicPermissions::new(true)),
+        )?;
+        let op1 = sign_policy_op(replica1.edit_policy(policy1, None)?, &owner_keypair)?;
+
+        let mut policy2 = replica2.policy(None)?.clone();
+        policy2.set_permissions(
+            User::Anyone,
+            Permissions::Public(PublicPermissions::new(true)),
+        )?;
+        let op2 = sign_policy_op(replica2.edit_policy(policy2, None)?, &owner_keypair)?;
+
+        replica1.apply_policy_op(op2.clone())?;
+        replica2.apply_policy_op(op1.clone())?;
+        // applying an op again changes nothing
+        replica1.apply_policy_op(op2)?;
+
+        assert_eq!(replica1.policy(None)?, replica2.policy(None)?);
+        assert_eq!(replica1.policy_version(), 1);
+        assert_eq!(replica2.policy_version(), 1);
+        assert_eq!(replica1.owner(), owner);
+
+        Ok(())
+    }
+
+    #[test]
+    fn register_policy_ops_applied_out_of_order() -> eyre::Result<()> {
+        let (owner_keypair, mut register) = create_public_reg_replicas(1)[0].clone();
+        let mut replica = register.clone();
+        let new_owner_keypair = Keypair::new_ed25519(&mut OsRng);
+        let new_owner = new_owner_keypair.public_key();
+
+        // the owner grants permissions to anyone, then transfers the ownership,
+        // after which the new owner revokes them
+        let mut policy = register.policy(None)?.clone();
+        policy.set_permissions(
+            User::Anyone,
+            Permissions::Public(PublicPermissions::new(true)),
+        )?;
+        let grant_op = sign_policy_op(register.edit_policy(policy, None)?, &owner_keypair)?;
+        let mut policy = register.policy(None)?.clone();
+        policy.set_owner(new_owner);
+        let transfer_op = sign_policy_op(register.edit_policy(policy, None)?, &owner_keypair)?;
+        let mut policy = register.policy(None)?.clone();
+        policy.set_permissions(
+            User::Anyone,
+            Permissions::Public(PublicPermissions::new(false)),
+        )?;
+        let revoke_op = sign_policy_op(
+            register.edit_policy(policy, Some(new_owner))?,
+            &new_owner_keypair,
+        )?;
+
+        // the transfer is held on to until the grant it is based on is applied,
+        // and so is the revocation, its source being the owner the transfer is to
+        replica.apply_policy_op(transfer_op)?;
+        replica.apply_policy_op(revoke_op)?;
+        assert_eq!(replica.policy_version(), 0);
+        assert_eq!(replica.owner(), owner_keypair.public_key());
+
+        replica.apply_policy_op(grant_op)?;
+        assert_eq!(replica.policy_version(), 3);
+        assert_eq!(replica.policy(None)?, register.policy(None)?);
+        assert_eq!(replica.owner(), new_owner);
+
+        Ok(())
+    }
+
+    #[test]
+    fn register_early_policy_ops_only_held_from_known_owners() -> eyre::Result<()> {
+        let (owner_keypair, mut register) = create_public_reg_replicas(1)[0].clone();
+        let mut replica = register.clone();
+        let new_owner_keypair = Keypair::new_ed25519(&mut OsRng);
+        let new_owner = new_owner_keypair.public_key();
+
+        // anyone can sign ops for far-off versions, which must not fill the pending ops
+        let junk_keypair = Keypair::new_ed25519(&mut OsRng);
+        for version in 2..2 + MAX_REG_PENDING_POLICY_OPS as u64 {
+            let op = PolicyOp {
+                address: *replica.address(),
+                policy: replica.policy(None)?.clone(),
+                version,
+                source: junk_keypair.public_key(),
+                signature: None,
+            };
+            assert!(matches!(
+                replica.apply_policy_op(sign_policy_op(op, &junk_keypair)?),
+                Err(Error::OpNotCausallyReady)
+            ));
+        }
+        assert_eq!(replica.pending_policy_ops(), 0);
+
+        // the owner transfers the ownership, then the new owner grants permissions to anyone
+        let mut policy = register.policy(None)?.clone();
+        policy.set_owner(new_owner);
+        let transfer_op = sign_policy_op(register.edit_policy(policy, None)?, &owner_keypair)?;
+        let mut policy = register.policy(None)?.clone();
+        policy.set_permissions(
+            User::Anyone,
+            Permissions::Public(PublicPermissions::new(true)),
+        )?;
+        let grant_op = sign_policy_op(
+            register.edit_policy(policy, Some(new_owner))?,
+            &new_owner_keypair,
+        )?;
+
+        // the new owner is not known before the transfer, so the grant is to be applied again
+        assert!(matches!(
+            replica.apply_policy_op(grant_op.clone()),
+            Err(Error::OpNotCausallyReady)
+        ));
+        replica.apply_policy_op(transfer_op)?;
+        replica.apply_policy_op(grant_op)?;
+        assert_eq!(replica.policy_version(), 2);
+        assert_eq!(replica.policy(None)?, register.policy(None)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn register_transfer_ownership() -> eyre::Result<()> {
+        let (owner_keypair, mut register) = create_public_reg_replicas(1)[0].clone();
+        let mut replica = register.clone();
+        let new_owner_keypair = Keypair::new_ed25519(&mut OsRng);
+        let new_owner = new_owner_keypair.public_key();
+
+        // only the owner can edit the policy
+        assert!(matches!(
+            register.edit_policy(register.policy(None)?.clone(), Some(new_owner)),
+            Err(Error::AccessDenied(_))
+        ));
+
+        let mut policy = register.policy(None)?.clone();
+        policy.set_owner(new_owner);
+        let op = sign_policy_op(register.edit_policy(policy, None)?, &owner_keypair)?;
+        replica.apply_policy_op(op)?;
+        assert_eq!(replica.owner(), new_owner);
+
+        // the previous owner can no longer edit the policy, while the new one can
+        let mut policy = replica.policy(None)?.clone();
+        policy.set_owner(owner_keypair.public_key());
+        let mut op = replica
+            .clone()
+            .edit_policy(policy.clone(), Some(new_owner))?;
+        op.source = owner_keypair.public_key();
+        let op = sign_policy_op(op, &owner_keypair)?;
+        assert!(matches!(
+            replica.apply_policy_op(op),
+            Err(Error::AccessDenied(_))
+        ));
+
+        let op = sign_policy_op(
+            replica.clone().edit_policy(policy, Some(new_owner))?,
+            &new_owner_keypair,
+        )?;
+        replica.apply_policy_op(op)?;
+        assert_eq!(replica.owner(), owner_keypair.public_key());
+        assert_eq!(replica.policy_version(), 2);
+
+        // a public Register cannot be given a private policy
+        let private_policy = PrivatePolicy {
+            owner: owner_keypair.public_key().into(),
+            permissions: BTreeMap::new(),
+        };
+        assert!(matches!(
+            replica.edit_policy(private_policy.into(), None),
+            Err(Error::InvalidOperation)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn register_history_at_version() -> eyre::Result<()> {
+        let (_, mut register) =
+            gen_pub_reg_replicas(None, XorName::random(), 43_000, None, 1).remove(0);
+
+        // two concurrent entries on top of the first one, then one merging them
+        let (first, _) = register.write(random_entry()?, BTreeSet::new())?;
+        let (left, _) = register.write(random_entry()?, iter::once(first).collect())?;
+        let (right, _) = register.write(random_entry()?, iter::once(first).collect())?;
+        let (merge, _) =
+            register.write(random_entry()?, vec![left, right].into_iter().collect())?;
+
+        let hashes = |entries: Vec<(EntryHash, Entry)>| -> Vec<EntryHash> {
+            entries.into_iter().map(|(hash, _)| hash).collect()
+        };
+        assert_eq!(hashes(register.history_at(first, None)?), vec![first]);
+        assert_eq!(hashes(register.history_at(left, None)?), vec![first, left]);
+        let at_merge = hashes(register.history_at(merge, None)?);
+        assert_eq!(at_merge.len(), 4);
+        assert_eq!((at_merge[0], at_merge[3]), (first, merge));
+        assert_eq!(at_merge, hashes(register.history(None)?));
+
+        assert_eq!(
+            register.history_at(EntryHash::default(), None),
+            Err(Error::NoSuchEntry)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn register_merge_converges() -> eyre::Result<()> {
+        let owner_keypair = Keypair::new_ed25519(&mut OsRng);
+        let replicas =
+            gen_pub_reg_replicas(Some(owner_keypair), XorName::random(), 43_000, None, 2);
+        let (owner_keypair, mut replica1) = replicas[0].clone();
+        let (_, mut replica2) = replicas[1].clone();
+
+        // each replica gets a different entry, and one of them a new policy
+        let (hash1, _) = replica1.write(random_entry()?, BTreeSet::new())?;
+        let (hash2, _) = replica2.write(random_entry()?, BTreeSet::new())?;
+        let mut policy = replica2.policy(None)?.clone();
+        policy.set_permissions(
+            User::Anyone,
+            Permissions::Public(PublicPermissions::new(true)),
+        )?;
+        let _ = sign_policy_op(replica2.edit_policy(policy, None)?, &owner_keypair)?;
+
+        let mut merged1 = replica1.clone();
+        merged1.merge(replica2.clone())?;
+        let mut merged2 = replica2.clone();
+        merged2.merge(replica1)?;
+
+        assert_eq!(merged1.read(None)?, merged2.read(None)?);
+        assert_eq!(merged1.read(None)?.len(), 2);
+        assert!(merged1.get(hash1, None).is_ok() && merged1.get(hash2, None).is_ok());
+        assert_eq!(merged1.policy(None)?, merged2.policy(None)?);
+        assert_eq!(merged1.policy_version(), 1);
+
+        // merging a replica of another Register is rejected
+        let other =
+            Register::new_public(owner_keypair.public_key(), XorName::random(), 43_000, None);
+        assert!(merged1.merge(other).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn register_multisig_owner() -> eyre::Result<()> {
+        let secret_key_set = bls::SecretKeySet::random(1, &mut OsRng);
+        let key_set = secret_key_set.public_keys();
+        let group_key = PublicKey::Bls(key_set.public_key());
+        let policy = PrivatePolicy {
+            owner: key_set.clone().into(),
+            permissions: BTreeMap::new(),
+        };
+        let mut register =
+            Register::new_private(group_key, XorName::random(), 43_000, Some(policy));
+        let mut replica = register.clone();
+        assert_eq!(register.owner(), group_key);
+        assert_eq!(register.owner_type().public_key_set()?, key_set);
+
+        let (_, mut op) = register.write_as(random_entry()?, BTreeSet::new(), group_key)?;
+        let bytes = utils::serialise(&op.crdt_op)?;
+
+        // a single share of the keys is not enough
+        let share = secret_key_set.secret_key_share(0).sign(&bytes);
+        op.signature = Some(Signature::BlsShare(SignatureShare { index: 0, share }));
+        assert!(replica.apply_op(op.clone()).is_err());
+
+        let shares: BTreeMap<_, _> = (0..2)
+            .map(|index| (index, secret_key_set.secret_key_share(index).sign(&bytes)))
+            .collect();
+        let signature = key_set.combine_signatures(shares.iter().map(|(i, share)| (*i, share)))?;
+        op.signature = Some(Signature::Bls(signature));
+        replica.apply_op(op)?;
+        assert_eq!(replica.size(None)?, 1);
+
+        Ok(())
+    }
+
     #[test]
     fn register_query_public_policy() -> eyre::Result<()> {
         let register_name = XorName::random();
@@ -378,7 +1039,7 @@ mod tests {
             register_tag,
             Some(authority_keypair1),
             Some(PublicPolicy {
-                owner: owner1,
+                owner: owner1.into(),
                 permissions: perms1,
             }),
         );
@@ -393,7 +1054,7 @@ mod tests {
             register_tag,
             Some(authority_keypair2),
             Some(PublicPolicy {
-                owner: authority2,
+                owner: authority2.into(),
                 permissions: perms2,
             }),
         );
@@ -448,7 +1109,7 @@ mod tests {
             register_tag,
             Some(authority_keypair1),
             Some(PrivatePolicy {
-                owner: authority1,
+                owner: authority1.into(),
                 permissions: perms1,
             }),
         );
@@ -458,7 +1119,7 @@ mod tests {
             register_tag,
             Some(authority_keypair2),
             Some(PrivatePolicy {
-                owner: authority2,
+                owner: authority2.into(),
                 permissions: perms2,
             }),
         );
@@ -511,7 +1172,7 @@ mod tests {
             register_tag,
             Some(authority_keypair1.clone()),
             Some(PublicPolicy {
-                owner: owner1,
+                owner: owner1.into(),
                 permissions: perms1,
             }),
         );
@@ -526,14 +1187,14 @@ mod tests {
             register_tag,
             Some(authority_keypair2.clone()),
             Some(PublicPolicy {
-                owner: authority2,
+                owner: authority2.into(),
                 permissions: perms2,
             }),
         );
 
         // let's write to both replicas with one first item
-        let item1 = random_url()?;
-        let item2 = random_url()?;
+        let item1 = random_entry()?;
+        let item2 = random_entry()?;
         let (_, op1) = replica1.write(item1, BTreeSet::new())?;
         let write_op1 = sign_register_op(op1, &authority_keypair1)?;
         check_op_not_allowed_failure(replica2.apply_op(write_op1))?;
@@ -570,7 +1231,7 @@ mod tests {
             register_tag,
             Some(authority_keypair1.clone()),
             Some(PrivatePolicy {
-                owner: authority1,
+                owner: authority1.into(),
                 permissions: perms1,
             }),
         );
@@ -580,14 +1241,14 @@ mod tests {
             register_tag,
             Some(authority_keypair2.clone()),
             Some(PrivatePolicy {
-                owner: authority2,
+                owner: authority2.into(),
                 permissions: perms2,
             }),
         );
 
         // let's try to write to both registers
-        let item1 = random_url()?;
-        let item2 = random_url()?;
+        let item1 = random_entry()?;
+        let item2 = random_entry()?;
 
         let (entry1_hash, op1) = replica1.write(item1.clone(), BTreeSet::new())?;
         let write_op1 = sign_register_op(op1, &authority_keypair1)?;
@@ -643,6 +1304,13 @@ mod tests {
         Ok(op)
     }
 
+    fn sign_policy_op(mut op: PolicyOp, keypair: &Keypair) -> Result<PolicyOp> {
+        let bytes = op.bytes_to_sign()?;
+        let signature = keypair.sign(&bytes);
+        op.signature = Some(signature);
+        Ok(op)
+    }
+
     fn gen_pub_reg_replicas(
         authority_keypair: Option<Keypair>,
         name: XorName,
@@ -755,7 +1423,7 @@ mod tests {
         let owner_keypair = Arc::new(Keypair::new_ed25519(&mut OsRng));
         let owner = owner_keypair.public_key();
         let policy = PublicPolicy {
-            owner,
+            owner: owner.into(),
             permissions: BTreeMap::default(),
         };
 
@@ -799,7 +1467,7 @@ mod tests {
             let register_tag = 45_000u64;
             let owner_keypair = Keypair::new_ed25519(&mut OsRng);
             let policy = PublicPolicy {
-                owner: owner_keypair.public_key(),
+                owner: owner_keypair.public_key().into(),
                 permissions: BTreeMap::default(),
             };
 
@@ -813,7 +1481,7 @@ mod tests {
             let (_, mut replica2) = replicas.remove(0);
 
             // Write an item on replicas
-            let (_, op) = replica1.write(random_url()?, BTreeSet::new())?;
+            let (_, op) = replica1.write(random_entry()?, BTreeSet::new())?;
             let write_op = sign_register_op(op, &owner_keypair)?;
             replica2.apply_op(write_op)?;
 
@@ -829,7 +1497,7 @@ mod tests {
             let register_tag = 43_001u64;
             let owner_keypair = Keypair::new_ed25519(&mut OsRng);
             let policy = PublicPolicy {
-                owner: owner_keypair.public_key(),
+                owner: owner_keypair.public_key().into(),
                 permissions: BTreeMap::default(),
             };
 
@@ -849,7 +1517,7 @@ mod tests {
             let mut children = BTreeSet::new();
             for _data in dataset {
                 // Write an item on replica1
-                let (hash, op) = replica1.write(random_url()?, children.clone())?;
+                let (hash, op) = replica1.write(random_entry()?, children.clone())?;
                 let write_op = sign_register_op(op, &owner_keypair)?;
                 // now apply that op to replica 2
                 replica2.apply_op(write_op)?;
@@ -868,7 +1536,7 @@ mod tests {
             let register_tag = 43_002u64;
             let owner_keypair = Keypair::new_ed25519(&mut OsRng);
             let policy = PublicPolicy {
-                owner: owner_keypair.public_key(),
+                owner: owner_keypair.public_key().into(),
                 permissions: BTreeMap::default(),
             };
 
@@ -893,7 +1561,7 @@ mod tests {
                 let children: BTreeSet<_> = list_of_hashes.choose_multiple(&mut OsRng, num_of_children).cloned().collect();
 
                 // Write an item on replica1 using the randomly generated set of children
-                let (hash, op) = replica1.write(random_url()?, children)?;
+                let (hash, op) = replica1.write(random_entry()?, children)?;
                 let write_op = sign_register_op(op, &owner_keypair)?;
 
                 // now apply that op to replica 2
@@ -916,7 +1584,7 @@ mod tests {
             let mut children = BTreeSet::new();
             for _data in dataset {
                 // first generate an op from one replica...
-                let (hash, op)= replicas[0].write(random_url()?, children)?;
+                let (hash, op)= replicas[0].write(random_entry()?, children)?;
                 let signed_op = sign_register_op(op, &owner_keypair)?;
 
                 // then apply this to all replicas
@@ -943,7 +1611,7 @@ mod tests {
 
             let mut children = BTreeSet::new();
             for _data in dataset {
-                let (hash, op) = replicas[0].write(random_url()?, children)?;
+                let (hash, op) = replicas[0].write(random_entry()?, children)?;
                 let signed_op = sign_register_op(op, &owner_keypair)?;
                 ops.push(signed_op);
                 children = vec![hash].into_iter().collect();
@@ -976,7 +1644,7 @@ mod tests {
             for _data in dataset {
                 if let Some(replica) = replicas.choose_mut(&mut OsRng)
                 {
-                    let (hash, op) = replica.write(random_url()?, children)?;
+                    let (hash, op) = replica.write(random_entry()?, children)?;
                     let signed_op = sign_register_op(op, &owner_keypair)?;
                     ops.push(signed_op);
                     children = vec![hash].into_iter().collect();
@@ -1008,7 +1676,7 @@ mod tests {
             let register_tag = 43_001u64;
             let owner_keypair = Keypair::new_ed25519(&mut OsRng);
             let policy = PublicPolicy {
-                owner: owner_keypair.public_key(),
+                owner: owner_keypair.public_key().into(),
                 permissions: BTreeMap::default(),
             };
 
@@ -1027,7 +1695,7 @@ mod tests {
             let mut ops = vec![];
             let mut children = BTreeSet::new();
             for (_data, delivery_chance) in dataset {
-                let (hash, op)= replica1.write(random_url()?, children)?;
+                let (hash, op)= replica1.write(random_entry()?, children)?;
                 let signed_op = sign_register_op(op, &owner_keypair)?;
 
                 ops.push((signed_op, delivery_chance));
@@ -1070,7 +1738,7 @@ mod tests {
                 let index: usize = OsRng.gen_range(0, replicas.len());
                 let replica = &mut replicas[index];
 
-                let (hash, op)=replica.write(random_url()?, children)?;
+                let (hash, op)=replica.write(random_entry()?, children)?;
                 let signed_op = sign_register_op(op, &owner_keypair)?;
                 ops.push((signed_op, delivery_chance));
                 children = vec![hash].into_iter().collect();
@@ -1117,7 +1785,7 @@ mod tests {
             for _data in dataset {
                 if let Some(replica) = replicas.choose_mut(&mut OsRng)
                 {
-                    let (hash, op)=replica.write(random_url()?, children)?;
+                    let (hash, op)=replica.write(random_entry()?, children)?;
                     let signed_op = sign_register_op(op, &owner_keypair)?;
                     ops.push(signed_op);
                     children = vec![hash].into_iter().collect();
@@ -1133,7 +1801,7 @@ mod tests {
             // add bogus ops from bogus replica + bogus data
             let mut children = BTreeSet::new();
             for _data in bogus_dataset {
-                let (hash, op)=bogus_replica.write(random_url()?, children)?;
+                let (hash, op)=bogus_replica.write(random_entry()?, children)?;
                 let bogus_op = sign_register_op(op, &random_owner_keypair)?;
                 bogus_replica.apply_op(bogus_op.clone())?;
                 ops.push(bogus_op);
@@ -1165,7 +1833,7 @@ mod tests {
         }
     }
 
-    fn random_url() -> Result<Url> {
+    fn random_entry() -> Result<Entry> {
         use crate::url::*;
         let url = Url::encode_bytes(
             BytesAddress::Public(XorName::random()),
@@ -1173,6 +1841,8 @@ mod tests {
             XorUrlBase::Base32z,
         )
         .map_err(|e| super::Error::Serialisation(e.to_string()))?;
-        Url::from_url(&url).map_err(|e| super::Error::FailedToParse(e.to_string()))
+        Url::from_url(&url)
+            .map(Entry::Url)
+            .map_err(|e| super::Error::FailedToParse(e.to_string()))
     }
 }
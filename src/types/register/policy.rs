@@ -7,7 +7,7 @@
 // specific language governing permissions and limitations relating to use of the SAFE Network
 // Software.
 
-use super::super::{Error, PublicKey, Result};
+use super::super::{utils, Error, OwnerType, PublicKey, RegisterAddress, Result, Signature};
 use super::Action;
 use serde::{Deserialize, Serialize};
 use std::{collections::BTreeMap, hash::Hash};
@@ -38,13 +38,90 @@ impl Policy {
         }
     }
 
-    /// Returns the owner.
-    pub fn owner(&self) -> &PublicKey {
+    /// Returns the public key of the owner, which is the key of the set for a multi-sig owner.
+    pub fn owner(&self) -> PublicKey {
         match self {
             Policy::Public(policy) => policy.owner(),
             Policy::Private(policy) => policy.owner(),
         }
     }
+
+    /// Returns the owner, either a single key or a set of keys.
+    pub fn owner_type(&self) -> &OwnerType {
+        match self {
+            Policy::Public(policy) => &policy.owner,
+            Policy::Private(policy) => &policy.owner,
+        }
+    }
+
+    /// Sets the owner.
+    pub fn set_owner(&mut self, owner: impl Into<OwnerType>) {
+        match self {
+            Policy::Public(policy) => policy.owner = owner.into(),
+            Policy::Private(policy) => policy.owner = owner.into(),
+        }
+    }
+
+    /// Sets the permissions of a user, replacing those it had if any.
+    ///
+    /// Returns `Err(InvalidOperation)` if the permissions are not of the same scope as the policy,
+    /// or if setting private permissions for `User::Anyone`.
+    pub fn set_permissions(&mut self, user: User, permissions: Permissions) -> Result<()> {
+        match (self, user, permissions) {
+            (Policy::Public(policy), user, Permissions::Public(permissions)) => {
+                let _ = policy.permissions.insert(user, permissions);
+                Ok(())
+            }
+            (Policy::Private(policy), User::Key(key), Permissions::Private(permissions)) => {
+                let _ = policy.permissions.insert(key, permissions);
+                Ok(())
+            }
+            _ => Err(Error::InvalidOperation),
+        }
+    }
+
+    /// Removes the permissions of a user, returning them if it had any.
+    pub fn remove_permissions(&mut self, user: User) -> Option<Permissions> {
+        match (self, user) {
+            (Policy::Public(policy), user) => {
+                policy.permissions.remove(&user).map(Permissions::Public)
+            }
+            (Policy::Private(policy), User::Key(key)) => {
+                policy.permissions.remove(&key).map(Permissions::Private)
+            }
+            (Policy::Private(_), User::Anyone) => None,
+        }
+    }
+}
+
+/// Operation replacing the policy of a Register, signed by its current owner.
+///
+/// Replicas keep the policy of the highest version, ties being broken by the ordering
+/// of the policies, so that they converge regardless of the order ops are applied in.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub struct PolicyOp {
+    /// Address of the Register.
+    pub address: RegisterAddress,
+    /// The new policy.
+    pub policy: Policy,
+    /// Version of the new policy, one above the version of the policy it replaces.
+    pub version: u64,
+    /// The PublicKey of the owner who generated the operation.
+    pub source: PublicKey,
+    /// The signature of source on the address, policy and version, required to apply the op.
+    pub signature: Option<Signature>,
+}
+
+impl PolicyOp {
+    /// Returns the bytes the source signs.
+    pub fn bytes_to_sign(&self) -> Result<Vec<u8>> {
+        utils::serialise(&(&self.address, &self.policy, self.version)).map_err(|err| {
+            Error::Serialisation(format!(
+                "Could not serialise policy operation to sign: {}",
+                err
+            ))
+        })
+    }
 }
 
 impl From<PrivatePolicy> for Policy {
@@ -154,9 +231,9 @@ pub enum User {
 /// Public permissions.
 #[derive(Clone, Serialize, Deserialize, PartialEq, PartialOrd, Ord, Eq, Hash, Debug)]
 pub struct PublicPolicy {
-    /// An owner could represent an individual user, or a group of users,
-    /// depending on the `public_key` type.
-    pub owner: PublicKey,
+    /// An owner could represent an individual user, or a group of users
+    /// holding shares of the keys of a `PublicKeySet`.
+    pub owner: OwnerType,
     /// Map of users to their public permission set.
     pub permissions: BTreeMap<User, PublicPermissions>,
 }
@@ -174,7 +251,7 @@ impl PublicPolicy {
     /// this action is not permitted.
     pub fn is_action_allowed(&self, requester: PublicKey, action: Action) -> Result<()> {
         // First checks if the requester is the owner.
-        if action == Action::Read || requester == self.owner {
+        if action == Action::Read || requester == self.owner() {
             Ok(())
         } else {
             match self
@@ -193,18 +270,18 @@ impl PublicPolicy {
         self.permissions.get(&user).map(|p| Permissions::Public(*p))
     }
 
-    /// Returns the owner.
-    pub fn owner(&self) -> &PublicKey {
-        &self.owner
+    /// Returns the public key of the owner.
+    pub fn owner(&self) -> PublicKey {
+        self.owner.public_key()
     }
 }
 
 /// Private permissions.
 #[derive(Clone, Serialize, Deserialize, PartialEq, PartialOrd, Ord, Eq, Hash, Debug)]
 pub struct PrivatePolicy {
-    /// An owner could represent an individual user, or a group of users,
-    /// depending on the `public_key` type.
-    pub owner: PublicKey,
+    /// An owner could represent an individual user, or a group of users
+    /// holding shares of the keys of a `PublicKeySet`.
+    pub owner: OwnerType,
     /// Map of users to their private permission set.
     pub permissions: BTreeMap<PublicKey, PrivatePermissions>,
 }
@@ -214,7 +291,7 @@ impl PrivatePolicy {
     /// this action is not permitted.
     pub fn is_action_allowed(&self, requester: PublicKey, action: Action) -> Result<()> {
         // First checks if the requester is the owner.
-        if requester == self.owner {
+        if requester == self.owner() {
             Ok(())
         } else {
             match self.permissions.get(&requester) {
@@ -238,8 +315,8 @@ impl PrivatePolicy {
         }
     }
 
-    /// Returns the owner.
-    pub fn owner(&self) -> &PublicKey {
-        &self.owner
+    /// Returns the public key of the owner.
+    pub fn owner(&self) -> PublicKey {
+        self.owner.public_key()
     }
 }
@@ -10,19 +10,28 @@
 use super::super::{
     RegisterAddress as Address, Signature, {utils, Error, PublicKey, Result},
 };
-use super::metadata::Entry;
+use super::metadata::{Entry, MAX_REG_ENTRY_SIZE};
 pub use crdts::merkle_reg::Hash as EntryHash;
 use crdts::{
     merkle_reg::{MerkleReg, Node},
-    CmRDT,
+    CmRDT, CvRDT,
 };
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::BTreeSet,
+    collections::{BTreeMap, BTreeSet},
     fmt::{self, Debug, Display},
     hash::Hash,
+    iter,
 };
 
+/// Maximum number of entries returned by a single read of the history or of the ancestors
+/// of an entry of a Register.
+pub const MAX_REG_HISTORY_PAGE_LEN: usize = 1024;
+
+/// Maximum number of levels of the entry DAG a single read of the ancestors of an entry
+/// goes back through.
+pub const MAX_REG_ANCESTORS_DEPTH: usize = 256;
+
 /// CRDT Data operation applicable to other Register replica.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct CrdtOperation<T> {
@@ -67,11 +76,21 @@ impl RegisterCrdt {
         }
     }
 
+    /// Constructs a 'RegisterCrdt' holding the given entries.
+    pub(super) fn from_data(address: Address, data: MerkleReg<Entry>) -> Self {
+        Self { address, data }
+    }
+
     /// Returns the address.
     pub(super) fn address(&self) -> &Address {
         &self.address
     }
 
+    /// Returns the entries.
+    pub(super) fn data(&self) -> &MerkleReg<Entry> {
+        &self.data
+    }
+
     /// Returns total number of items in the register.
     pub(super) fn size(&self) -> u64 {
         (self.data.num_nodes() + self.data.num_orphans()) as u64
@@ -86,6 +105,7 @@ impl RegisterCrdt {
         source: PublicKey,
     ) -> Result<(EntryHash, CrdtOperation<Entry>)> {
         let address = *self.address();
+        check_entry_size(&entry)?;
 
         let crdt_op = self.data.write(entry, children);
         self.data.apply(crdt_op.clone());
@@ -113,12 +133,27 @@ impl RegisterCrdt {
                 err
             ))
         })?;
-        op.source.verify(&sig, &bytes_to_verify)?;
+        let (source, crdt_op) = (op.source, &op.crdt_op);
+        source
+            .verify(&sig, &bytes_to_verify)
+            .or_else(|error| match &crdt_op.value {
+                // Url entries may have been signed as they were encoded before there were
+                // other kinds of entries.
+                Entry::Url(url) => {
+                    let legacy_bytes = utils::serialise(&Node {
+                        children: crdt_op.children.clone(),
+                        value: url,
+                    })?;
+                    source.verify(&sig, &legacy_bytes)
+                }
+                Entry::Bytes(_) => Err(error),
+            })?;
 
         // Check the targetting address is correct
         if self.address != op.address {
             return Err(Error::CrdtWrongAddress(op.address));
         }
+        check_entry_size(&op.crdt_op.value)?;
 
         // Apply the CRDT operation to the Register
         self.data.apply(op.crdt_op);
@@ -126,6 +161,11 @@ impl RegisterCrdt {
         Ok(())
     }
 
+    /// Merge the entries of another replica of the RegisterCrdt into this one.
+    pub(super) fn merge(&mut self, other: RegisterCrdt) {
+        self.data.merge(other.data);
+    }
+
     /// Get the entry corresponding to the provided `hash` if it exists.
     pub(super) fn get(&self, hash: EntryHash) -> Option<&Entry> {
         self.data.node(hash).map(|node| &node.value)
@@ -139,4 +179,142 @@ impl RegisterCrdt {
             .map(|(hash, node)| (hash, node.value.clone()))
             .collect()
     }
+
+    /// Get the entries written on top of the entry with the provided `hash`,
+    /// i.e. those which have it as a child.
+    pub(super) fn parents(&self, hash: EntryHash) -> Result<BTreeSet<(EntryHash, Entry)>> {
+        let _ = self.data.node(hash).ok_or(Error::NoSuchEntry)?;
+
+        Ok(self
+            .data
+            .parents(hash)
+            .hashes_and_nodes()
+            .map(|(hash, node)| (hash, node.value.clone()))
+            .collect())
+    }
+
+    /// Get the entries the entry with the provided `hash` was written on top of.
+    pub(super) fn children(&self, hash: EntryHash) -> Result<BTreeSet<(EntryHash, Entry)>> {
+        let _ = self.data.node(hash).ok_or(Error::NoSuchEntry)?;
+
+        Ok(self
+            .data
+            .children(hash)
+            .hashes_and_nodes()
+            .map(|(hash, node)| (hash, node.value.clone()))
+            .collect())
+    }
+
+    /// Get all entries in topological order, each entry coming after all its children,
+    /// with the current entries last. Entries which are not ordered relative to each
+    /// other are sorted by hash, so that all replicas return the same history.
+    pub(super) fn history(&self) -> Vec<(EntryHash, Entry)> {
+        // Every entry is reachable from the current entries through its parents' children.
+        let sorted = self.sort_from(self.data.read().hashes());
+        self.entries(sorted)
+    }
+
+    /// Get at most [`MAX_REG_HISTORY_PAGE_LEN`] entries of the history, starting at `start`.
+    pub(super) fn history_page(&self, start: usize) -> Vec<(EntryHash, Entry)> {
+        let sorted = self.sort_from(self.data.read().hashes());
+        self.entries(
+            sorted
+                .into_iter()
+                .skip(start)
+                .take(MAX_REG_HISTORY_PAGE_LEN),
+        )
+    }
+
+    /// Get the entry with the provided `hash` and all the entries it was written on top of,
+    /// i.e. the Register as of that entry, in the same order as the full history.
+    /// The entry comes last.
+    pub(super) fn history_at(&self, hash: EntryHash) -> Result<Vec<(EntryHash, Entry)>> {
+        let _ = self.data.node(hash).ok_or(Error::NoSuchEntry)?;
+
+        let sorted = self.sort_from(iter::once(hash));
+        Ok(self.entries(sorted))
+    }
+
+    // Sorts topologically the given entries and all the entries reachable through their children.
+    fn sort_from(&self, heads: impl IntoIterator<Item = EntryHash>) -> Vec<EntryHash> {
+        let mut pending_children = BTreeMap::new();
+        let mut to_visit: Vec<_> = heads.into_iter().collect();
+        while let Some(hash) = to_visit.pop() {
+            if pending_children.contains_key(&hash) {
+                continue;
+            }
+            let children = self.data.children(hash).hashes();
+            to_visit.extend(children.iter().copied());
+            let _ = pending_children.insert(hash, children);
+        }
+
+        let mut ready: BTreeSet<_> = pending_children
+            .iter()
+            .filter(|(_, children)| children.is_empty())
+            .map(|(hash, _)| *hash)
+            .collect();
+        let mut sorted = Vec::with_capacity(pending_children.len());
+        while let Some(hash) = ready.iter().next().copied() {
+            let _ = ready.remove(&hash);
+            sorted.push(hash);
+            for parent in self.data.parents(hash).hashes() {
+                if let Some(children) = pending_children.get_mut(&parent) {
+                    let _ = children.remove(&hash);
+                    if children.is_empty() {
+                        let _ = ready.insert(parent);
+                    }
+                }
+            }
+        }
+
+        sorted
+    }
+
+    fn entries(&self, hashes: impl IntoIterator<Item = EntryHash>) -> Vec<(EntryHash, Entry)> {
+        hashes
+            .into_iter()
+            .filter_map(|hash| self.data.node(hash).map(|node| (hash, node.value.clone())))
+            .collect()
+    }
+
+    /// Get the entries preceding the entry with the provided `hash`, following its
+    /// children down to `depth` levels. Entries are returned breadth first, the nearest first.
+    /// At most [`MAX_REG_ANCESTORS_DEPTH`] levels and [`MAX_REG_HISTORY_PAGE_LEN`] entries
+    /// are returned, the farthest ones being left out.
+    pub(super) fn ancestors(
+        &self,
+        hash: EntryHash,
+        depth: usize,
+    ) -> Result<Vec<(EntryHash, Entry)>> {
+        let _ = self.data.node(hash).ok_or(Error::NoSuchEntry)?;
+
+        let mut seen = BTreeSet::new();
+        let mut ancestors = vec![];
+        let mut level: BTreeSet<_> = iter::once(hash).collect();
+        for _ in 0..depth.min(MAX_REG_ANCESTORS_DEPTH) {
+            level = level
+                .into_iter()
+                .flat_map(|hash| self.data.children(hash).hashes())
+                .filter(|hash| seen.insert(*hash))
+                .collect();
+            if level.is_empty() {
+                break;
+            }
+            let room = MAX_REG_HISTORY_PAGE_LEN - ancestors.len();
+            ancestors.extend(self.entries(level.iter().copied().take(room)));
+            if ancestors.len() == MAX_REG_HISTORY_PAGE_LEN {
+                break;
+            }
+        }
+
+        Ok(ancestors)
+    }
+}
+
+// Byte entries are limited in size, a Url is instead bounded by its format.
+fn check_entry_size(entry: &Entry) -> Result<()> {
+    match entry {
+        Entry::Bytes(bytes) if bytes.len() > MAX_REG_ENTRY_SIZE => Err(Error::ExceededSize),
+        _ => Ok(()),
+    }
 }
@@ -0,0 +1,94 @@
+// Copyright 2021 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under the MIT license <LICENSE-MIT
+// https://opensource.org/licenses/MIT> or the Modified BSD license <LICENSE-BSD
+// https://opensource.org/licenses/BSD-3-Clause>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms. Please review the Licences for the
+// specific language governing permissions and limitations relating to use of the SAFE Network
+// Software.
+
+use super::{Error, Keypair, PublicKey, Result, SafeKeyAddress, Signature, XorName};
+use crate::url::Scope;
+use serde::{Deserialize, Serialize};
+
+/// SafeKey, a public key stored at the address derived from it,
+/// signed by its secret key to prove it was stored by its owner.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SafeKey {
+    address: SafeKeyAddress,
+    public_key: PublicKey,
+    signature: Signature,
+}
+
+impl SafeKey {
+    /// Creates a new instance of `SafeKey` for the public key of the keypair.
+    pub fn new(keypair: &Keypair, scope: Scope) -> Result<Self> {
+        let public_key = keypair.public_key();
+        let address = SafeKeyAddress::new(XorName::from(public_key), scope);
+        let signature = keypair.sign(&Self::bytes_to_sign(&address)?);
+
+        Ok(Self {
+            address,
+            public_key,
+            signature,
+        })
+    }
+
+    /// Returns the address.
+    pub fn address(&self) -> &SafeKeyAddress {
+        &self.address
+    }
+
+    /// Returns the name.
+    pub fn name(&self) -> &XorName {
+        self.address.name()
+    }
+
+    /// Returns the public key.
+    pub fn public_key(&self) -> PublicKey {
+        self.public_key
+    }
+
+    /// Verifies that the SafeKey is stored at the address of its public key,
+    /// and was signed by its secret key.
+    pub fn verify(&self) -> bool {
+        *self.name() == XorName::from(self.public_key)
+            && Self::bytes_to_sign(&self.address)
+                .map(|bytes| self.public_key.verify(&self.signature, &bytes).is_ok())
+                .unwrap_or(false)
+    }
+
+    fn bytes_to_sign(address: &SafeKeyAddress) -> Result<Vec<u8>> {
+        bincode::serialize(address).map_err(|err| {
+            Error::Serialisation(format!("Could not serialise SafeKey address: {}", err))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SafeKey;
+    use crate::types::Keypair;
+    use crate::url::Scope;
+    use eyre::Result;
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn safe_key_is_addressed_by_its_public_key() -> Result<()> {
+        let keypair = Keypair::new_ed25519(&mut OsRng);
+        let safe_key = SafeKey::new(&keypair, Scope::Private)?;
+        assert!(safe_key.verify());
+        assert!(safe_key.address().is_private());
+        assert_eq!(safe_key.public_key(), keypair.public_key());
+
+        // a SafeKey moved to the address of another key is rejected
+        let other = SafeKey::new(&Keypair::new_ed25519(&mut OsRng), Scope::Private)?;
+        let forged = SafeKey {
+            address: *other.address(),
+            ..safe_key
+        };
+        assert!(!forged.verify());
+
+        Ok(())
+    }
+}
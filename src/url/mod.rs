@@ -29,6 +29,9 @@ use xorurl_media_types::{MEDIA_TYPE_CODES, MEDIA_TYPE_STR};
 /// Type tag to use for the NrsMapContainer stored on Register
 pub const NRS_MAP_TYPE_TAG: u64 = 1_500;
 
+/// Type tag to use for the FilesContainer stored on Register
+pub const FILES_CONTAINER_TYPE_TAG: u64 = 1_100;
+
 /// Default base encoding used for XOR URLs
 pub const DEFAULT_XORURL_BASE: XorUrlBase = XorUrlBase::Base32z;
 
@@ -228,7 +231,7 @@ impl UrlType {
 ///   public_name()   --> hnyynyzhjjjatqkfkjux8maaojtj8r59aphcnue6a11qgecpcebidkywmybnc
 ///   top_name() --> hnyynyzhjjjatqkfkjux8maaojtj8r59aphcnue6a11qgecpcebidkywmybnc
 ///   sub_names()   --> None
-#[derive(Debug, Clone, Hash, Serialize, Deserialize, PartialEq, Ord, PartialOrd)]
+#[derive(Debug, Clone, Hash, Serialize, Deserialize, PartialEq, Eq, Ord, PartialOrd)]
 pub struct Url {
     encoding_version: u64,      // currently only v1 supported
     public_name: String,        // "a.b.name" in "a.b.name"